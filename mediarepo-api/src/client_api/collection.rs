@@ -0,0 +1,132 @@
+use super::IPCApi;
+use crate::client_api::error::ApiResult;
+use crate::types::collections::{
+    AddCollectionRequest, CollectionResponse, InsertCollectionFilesRequest,
+    MoveCollectionFileRequest, RemoveCollectionFilesRequest, UpdateCollectionRequest,
+};
+use crate::types::files::FileBasicDataResponse;
+use crate::types::identifier::FileIdentifier;
+use bromine::prelude::*;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct CollectionApi {
+    ctx: PooledContext,
+}
+
+impl IPCApi for CollectionApi {
+    fn namespace() -> &'static str {
+        "collections"
+    }
+
+    fn ctx(&self) -> PoolGuard<Context> {
+        self.ctx.acquire()
+    }
+}
+
+impl CollectionApi {
+    pub fn new(ctx: PooledContext) -> Self {
+        Self { ctx }
+    }
+
+    /// Returns all collections
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all_collections(&self) -> ApiResult<Vec<CollectionResponse>> {
+        self.emit_and_get("all_collections", (), Some(Duration::from_secs(1)))
+            .await
+    }
+
+    /// Creates a new empty collection
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add_collection(
+        &self,
+        request: AddCollectionRequest,
+    ) -> ApiResult<CollectionResponse> {
+        self.emit_and_get("add_collection", request, Some(Duration::from_secs(1)))
+            .await
+    }
+
+    /// Updates the name, description or cover file of a collection
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn update_collection(
+        &self,
+        request: UpdateCollectionRequest,
+    ) -> ApiResult<CollectionResponse> {
+        self.emit_and_get("update_collection", request, Some(Duration::from_secs(1)))
+            .await
+    }
+
+    /// Deletes a collection by id without deleting its files
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete_collection(&self, id: i64) -> ApiResult<()> {
+        self.emit("delete_collection", id).await_reply().await?;
+
+        Ok(())
+    }
+
+    /// Returns the files of a collection in their order
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_collection_files(&self, id: i64) -> ApiResult<Vec<FileBasicDataResponse>> {
+        self.emit_and_get("collection_files", id, Some(Duration::from_secs(1)))
+            .await
+    }
+
+    /// Inserts files into a collection at a position or at the end
+    /// and returns the files of the collection
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn insert_collection_files(
+        &self,
+        collection_id: i64,
+        files: Vec<FileIdentifier>,
+        position: Option<u32>,
+    ) -> ApiResult<Vec<FileBasicDataResponse>> {
+        self.emit_and_get(
+            "insert_collection_files",
+            InsertCollectionFilesRequest {
+                collection_id,
+                files,
+                position,
+            },
+            Some(Duration::from_secs(1)),
+        )
+        .await
+    }
+
+    /// Moves a file of a collection to a new position and returns the files of the collection
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn move_collection_file(
+        &self,
+        collection_id: i64,
+        file: FileIdentifier,
+        position: u32,
+    ) -> ApiResult<Vec<FileBasicDataResponse>> {
+        self.emit_and_get(
+            "move_collection_file",
+            MoveCollectionFileRequest {
+                collection_id,
+                file,
+                position,
+            },
+            Some(Duration::from_secs(1)),
+        )
+        .await
+    }
+
+    /// Removes files from a collection and returns the remaining files of the collection
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn remove_collection_files(
+        &self,
+        collection_id: i64,
+        files: Vec<FileIdentifier>,
+    ) -> ApiResult<Vec<FileBasicDataResponse>> {
+        self.emit_and_get(
+            "remove_collection_files",
+            RemoveCollectionFilesRequest {
+                collection_id,
+                files,
+            },
+            Some(Duration::from_secs(1)),
+        )
+        .await
+    }
+}
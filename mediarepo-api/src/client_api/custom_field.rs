@@ -0,0 +1,89 @@
+use super::IPCApi;
+use crate::client_api::error::ApiResult;
+use crate::types::custom_fields::{
+    AddCustomFieldRequest, CustomFieldResponse, CustomFieldType, CustomFieldValueResponse,
+    CustomFieldValueUpdate, SetCustomFieldValuesRequest,
+};
+use crate::types::identifier::FileIdentifier;
+use bromine::prelude::*;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct CustomFieldApi {
+    ctx: PooledContext,
+}
+
+impl IPCApi for CustomFieldApi {
+    fn namespace() -> &'static str {
+        "custom_fields"
+    }
+
+    fn ctx(&self) -> PoolGuard<Context> {
+        self.ctx.acquire()
+    }
+}
+
+impl CustomFieldApi {
+    pub fn new(ctx: PooledContext) -> Self {
+        Self { ctx }
+    }
+
+    /// Returns all custom fields defined in the repository
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all_custom_fields(&self) -> ApiResult<Vec<CustomFieldResponse>> {
+        self.emit_and_get("all_custom_fields", (), Some(Duration::from_secs(1)))
+            .await
+    }
+
+    /// Defines a new custom field
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add_custom_field(
+        &self,
+        name: String,
+        field_type: CustomFieldType,
+    ) -> ApiResult<CustomFieldResponse> {
+        self.emit_and_get(
+            "add_custom_field",
+            AddCustomFieldRequest { name, field_type },
+            Some(Duration::from_secs(1)),
+        )
+        .await
+    }
+
+    /// Deletes a custom field with all of its values
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete_custom_field(&self, id: i64) -> ApiResult<()> {
+        self.emit("delete_custom_field", id).await_reply().await?;
+
+        Ok(())
+    }
+
+    /// Returns the custom field values of a file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_custom_field_values(
+        &self,
+        file_id: FileIdentifier,
+    ) -> ApiResult<Vec<CustomFieldValueResponse>> {
+        self.emit_and_get(
+            "custom_field_values_for_file",
+            file_id,
+            Some(Duration::from_secs(1)),
+        )
+        .await
+    }
+
+    /// Sets or removes custom field values of a file and returns all values of the file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn set_custom_field_values(
+        &self,
+        file_id: FileIdentifier,
+        values: Vec<CustomFieldValueUpdate>,
+    ) -> ApiResult<Vec<CustomFieldValueResponse>> {
+        self.emit_and_get(
+            "set_custom_field_values",
+            SetCustomFieldValuesRequest { file_id, values },
+            Some(Duration::from_secs(1)),
+        )
+        .await
+    }
+}
@@ -0,0 +1,51 @@
+use super::IPCApi;
+use crate::client_api::error::ApiResult;
+use crate::types::duplicates::{
+    DuplicateCandidateResponse, DuplicateResolution, ResolveDuplicateRequest,
+};
+use bromine::prelude::*;
+
+#[derive(Clone)]
+pub struct DuplicateApi {
+    ctx: PooledContext,
+}
+
+impl IPCApi for DuplicateApi {
+    fn namespace() -> &'static str {
+        "duplicates"
+    }
+
+    fn ctx(&self) -> PoolGuard<Context> {
+        self.ctx.acquire()
+    }
+}
+
+impl DuplicateApi {
+    pub fn new(ctx: PooledContext) -> Self {
+        Self { ctx }
+    }
+
+    /// Returns all pairs of similar files that haven't been reviewed yet
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_duplicate_candidates(&self) -> ApiResult<Vec<DuplicateCandidateResponse>> {
+        self.emit_and_get("get_duplicate_candidates", (), None)
+            .await
+    }
+
+    /// Resolves a pair of similar files
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn resolve_duplicate(
+        &self,
+        id: i64,
+        resolution: DuplicateResolution,
+    ) -> ApiResult<()> {
+        self.emit(
+            "resolve_duplicate",
+            ResolveDuplicateRequest { id, resolution },
+        )
+        .await_reply()
+        .await?;
+
+        Ok(())
+    }
+}
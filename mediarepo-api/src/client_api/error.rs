@@ -9,6 +9,15 @@ pub enum ApiError {
 
     #[error("The servers api version (version {server:?}) is incompatible with the api client {client:?}")]
     VersionMismatch { server: String, client: String },
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("The upload {upload_id} was interrupted and can be resumed: {source}")]
+    UploadInterrupted {
+        upload_id: String,
+        source: Box<ApiError>,
+    },
 }
 
 unsafe impl Send for ApiError {}
@@ -1,18 +1,29 @@
+use crate::client_api::error::ApiError;
 use crate::client_api::error::ApiResult;
 use crate::client_api::IPCApi;
+use crate::types::facets::{SearchFacetsRequest, SearchFacetsResponse};
 use crate::types::files::{
-    AddFileRequestHeader, FileBasicDataResponse, FileMetadataResponse, FileOSMetadata, FileStatus,
-    GetFileThumbnailOfSizeRequest, GetFileThumbnailsRequest, ReadFileRequest,
-    ThumbnailMetadataResponse, UpdateFileNameRequest, UpdateFileStatusRequest,
+    AddFileByReferenceRequest, AddFileRequestHeader, CommitUploadRequest, FileBasicDataResponse,
+    FileImportMode, FileMetadataResponse, FileOSMetadata, FileRangeResponse, FileRatingResponse,
+    FileStatus, FileUploadStatusResponse, FindFilesPageResponse, GetFileThumbnailOfSizeRequest,
+    GetFileThumbnailsRequest, ReadFileRangeRequest, ReadFileRequest, ThumbnailMetadataResponse,
+    UpdateFileMetadataRequest, UpdateFileNameRequest, UpdateFileRatingRequest,
+    UpdateFileStatusRequest, UploadChunkRequestHeader,
 };
-use crate::types::filtering::{FilterExpression, FindFilesRequest, SortKey};
+use crate::types::filtering::{FilterExpression, FindFilesPageRequest, FindFilesRequest, SortKey};
 use crate::types::identifier::FileIdentifier;
 use async_trait::async_trait;
 use bromine::context::{PoolGuard, PooledContext};
 use bromine::payload::BytePayload;
 use bromine::prelude::*;
+use std::io::{Cursor, SeekFrom};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 use tokio::time::Duration;
 
+/// Files larger than this size are transferred in chunks
+pub const CHUNKED_UPLOAD_THRESHOLD: usize = 32 * 1024 * 1024;
+const UPLOAD_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
 pub struct FileApi {
     ctx: PooledContext,
 }
@@ -89,6 +100,52 @@ impl FileApi {
         .await
     }
 
+    /// Searches for files and returns the requested page of the results
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn find_files_page(
+        &self,
+        filters: Vec<FilterExpression>,
+        sort_expression: Vec<SortKey>,
+        offset: u64,
+        limit: u64,
+    ) -> ApiResult<FindFilesPageResponse> {
+        self.emit_and_get(
+            "find_files_page",
+            FindFilesPageRequest {
+                query: FindFilesRequest {
+                    filters,
+                    sort_expression,
+                },
+                offset,
+                limit,
+            },
+            Some(Duration::from_secs(20)),
+        )
+        .await
+    }
+
+    /// Returns the most used tags, namespaces, mime types, sizes and import dates
+    /// of the files matching the filters
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn search_facets(
+        &self,
+        filters: Vec<FilterExpression>,
+        tag_limit: u64,
+    ) -> ApiResult<SearchFacetsResponse> {
+        self.emit_and_get(
+            "search_facets",
+            SearchFacetsRequest {
+                query: FindFilesRequest {
+                    filters,
+                    sort_expression: vec![],
+                },
+                tag_limit,
+            },
+            Some(Duration::from_secs(20)),
+        )
+        .await
+    }
+
     /// Reads the file and returns its contents as bytes
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn read_file(&self, id: FileIdentifier) -> ApiResult<Vec<u8>> {
@@ -103,16 +160,48 @@ impl FileApi {
         Ok(payload.into_inner())
     }
 
-    /// Adds a file with predefined tags
+    /// Reads up to `length` bytes of the file starting at `offset`.
+    /// The returned range metadata contains the total size of the file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn read_file_range(
+        &self,
+        id: FileIdentifier,
+        offset: u64,
+        length: u64,
+    ) -> ApiResult<(FileRangeResponse, Vec<u8>)> {
+        let payload: TandemPayload<SerdePayload<FileRangeResponse>, BytePayload> = self
+            .emit_and_get(
+                "read_file_range",
+                ReadFileRangeRequest { id, offset, length },
+                Some(Duration::from_secs(30)),
+            )
+            .await?;
+        let (range, bytes) = payload.into_inner();
+
+        Ok((range.data(), bytes.into_inner()))
+    }
+
+    /// Adds a file with predefined tags and source urls.
+    /// Large files are automatically transferred in chunks
     #[tracing::instrument(level = "debug", skip(self, bytes))]
     pub async fn add_file(
         &self,
         metadata: FileOSMetadata,
         tags: Vec<String>,
+        sources: Vec<String>,
         bytes: Vec<u8>,
     ) -> ApiResult<FileBasicDataResponse> {
+        if bytes.len() > CHUNKED_UPLOAD_THRESHOLD {
+            return self
+                .upload_file(metadata, tags, sources, Cursor::new(bytes))
+                .await;
+        }
         let payload = TandemPayload::new(
-            AddFileRequestHeader { metadata, tags },
+            AddFileRequestHeader {
+                metadata,
+                tags,
+                sources,
+            },
             BytePayload::new(bytes),
         );
 
@@ -120,6 +209,175 @@ impl FileApi {
             .await
     }
 
+    /// Adds a file by streaming its contents to the daemon in chunks.
+    /// If the transfer fails an [ApiError::UploadInterrupted] with the id of the upload
+    /// is returned that can be used to continue the upload with [FileApi::resume_upload]
+    #[tracing::instrument(level = "debug", skip(self, reader))]
+    pub async fn upload_file<R: AsyncRead + Unpin + Send>(
+        &self,
+        metadata: FileOSMetadata,
+        tags: Vec<String>,
+        sources: Vec<String>,
+        reader: R,
+    ) -> ApiResult<FileBasicDataResponse> {
+        let status = self.begin_upload().await?;
+        let upload_id = status.upload_id;
+
+        if let Err(e) = self.upload_chunks(&upload_id, status.offset, reader).await {
+            return Err(ApiError::UploadInterrupted {
+                upload_id,
+                source: Box::new(e),
+            });
+        }
+
+        self.commit_upload(upload_id, metadata, tags, sources).await
+    }
+
+    /// Continues an interrupted upload. The reader has to provide the complete
+    /// file and is moved to the position of the data that hasn't been received yet
+    #[tracing::instrument(level = "debug", skip(self, reader))]
+    pub async fn resume_upload<R: AsyncRead + AsyncSeek + Unpin + Send>(
+        &self,
+        upload_id: String,
+        metadata: FileOSMetadata,
+        tags: Vec<String>,
+        sources: Vec<String>,
+        mut reader: R,
+    ) -> ApiResult<FileBasicDataResponse> {
+        let status = self.get_upload_status(upload_id).await?;
+        reader.seek(SeekFrom::Start(status.offset)).await?;
+
+        if let Err(e) = self
+            .upload_chunks(&status.upload_id, status.offset, reader)
+            .await
+        {
+            return Err(ApiError::UploadInterrupted {
+                upload_id: status.upload_id,
+                source: Box::new(e),
+            });
+        }
+
+        self.commit_upload(status.upload_id, metadata, tags, sources)
+            .await
+    }
+
+    /// Starts a new chunked upload
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn begin_upload(&self) -> ApiResult<FileUploadStatusResponse> {
+        self.emit_and_get("begin_upload", (), Some(Duration::from_secs(2)))
+            .await
+    }
+
+    /// Returns the number of bytes the daemon has received for an upload
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_upload_status(
+        &self,
+        upload_id: String,
+    ) -> ApiResult<FileUploadStatusResponse> {
+        self.emit_and_get("upload_status", upload_id, Some(Duration::from_secs(60)))
+            .await
+    }
+
+    /// Sends a single chunk of an upload and returns the new status of the upload
+    #[tracing::instrument(level = "debug", skip(self, bytes))]
+    pub async fn upload_chunk(
+        &self,
+        upload_id: String,
+        offset: u64,
+        bytes: Vec<u8>,
+    ) -> ApiResult<FileUploadStatusResponse> {
+        let payload = TandemPayload::new(
+            UploadChunkRequestHeader { upload_id, offset },
+            BytePayload::new(bytes),
+        );
+
+        self.emit_and_get("upload_chunk", payload, Some(Duration::from_secs(30)))
+            .await
+    }
+
+    /// Adds the file of a completely transferred upload with predefined tags and source urls
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn commit_upload(
+        &self,
+        upload_id: String,
+        metadata: FileOSMetadata,
+        tags: Vec<String>,
+        sources: Vec<String>,
+    ) -> ApiResult<FileBasicDataResponse> {
+        self.emit_and_get(
+            "commit_upload",
+            CommitUploadRequest {
+                upload_id,
+                header: AddFileRequestHeader {
+                    metadata,
+                    tags,
+                    sources,
+                },
+            },
+            Some(Duration::from_secs(30)),
+        )
+        .await
+    }
+
+    /// Adds a file the daemon can read from its own file system without transferring
+    /// its contents. The path of the file is taken from the metadata
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add_file_by_reference(
+        &self,
+        metadata: FileOSMetadata,
+        tags: Vec<String>,
+        sources: Vec<String>,
+        mode: FileImportMode,
+    ) -> ApiResult<FileBasicDataResponse> {
+        self.emit_and_get(
+            "add_file_by_reference",
+            AddFileByReferenceRequest {
+                mode,
+                header: AddFileRequestHeader {
+                    metadata,
+                    tags,
+                    sources,
+                },
+            },
+            Some(Duration::from_secs(30)),
+        )
+        .await
+    }
+
+    /// Discards an upload and the data that has been transferred for it
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn abort_upload(&self, upload_id: String) -> ApiResult<()> {
+        self.emit("abort_upload", upload_id).await_reply().await?;
+
+        Ok(())
+    }
+
+    /// Reads the remaining data from the reader and sends it to the daemon in chunks
+    async fn upload_chunks<R: AsyncRead + Unpin + Send>(
+        &self,
+        upload_id: &str,
+        mut offset: u64,
+        reader: R,
+    ) -> ApiResult<()> {
+        let mut reader = reader.take(u64::MAX);
+
+        loop {
+            let mut chunk = Vec::with_capacity(UPLOAD_CHUNK_SIZE);
+            reader.set_limit(UPLOAD_CHUNK_SIZE as u64);
+            reader.read_to_end(&mut chunk).await?;
+
+            if chunk.is_empty() {
+                break;
+            }
+            offset = self
+                .upload_chunk(upload_id.to_owned(), offset, chunk)
+                .await?
+                .offset;
+        }
+
+        Ok(())
+    }
+
     /// Updates a files name
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn update_file_name(
@@ -135,6 +393,51 @@ impl FileApi {
         .await
     }
 
+    /// Updates the name, comment and timestamps of a file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn update_file_metadata(
+        &self,
+        request: UpdateFileMetadataRequest,
+    ) -> ApiResult<FileMetadataResponse> {
+        self.emit_and_get(
+            "update_file_metadata",
+            request,
+            Some(Duration::from_secs(1)),
+        )
+        .await
+    }
+
+    /// Updates the metadata of multiple files at once
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn update_files_metadata(
+        &self,
+        requests: Vec<UpdateFileMetadataRequest>,
+    ) -> ApiResult<Vec<FileMetadataResponse>> {
+        self.emit_and_get(
+            "update_files_metadata",
+            requests,
+            Some(Duration::from_secs(10)),
+        )
+        .await
+    }
+
+    /// Returns the rating and favorite flag of a file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_file_rating(&self, file_id: FileIdentifier) -> ApiResult<FileRatingResponse> {
+        self.emit_and_get("get_file_rating", file_id, Some(Duration::from_secs(1)))
+            .await
+    }
+
+    /// Updates the rating and favorite flag of a file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn update_file_rating(
+        &self,
+        request: UpdateFileRatingRequest,
+    ) -> ApiResult<FileRatingResponse> {
+        self.emit_and_get("update_file_rating", request, Some(Duration::from_secs(1)))
+            .await
+    }
+
     /// Updates the status of a file
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn update_file_status(
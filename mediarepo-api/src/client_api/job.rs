@@ -1,6 +1,9 @@
 use crate::client_api::error::ApiResult;
 use crate::client_api::IPCApi;
-use crate::types::jobs::{JobType, RunJobRequest};
+use crate::types::files::FileBasicDataResponse;
+use crate::types::jobs::{
+    GarbageCollectionResponse, IntegrityFindingResponse, JobType, RunJobRequest,
+};
 use bromine::context::{Context, PoolGuard, PooledContext};
 use std::time::Duration;
 
@@ -40,4 +43,39 @@ impl JobApi {
     pub async fn is_job_running(&self, job_type: JobType) -> ApiResult<bool> {
         self.emit_and_get("is_job_running", job_type, None).await
     }
+
+    /// Returns the findings of the last file verification
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_integrity_findings(&self) -> ApiResult<Vec<IntegrityFindingResponse>> {
+        self.emit_and_get("get_integrity_findings", (), None).await
+    }
+
+    /// Quarantines the files affected by the given findings and returns them
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn quarantine_integrity_findings(
+        &self,
+        ids: Vec<i64>,
+    ) -> ApiResult<Vec<FileBasicDataResponse>> {
+        self.emit_and_get("quarantine_integrity_findings", ids, None)
+            .await
+    }
+
+    /// Removes the given findings
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn dismiss_integrity_findings(&self, ids: Vec<i64>) -> ApiResult<()> {
+        self.emit("dismiss_integrity_findings", ids)
+            .await_reply()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the orphans found by the last garbage collection
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_garbage_collection_report(
+        &self,
+    ) -> ApiResult<Option<GarbageCollectionResponse>> {
+        self.emit_and_get("get_garbage_collection_report", (), None)
+            .await
+    }
 }
@@ -1,16 +1,28 @@
+pub mod collection;
+pub mod custom_field;
+pub mod duplicate;
 pub mod error;
 pub mod file;
 pub mod job;
 pub mod preset;
 pub mod protocol;
+pub mod relation;
 pub mod repo;
+pub mod search;
+pub mod source;
 pub mod tag;
 
+use crate::client_api::collection::CollectionApi;
+use crate::client_api::custom_field::CustomFieldApi;
+use crate::client_api::duplicate::DuplicateApi;
 use crate::client_api::error::{ApiError, ApiResult};
 use crate::client_api::file::FileApi;
 use crate::client_api::job::JobApi;
 use crate::client_api::preset::PresetApi;
+use crate::client_api::relation::RelationApi;
 use crate::client_api::repo::RepoApi;
+use crate::client_api::search::SearchApi;
+use crate::client_api::source::SourceApi;
 use crate::client_api::tag::TagApi;
 use crate::types::misc::{check_apis_compatible, get_api_version, InfoResponse};
 use async_trait::async_trait;
@@ -51,6 +63,12 @@ pub struct ApiClient {
     pub repo: RepoApi,
     pub job: JobApi,
     pub preset: PresetApi,
+    pub duplicate: DuplicateApi,
+    pub search: SearchApi,
+    pub source: SourceApi,
+    pub custom_field: CustomFieldApi,
+    pub relation: RelationApi,
+    pub collection: CollectionApi,
 }
 
 impl Clone for ApiClient {
@@ -62,6 +80,12 @@ impl Clone for ApiClient {
             repo: self.repo.clone(),
             job: self.job.clone(),
             preset: self.preset.clone(),
+            duplicate: self.duplicate.clone(),
+            search: self.search.clone(),
+            source: self.source.clone(),
+            custom_field: self.custom_field.clone(),
+            relation: self.relation.clone(),
+            collection: self.collection.clone(),
         }
     }
 }
@@ -75,6 +99,12 @@ impl ApiClient {
             repo: RepoApi::new(ctx.clone()),
             job: JobApi::new(ctx.clone()),
             preset: PresetApi::new(ctx.clone()),
+            duplicate: DuplicateApi::new(ctx.clone()),
+            search: SearchApi::new(ctx.clone()),
+            source: SourceApi::new(ctx.clone()),
+            custom_field: CustomFieldApi::new(ctx.clone()),
+            relation: RelationApi::new(ctx.clone()),
+            collection: CollectionApi::new(ctx.clone()),
             ctx,
         }
     }
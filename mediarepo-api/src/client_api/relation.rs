@@ -0,0 +1,78 @@
+use super::IPCApi;
+use crate::client_api::error::ApiResult;
+use crate::types::identifier::FileIdentifier;
+use crate::types::relations::{ChangeFileRelationRequest, FileRelationResponse, FileRelationType};
+use bromine::prelude::*;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct RelationApi {
+    ctx: PooledContext,
+}
+
+impl IPCApi for RelationApi {
+    fn namespace() -> &'static str {
+        "relations"
+    }
+
+    fn ctx(&self) -> PoolGuard<Context> {
+        self.ctx.acquire()
+    }
+}
+
+impl RelationApi {
+    pub fn new(ctx: PooledContext) -> Self {
+        Self { ctx }
+    }
+
+    /// Returns all relations the file is part of
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_relations_for_file(
+        &self,
+        file_id: FileIdentifier,
+    ) -> ApiResult<Vec<FileRelationResponse>> {
+        self.emit_and_get("relations_for_file", file_id, Some(Duration::from_secs(1)))
+            .await
+    }
+
+    /// Adds a relation that reads as "the file is the `relation_type` of the related file"
+    /// and returns all relations of the file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add_file_relation(
+        &self,
+        file_id: FileIdentifier,
+        related_file_id: FileIdentifier,
+        relation_type: FileRelationType,
+    ) -> ApiResult<Vec<FileRelationResponse>> {
+        self.emit_and_get(
+            "add_file_relation",
+            ChangeFileRelationRequest {
+                file_id,
+                related_file_id,
+                relation_type,
+            },
+            Some(Duration::from_secs(1)),
+        )
+        .await
+    }
+
+    /// Removes a relation and returns the remaining relations of the file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn remove_file_relation(
+        &self,
+        file_id: FileIdentifier,
+        related_file_id: FileIdentifier,
+        relation_type: FileRelationType,
+    ) -> ApiResult<Vec<FileRelationResponse>> {
+        self.emit_and_get(
+            "remove_file_relation",
+            ChangeFileRelationRequest {
+                file_id,
+                related_file_id,
+                relation_type,
+            },
+            Some(Duration::from_secs(1)),
+        )
+        .await
+    }
+}
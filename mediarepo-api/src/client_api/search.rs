@@ -0,0 +1,78 @@
+use super::IPCApi;
+use crate::client_api::error::ApiResult;
+use crate::types::files::FindFilesPageResponse;
+use crate::types::searches::{
+    AddSavedSearchRequest, RunSavedSearchRequest, SavedSearch, UpdateSavedSearchRequest,
+};
+use bromine::prelude::*;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct SearchApi {
+    ctx: PooledContext,
+}
+
+impl IPCApi for SearchApi {
+    fn namespace() -> &'static str {
+        "searches"
+    }
+
+    fn ctx(&self) -> PoolGuard<Context> {
+        self.ctx.acquire()
+    }
+}
+
+impl SearchApi {
+    pub fn new(ctx: PooledContext) -> Self {
+        Self { ctx }
+    }
+
+    /// Returns all saved searches of the repository
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all_saved_searches(&self) -> ApiResult<Vec<SavedSearch>> {
+        self.emit_and_get("all_saved_searches", (), Some(Duration::from_secs(1)))
+            .await
+    }
+
+    /// Saves a search under the given name
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add_saved_search(&self, request: AddSavedSearchRequest) -> ApiResult<SavedSearch> {
+        self.emit_and_get("add_saved_search", request, Some(Duration::from_secs(1)))
+            .await
+    }
+
+    /// Updates the name, query or sorting preset of a saved search
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn update_saved_search(
+        &self,
+        request: UpdateSavedSearchRequest,
+    ) -> ApiResult<SavedSearch> {
+        self.emit_and_get("update_saved_search", request, Some(Duration::from_secs(1)))
+            .await
+    }
+
+    /// Deletes a saved search by id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete_saved_search(&self, id: i64) -> ApiResult<()> {
+        self.emit("delete_saved_search", id).await_reply().await?;
+
+        Ok(())
+    }
+
+    /// Returns the requested page of the files matching a saved search
+    /// sorted by its sorting preset
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn run_saved_search(
+        &self,
+        id: i64,
+        offset: u64,
+        limit: u64,
+    ) -> ApiResult<FindFilesPageResponse> {
+        self.emit_and_get(
+            "run_saved_search",
+            RunSavedSearchRequest { id, offset, limit },
+            Some(Duration::from_secs(20)),
+        )
+        .await
+    }
+}
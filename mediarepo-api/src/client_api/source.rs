@@ -0,0 +1,67 @@
+use super::IPCApi;
+use crate::client_api::error::ApiResult;
+use crate::types::identifier::FileIdentifier;
+use crate::types::sources::{ChangeFileSourcesRequest, SourceResponse};
+use bromine::prelude::*;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct SourceApi {
+    ctx: PooledContext,
+}
+
+impl IPCApi for SourceApi {
+    fn namespace() -> &'static str {
+        "sources"
+    }
+
+    fn ctx(&self) -> PoolGuard<Context> {
+        self.ctx.acquire()
+    }
+}
+
+impl SourceApi {
+    pub fn new(ctx: PooledContext) -> Self {
+        Self { ctx }
+    }
+
+    /// Returns the source urls of a file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_sources_for_file(
+        &self,
+        file_id: FileIdentifier,
+    ) -> ApiResult<Vec<SourceResponse>> {
+        self.emit_and_get("sources_for_file", file_id, Some(Duration::from_secs(1)))
+            .await
+    }
+
+    /// Adds source urls to a file and returns all sources of the file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add_sources_to_file(
+        &self,
+        file_id: FileIdentifier,
+        urls: Vec<String>,
+    ) -> ApiResult<Vec<SourceResponse>> {
+        self.emit_and_get(
+            "add_sources_to_file",
+            ChangeFileSourcesRequest { file_id, urls },
+            Some(Duration::from_secs(1)),
+        )
+        .await
+    }
+
+    /// Removes source urls from a file and returns the remaining sources of the file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn remove_sources_from_file(
+        &self,
+        file_id: FileIdentifier,
+        urls: Vec<String>,
+    ) -> ApiResult<Vec<SourceResponse>> {
+        self.emit_and_get(
+            "remove_sources_from_file",
+            ChangeFileSourcesRequest { file_id, urls },
+            Some(Duration::from_secs(1)),
+        )
+        .await
+    }
+}
@@ -0,0 +1,128 @@
+use crate::tauri_plugin::commands::ApiAccess;
+use crate::tauri_plugin::error::PluginResult;
+use crate::types::collections::{
+    AddCollectionRequest, CollectionResponse, UpdateCollectionRequest,
+};
+use crate::types::files::FileBasicDataResponse;
+use crate::types::identifier::FileIdentifier;
+
+#[tauri::command]
+pub async fn all_collections(api_state: ApiAccess<'_>) -> PluginResult<Vec<CollectionResponse>> {
+    let api = api_state.api().await?;
+    let collections = api.collection.all_collections().await?;
+
+    Ok(collections)
+}
+
+#[tauri::command]
+pub async fn add_collection(
+    api_state: ApiAccess<'_>,
+    name: String,
+    description: Option<String>,
+    cover_file_id: Option<i64>,
+) -> PluginResult<CollectionResponse> {
+    let api = api_state.api().await?;
+    let collection = api
+        .collection
+        .add_collection(AddCollectionRequest {
+            name,
+            description,
+            cover_file_id,
+        })
+        .await?;
+
+    Ok(collection)
+}
+
+/// The description and cover file are always replaced as a missing value can't be told apart from null
+#[tauri::command]
+pub async fn update_collection(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    name: Option<String>,
+    description: Option<String>,
+    cover_file_id: Option<i64>,
+) -> PluginResult<CollectionResponse> {
+    let api = api_state.api().await?;
+    let collection = api
+        .collection
+        .update_collection(UpdateCollectionRequest {
+            id,
+            name,
+            description: Some(description),
+            cover_file_id: Some(cover_file_id),
+        })
+        .await?;
+
+    Ok(collection)
+}
+
+#[tauri::command]
+pub async fn delete_collection(api_state: ApiAccess<'_>, id: i64) -> PluginResult<()> {
+    let api = api_state.api().await?;
+    api.collection.delete_collection(id).await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_collection_files(
+    api_state: ApiAccess<'_>,
+    id: i64,
+) -> PluginResult<Vec<FileBasicDataResponse>> {
+    let api = api_state.api().await?;
+    let files = api.collection.get_collection_files(id).await?;
+
+    Ok(files)
+}
+
+#[tauri::command]
+pub async fn insert_collection_files(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    file_ids: Vec<i64>,
+    position: Option<u32>,
+) -> PluginResult<Vec<FileBasicDataResponse>> {
+    let api = api_state.api().await?;
+    let files = api
+        .collection
+        .insert_collection_files(
+            id,
+            file_ids.into_iter().map(FileIdentifier::ID).collect(),
+            position,
+        )
+        .await?;
+
+    Ok(files)
+}
+
+#[tauri::command]
+pub async fn move_collection_file(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    file_id: i64,
+    position: u32,
+) -> PluginResult<Vec<FileBasicDataResponse>> {
+    let api = api_state.api().await?;
+    let files = api
+        .collection
+        .move_collection_file(id, FileIdentifier::ID(file_id), position)
+        .await?;
+
+    Ok(files)
+}
+
+#[tauri::command]
+pub async fn remove_collection_files(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    file_ids: Vec<i64>,
+) -> PluginResult<Vec<FileBasicDataResponse>> {
+    let api = api_state.api().await?;
+    let files = api
+        .collection
+        .remove_collection_files(id, file_ids.into_iter().map(FileIdentifier::ID).collect())
+        .await?;
+
+    Ok(files)
+}
@@ -0,0 +1,65 @@
+use crate::tauri_plugin::commands::ApiAccess;
+use crate::tauri_plugin::error::PluginResult;
+use crate::types::custom_fields::{
+    CustomFieldResponse, CustomFieldType, CustomFieldValueResponse, CustomFieldValueUpdate,
+};
+use crate::types::identifier::FileIdentifier;
+
+#[tauri::command]
+pub async fn get_all_custom_fields(
+    api_state: ApiAccess<'_>,
+) -> PluginResult<Vec<CustomFieldResponse>> {
+    let api = api_state.api().await?;
+    let fields = api.custom_field.all_custom_fields().await?;
+
+    Ok(fields)
+}
+
+#[tauri::command]
+pub async fn add_custom_field(
+    api_state: ApiAccess<'_>,
+    name: String,
+    field_type: CustomFieldType,
+) -> PluginResult<CustomFieldResponse> {
+    let api = api_state.api().await?;
+    let field = api.custom_field.add_custom_field(name, field_type).await?;
+
+    Ok(field)
+}
+
+#[tauri::command]
+pub async fn delete_custom_field(api_state: ApiAccess<'_>, id: i64) -> PluginResult<()> {
+    let api = api_state.api().await?;
+    api.custom_field.delete_custom_field(id).await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_custom_field_values(
+    api_state: ApiAccess<'_>,
+    id: i64,
+) -> PluginResult<Vec<CustomFieldValueResponse>> {
+    let api = api_state.api().await?;
+    let values = api
+        .custom_field
+        .get_custom_field_values(FileIdentifier::ID(id))
+        .await?;
+
+    Ok(values)
+}
+
+#[tauri::command]
+pub async fn set_custom_field_values(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    values: Vec<CustomFieldValueUpdate>,
+) -> PluginResult<Vec<CustomFieldValueResponse>> {
+    let api = api_state.api().await?;
+    let values = api
+        .custom_field
+        .set_custom_field_values(FileIdentifier::ID(id), values)
+        .await?;
+
+    Ok(values)
+}
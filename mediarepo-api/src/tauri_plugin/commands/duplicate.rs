@@ -0,0 +1,25 @@
+use crate::tauri_plugin::commands::ApiAccess;
+use crate::tauri_plugin::error::PluginResult;
+use crate::types::duplicates::{DuplicateCandidateResponse, DuplicateResolution};
+
+#[tauri::command]
+pub async fn get_duplicate_candidates(
+    api_state: ApiAccess<'_>,
+) -> PluginResult<Vec<DuplicateCandidateResponse>> {
+    let api = api_state.api().await?;
+    let candidates = api.duplicate.get_duplicate_candidates().await?;
+
+    Ok(candidates)
+}
+
+#[tauri::command]
+pub async fn resolve_duplicate(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    resolution: DuplicateResolution,
+) -> PluginResult<()> {
+    let api = api_state.api().await?;
+    api.duplicate.resolve_duplicate(id, resolution).await?;
+
+    Ok(())
+}
@@ -1,12 +1,16 @@
+use crate::client_api::file::CHUNKED_UPLOAD_THRESHOLD;
 use crate::tauri_plugin::commands::{ApiAccess, BufferAccess};
 use crate::tauri_plugin::error::PluginResult;
 use crate::tauri_plugin::utils::system_time_to_naive_date_time;
+use crate::types::facets::SearchFacetsResponse;
 use crate::types::files::{
-    FileBasicDataResponse, FileMetadataResponse, FileOSMetadata, FileStatus,
-    ThumbnailMetadataResponse,
+    FileBasicDataResponse, FileImportMode, FileMetadataResponse, FileOSMetadata,
+    FileRatingResponse, FileStatus, FindFilesPageResponse, ThumbnailMetadataResponse,
+    UpdateFileMetadataRequest, UpdateFileRatingRequest,
 };
 use crate::types::filtering::{FilterExpression, SortKey};
 use crate::types::identifier::FileIdentifier;
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::time::SystemTime;
@@ -17,6 +21,33 @@ use tokio::fs::DirEntry;
 pub struct AddFileOptions {
     pub read_tags_from_txt: bool,
     pub delete_after_import: bool,
+    #[serde(default)]
+    pub import_mode: Option<FileImportMode>,
+    #[serde(default)]
+    pub sources: Vec<String>,
+}
+
+/// Changes the fields of a file's metadata that are set.
+/// An empty comment removes the existing one
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileMetadataUpdate {
+    pub id: i64,
+    pub name: Option<String>,
+    pub comment: Option<String>,
+    pub creation_time: Option<NaiveDateTime>,
+    pub change_time: Option<NaiveDateTime>,
+}
+
+impl From<FileMetadataUpdate> for UpdateFileMetadataRequest {
+    fn from(update: FileMetadataUpdate) -> Self {
+        Self {
+            file_id: FileIdentifier::ID(update.id),
+            name: update.name,
+            comment: update.comment.map(|c| Some(c).filter(|c| !c.is_empty())),
+            creation_time: update.creation_time,
+            change_time: update.change_time,
+        }
+    }
 }
 
 #[tauri::command]
@@ -62,8 +93,23 @@ pub async fn add_local_file(
         }
     }
 
-    let file_content = fs::read(&path).await?;
-    let file = api.file.add_file(metadata, tags, file_content).await?;
+    let sources = options.sources;
+
+    let file = if let Some(mode) = options.import_mode {
+        api.file
+            .add_file_by_reference(metadata, tags, sources, mode)
+            .await?
+    } else if fs::metadata(&path).await?.len() > CHUNKED_UPLOAD_THRESHOLD as u64 {
+        let reader = fs::File::open(&path).await?;
+        api.file
+            .upload_file(metadata, tags, sources, reader)
+            .await?
+    } else {
+        let file_content = fs::read(&path).await?;
+        api.file
+            .add_file(metadata, tags, sources, file_content)
+            .await?
+    };
     if options.delete_after_import {
         fs::remove_file(path).await?;
 
@@ -87,6 +133,35 @@ pub async fn find_files(
     Ok(files)
 }
 
+#[tauri::command]
+pub async fn find_files_page(
+    filters: Vec<FilterExpression>,
+    sort_by: Vec<SortKey>,
+    offset: u64,
+    limit: u64,
+    api_state: ApiAccess<'_>,
+) -> PluginResult<FindFilesPageResponse> {
+    let api = api_state.api().await?;
+    let page = api
+        .file
+        .find_files_page(filters, sort_by, offset, limit)
+        .await?;
+
+    Ok(page)
+}
+
+#[tauri::command]
+pub async fn search_facets(
+    filters: Vec<FilterExpression>,
+    tag_limit: u64,
+    api_state: ApiAccess<'_>,
+) -> PluginResult<SearchFacetsResponse> {
+    let api = api_state.api().await?;
+    let facets = api.file.search_facets(filters, tag_limit).await?;
+
+    Ok(facets)
+}
+
 #[tauri::command]
 pub async fn get_file_thumbnails(
     api_state: ApiAccess<'_>,
@@ -124,6 +199,98 @@ pub async fn update_file_name(
     Ok(metadata)
 }
 
+#[tauri::command]
+pub async fn update_file_metadata(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    name: Option<String>,
+    comment: Option<String>,
+    creation_time: Option<NaiveDateTime>,
+    change_time: Option<NaiveDateTime>,
+) -> PluginResult<FileMetadataResponse> {
+    let api = api_state.api().await?;
+    let metadata = api
+        .file
+        .update_file_metadata(
+            FileMetadataUpdate {
+                id,
+                name,
+                comment,
+                creation_time,
+                change_time,
+            }
+            .into(),
+        )
+        .await?;
+
+    Ok(metadata)
+}
+
+#[tauri::command]
+pub async fn update_files_metadata(
+    api_state: ApiAccess<'_>,
+    updates: Vec<FileMetadataUpdate>,
+) -> PluginResult<Vec<FileMetadataResponse>> {
+    let api = api_state.api().await?;
+    let requests = updates
+        .into_iter()
+        .map(UpdateFileMetadataRequest::from)
+        .collect();
+    let metadata = api.file.update_files_metadata(requests).await?;
+
+    Ok(metadata)
+}
+
+#[tauri::command]
+pub async fn get_file_rating(
+    api_state: ApiAccess<'_>,
+    id: i64,
+) -> PluginResult<FileRatingResponse> {
+    let api = api_state.api().await?;
+    let rating = api.file.get_file_rating(FileIdentifier::ID(id)).await?;
+
+    Ok(rating)
+}
+
+/// Sets the rating of a file or removes it if no rating is given
+#[tauri::command]
+pub async fn set_file_rating(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    rating: Option<u32>,
+) -> PluginResult<FileRatingResponse> {
+    let api = api_state.api().await?;
+    let rating = api
+        .file
+        .update_file_rating(UpdateFileRatingRequest {
+            file_id: FileIdentifier::ID(id),
+            rating: Some(rating),
+            favorite: None,
+        })
+        .await?;
+
+    Ok(rating)
+}
+
+#[tauri::command]
+pub async fn set_file_favorite(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    favorite: bool,
+) -> PluginResult<FileRatingResponse> {
+    let api = api_state.api().await?;
+    let rating = api
+        .file
+        .update_file_rating(UpdateFileRatingRequest {
+            file_id: FileIdentifier::ID(id),
+            rating: None,
+            favorite: Some(favorite),
+        })
+        .await?;
+
+    Ok(rating)
+}
+
 #[tauri::command]
 pub async fn update_file_status(
     api_state: ApiAccess<'_>,
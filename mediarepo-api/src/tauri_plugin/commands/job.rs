@@ -1,6 +1,7 @@
 use crate::tauri_plugin::commands::ApiAccess;
 use crate::tauri_plugin::error::PluginResult;
-use crate::types::jobs::JobType;
+use crate::types::files::FileBasicDataResponse;
+use crate::types::jobs::{GarbageCollectionResponse, IntegrityFindingResponse, JobType};
 
 #[tauri::command]
 pub async fn run_job(api_state: ApiAccess<'_>, job_type: JobType, sync: bool) -> PluginResult<()> {
@@ -17,3 +18,45 @@ pub async fn is_job_running(api_state: ApiAccess<'_>, job_type: JobType) -> Plug
 
     Ok(running)
 }
+
+#[tauri::command]
+pub async fn get_integrity_findings(
+    api_state: ApiAccess<'_>,
+) -> PluginResult<Vec<IntegrityFindingResponse>> {
+    let api = api_state.api().await?;
+    let findings = api.job.get_integrity_findings().await?;
+
+    Ok(findings)
+}
+
+#[tauri::command]
+pub async fn quarantine_integrity_findings(
+    api_state: ApiAccess<'_>,
+    ids: Vec<i64>,
+) -> PluginResult<Vec<FileBasicDataResponse>> {
+    let api = api_state.api().await?;
+    let files = api.job.quarantine_integrity_findings(ids).await?;
+
+    Ok(files)
+}
+
+#[tauri::command]
+pub async fn dismiss_integrity_findings(
+    api_state: ApiAccess<'_>,
+    ids: Vec<i64>,
+) -> PluginResult<()> {
+    let api = api_state.api().await?;
+    api.job.dismiss_integrity_findings(ids).await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_garbage_collection_report(
+    api_state: ApiAccess<'_>,
+) -> PluginResult<Option<GarbageCollectionResponse>> {
+    let api = api_state.api().await?;
+    let report = api.job.get_garbage_collection_report().await?;
+
+    Ok(report)
+}
@@ -1,18 +1,30 @@
 use tauri::State;
 
+pub use collection::*;
+pub use custom_field::*;
 pub use daemon::*;
+pub use duplicate::*;
 pub use file::*;
 pub use job::*;
+pub use relation::*;
 pub use repo::*;
+pub use search::*;
+pub use source::*;
 pub use tag::*;
 pub use preset::*;
 
 use crate::tauri_plugin::state::{ApiState, AppState, BufferState};
 
+pub mod collection;
+pub mod custom_field;
 pub mod daemon;
+pub mod duplicate;
 pub mod file;
 pub mod job;
+pub mod relation;
 pub mod repo;
+pub mod search;
+pub mod source;
 pub mod tag;
 pub mod preset;
 
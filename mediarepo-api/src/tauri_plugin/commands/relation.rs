@@ -0,0 +1,58 @@
+use crate::tauri_plugin::commands::ApiAccess;
+use crate::tauri_plugin::error::PluginResult;
+use crate::types::identifier::FileIdentifier;
+use crate::types::relations::{FileRelationResponse, FileRelationType};
+
+#[tauri::command]
+pub async fn get_file_relations(
+    api_state: ApiAccess<'_>,
+    id: i64,
+) -> PluginResult<Vec<FileRelationResponse>> {
+    let api = api_state.api().await?;
+    let relations = api
+        .relation
+        .get_relations_for_file(FileIdentifier::ID(id))
+        .await?;
+
+    Ok(relations)
+}
+
+#[tauri::command]
+pub async fn add_file_relation(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    related_id: i64,
+    relation_type: FileRelationType,
+) -> PluginResult<Vec<FileRelationResponse>> {
+    let api = api_state.api().await?;
+    let relations = api
+        .relation
+        .add_file_relation(
+            FileIdentifier::ID(id),
+            FileIdentifier::ID(related_id),
+            relation_type,
+        )
+        .await?;
+
+    Ok(relations)
+}
+
+#[tauri::command]
+pub async fn remove_file_relation(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    related_id: i64,
+    relation_type: FileRelationType,
+) -> PluginResult<Vec<FileRelationResponse>> {
+    let api = api_state.api().await?;
+    let relations = api
+        .relation
+        .remove_file_relation(
+            FileIdentifier::ID(id),
+            FileIdentifier::ID(related_id),
+            relation_type,
+        )
+        .await?;
+
+    Ok(relations)
+}
@@ -0,0 +1,77 @@
+use crate::tauri_plugin::commands::ApiAccess;
+use crate::tauri_plugin::error::PluginResult;
+use crate::types::files::FindFilesPageResponse;
+use crate::types::filtering::FindFilesRequest;
+use crate::types::searches::{AddSavedSearchRequest, SavedSearch, UpdateSavedSearchRequest};
+
+#[tauri::command]
+pub async fn all_saved_searches(api_state: ApiAccess<'_>) -> PluginResult<Vec<SavedSearch>> {
+    let api = api_state.api().await?;
+    let searches = api.search.all_saved_searches().await?;
+
+    Ok(searches)
+}
+
+#[tauri::command]
+pub async fn add_saved_search(
+    api_state: ApiAccess<'_>,
+    name: String,
+    query: FindFilesRequest,
+    sorting_preset_id: Option<i32>,
+) -> PluginResult<SavedSearch> {
+    let api = api_state.api().await?;
+    let search = api
+        .search
+        .add_saved_search(AddSavedSearchRequest {
+            name,
+            query,
+            sorting_preset_id,
+        })
+        .await?;
+
+    Ok(search)
+}
+
+/// The sorting preset is always replaced as a missing value can't be told apart from null
+#[tauri::command]
+pub async fn update_saved_search(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    name: Option<String>,
+    query: Option<FindFilesRequest>,
+    sorting_preset_id: Option<i32>,
+) -> PluginResult<SavedSearch> {
+    let api = api_state.api().await?;
+    let search = api
+        .search
+        .update_saved_search(UpdateSavedSearchRequest {
+            id,
+            name,
+            query,
+            sorting_preset_id: Some(sorting_preset_id),
+        })
+        .await?;
+
+    Ok(search)
+}
+
+#[tauri::command]
+pub async fn delete_saved_search(api_state: ApiAccess<'_>, id: i64) -> PluginResult<()> {
+    let api = api_state.api().await?;
+    api.search.delete_saved_search(id).await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn run_saved_search(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    offset: u64,
+    limit: u64,
+) -> PluginResult<FindFilesPageResponse> {
+    let api = api_state.api().await?;
+    let page = api.search.run_saved_search(id, offset, limit).await?;
+
+    Ok(page)
+}
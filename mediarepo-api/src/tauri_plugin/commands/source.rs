@@ -0,0 +1,48 @@
+use crate::tauri_plugin::commands::ApiAccess;
+use crate::tauri_plugin::error::PluginResult;
+use crate::types::identifier::FileIdentifier;
+use crate::types::sources::SourceResponse;
+
+#[tauri::command]
+pub async fn get_sources_for_file(
+    api_state: ApiAccess<'_>,
+    id: i64,
+) -> PluginResult<Vec<SourceResponse>> {
+    let api = api_state.api().await?;
+    let sources = api
+        .source
+        .get_sources_for_file(FileIdentifier::ID(id))
+        .await?;
+
+    Ok(sources)
+}
+
+#[tauri::command]
+pub async fn add_sources_to_file(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    urls: Vec<String>,
+) -> PluginResult<Vec<SourceResponse>> {
+    let api = api_state.api().await?;
+    let sources = api
+        .source
+        .add_sources_to_file(FileIdentifier::ID(id), urls)
+        .await?;
+
+    Ok(sources)
+}
+
+#[tauri::command]
+pub async fn remove_sources_from_file(
+    api_state: ApiAccess<'_>,
+    id: i64,
+    urls: Vec<String>,
+) -> PluginResult<Vec<SourceResponse>> {
+    let api = api_state.api().await?;
+    let sources = api
+        .source
+        .remove_sources_from_file(FileIdentifier::ID(id), urls)
+        .await?;
+
+    Ok(sources)
+}
@@ -2,6 +2,7 @@ use crate::client_api::ApiClient;
 use crate::tauri_plugin::background_tasks::TaskContext;
 use crate::tauri_plugin::error::{PluginError, PluginResult};
 use crate::tauri_plugin::state::{ApiState, BufferState};
+use crate::types::files::MAX_RANGE_LENGTH;
 use crate::types::identifier::FileIdentifier;
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -61,13 +62,36 @@ async fn content_scheme<R: Runtime>(app: &AppHandle<R>, request: &Request) -> Re
         .uri()
         .trim_start_matches("content://")
         .trim_end_matches("/");
+    let range = request
+        .headers()
+        .get("Range")
+        .and_then(|h| h.to_str().ok())
+        .and_then(parse_range_header);
 
     if let Some(buffer) = buf_state.get_entry(hash) {
         tracing::debug!("Fetching content from cache");
-        ResponseBuilder::new()
-            .status(200)
-            .mimetype(&buffer.mime)
-            .body(buffer.buf)
+        let total_size = buffer.buf.len() as u64;
+
+        match range.map(|r| resolve_range(r, total_size)) {
+            Some(Some((offset, length))) => {
+                let start = offset as usize;
+                let bytes = buffer.buf[start..start + length as usize].to_vec();
+                partial_content_response(&buffer.mime, bytes, offset, total_size)
+            }
+            Some(None) => range_not_satisfiable_response(total_size),
+            None => ResponseBuilder::new()
+                .status(200)
+                .mimetype(&buffer.mime)
+                .header("Accept-Ranges", "bytes")
+                .body(buffer.buf),
+        }
+    } else if let Some(range) = range {
+        tracing::debug!("Fetching content range from daemon");
+
+        let api_state = app.state::<ApiState>();
+        let api = api_state.api().await?;
+
+        read_content_range(&api, hash, range).await
     } else {
         tracing::debug!("Fetching content from daemon");
 
@@ -89,10 +113,123 @@ async fn content_scheme<R: Runtime>(app: &AppHandle<R>, request: &Request) -> Re
         ResponseBuilder::new()
             .status(200)
             .mimetype(&mime)
+            .header("Accept-Ranges", "bytes")
             .body(bytes)
     }
 }
 
+/// Reads a range of a files content from the daemon. The total size and mime type
+/// are taken from the range read, so only suffix ranges need an additional request
+async fn read_content_range(
+    api: &ApiClient,
+    hash: &str,
+    range: (Option<u64>, Option<u64>),
+) -> Result<Response> {
+    let id = FileIdentifier::CD(hash.to_string());
+    let (offset, length) = match range {
+        (Some(first), last) => (
+            first,
+            last.map(|last| last.saturating_sub(first).saturating_add(1))
+                .unwrap_or(MAX_RANGE_LENGTH),
+        ),
+        (None, _) => {
+            let (info, _) = api.file.read_file_range(id.clone(), 0, 0).await?;
+
+            match resolve_range(range, info.total_size) {
+                Some(resolved) => resolved,
+                None => return range_not_satisfiable_response(info.total_size),
+            }
+        }
+    };
+    let (info, mut bytes) = api
+        .file
+        .read_file_range(id, offset, length.min(MAX_RANGE_LENGTH))
+        .await?;
+    tracing::debug!("Received {} content bytes", bytes.len());
+
+    match resolve_range(range, info.total_size) {
+        Some((offset, length)) => {
+            bytes.truncate(length as usize);
+            partial_content_response(&info.mime_type, bytes, offset, info.total_size)
+        }
+        None => range_not_satisfiable_response(info.total_size),
+    }
+}
+
+/// Parses the value of a `Range` header into the first and last requested byte.
+/// Only single ranges are supported
+fn parse_range_header(header: &str) -> Option<(Option<u64>, Option<u64>)> {
+    let range = header.trim().strip_prefix("bytes=")?;
+
+    if range.contains(',') {
+        return None;
+    }
+    let (first, last) = range.split_once('-')?;
+    let first = first.trim();
+    let last = last.trim();
+    let first = if first.is_empty() {
+        None
+    } else {
+        Some(first.parse::<u64>().ok()?)
+    };
+    let last = if last.is_empty() {
+        None
+    } else {
+        Some(last.parse::<u64>().ok()?)
+    };
+
+    if first.is_none() && last.is_none() {
+        None
+    } else {
+        Some((first, last))
+    }
+}
+
+/// Resolves a requested range into the offset and length of the data to send.
+/// Returns None if the range can't be satisfied
+fn resolve_range((first, last): (Option<u64>, Option<u64>), total_size: u64) -> Option<(u64, u64)> {
+    let last_index = total_size.checked_sub(1)?;
+    let (first, last) = match (first, last) {
+        (Some(first), Some(last)) => (first, last.min(last_index)),
+        (Some(first), None) => (first, last_index),
+        (None, Some(suffix)) => (total_size.saturating_sub(suffix), last_index),
+        (None, None) => return None,
+    };
+
+    if first > last {
+        None
+    } else {
+        Some((first, (last - first + 1).min(MAX_RANGE_LENGTH)))
+    }
+}
+
+fn partial_content_response(
+    mime: &str,
+    bytes: Vec<u8>,
+    offset: u64,
+    total_size: u64,
+) -> Result<Response> {
+    let last = offset + (bytes.len() as u64).max(1) - 1;
+
+    ResponseBuilder::new()
+        .status(206)
+        .mimetype(mime)
+        .header("Accept-Ranges", "bytes")
+        .header(
+            "Content-Range",
+            format!("bytes {}-{}/{}", offset, last, total_size),
+        )
+        .body(bytes)
+}
+
+fn range_not_satisfiable_response(total_size: u64) -> Result<Response> {
+    ResponseBuilder::new()
+        .status(416)
+        .mimetype("text/plain")
+        .header("Content-Range", format!("bytes */{}", total_size))
+        .body("Range not satisfiable".as_bytes().to_vec())
+}
+
 #[tracing::instrument(level = "debug", skip_all)]
 async fn thumb_scheme<R: Runtime>(app: &AppHandle<R>, request: &Request) -> Result<Response> {
     let buf_state = app.state::<BufferState>();
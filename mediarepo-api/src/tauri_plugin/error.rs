@@ -49,6 +49,7 @@ impl From<ApiError> for PluginError {
             ApiError::VersionMismatch { server, client } => {
                 format!("The servers API version ({}) is not supported by the client ({}). Please make sure both are up to date.", server, client)
             }
+            e @ ApiError::Io(_) | e @ ApiError::UploadInterrupted { .. } => e.to_string(),
         };
         Self { message }
     }
@@ -36,6 +36,8 @@ impl<R: Runtime> MediarepoPlugin<R> {
             invoke_handler: Box::new(tauri::generate_handler![
                 get_all_files,
                 find_files,
+                find_files_page,
+                search_facets,
                 get_file_thumbnails,
                 get_repositories,
                 get_all_tags,
@@ -55,6 +57,11 @@ impl<R: Runtime> MediarepoPlugin<R> {
                 change_file_tags,
                 create_tags,
                 update_file_name,
+                update_file_metadata,
+                update_files_metadata,
+                get_file_rating,
+                set_file_rating,
+                set_file_favorite,
                 resolve_paths_to_files,
                 add_local_file,
                 save_file_locally,
@@ -76,7 +83,37 @@ impl<R: Runtime> MediarepoPlugin<R> {
                 all_sorting_presets,
                 add_sorting_preset,
                 delete_sorting_preset,
-                is_job_running
+                is_job_running,
+                get_integrity_findings,
+                quarantine_integrity_findings,
+                dismiss_integrity_findings,
+                get_garbage_collection_report,
+                get_duplicate_candidates,
+                resolve_duplicate,
+                all_saved_searches,
+                add_saved_search,
+                update_saved_search,
+                delete_saved_search,
+                run_saved_search,
+                get_sources_for_file,
+                add_sources_to_file,
+                remove_sources_from_file,
+                get_all_custom_fields,
+                add_custom_field,
+                delete_custom_field,
+                get_custom_field_values,
+                set_custom_field_values,
+                get_file_relations,
+                add_file_relation,
+                remove_file_relation,
+                all_collections,
+                add_collection,
+                update_collection,
+                delete_collection,
+                get_collection_files,
+                insert_collection_files,
+                move_collection_file,
+                remove_collection_files
             ]),
         }
     }
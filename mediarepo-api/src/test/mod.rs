@@ -1,2 +1,3 @@
+mod test_query_parsing;
 #[cfg(feature = "bromine")]
 mod test_type_serialization;
@@ -0,0 +1,326 @@
+use crate::types::custom_fields::CustomFieldValue;
+use crate::types::filtering::{
+    parse_query, parse_query_at, CustomFieldQuery, FilterExpression, FilterQuery, PropertyQuery,
+    RelationQuery, SortCustomField, SortDirection, SortKey, SortNamespace, TagQuery,
+    ValueComparator,
+};
+use crate::types::relations::FileRelationType;
+use chrono::{NaiveDate, NaiveDateTime};
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2022, 3, 15)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap()
+}
+
+fn tag(tag: &str, negate: bool) -> FilterQuery {
+    FilterQuery::Tag(TagQuery {
+        negate,
+        tag: tag.to_string(),
+    })
+}
+
+#[test]
+fn it_parses_queries() {
+    let request = parse_query_at(
+        "character:alice -rating:explicit (artist:bob OR artist:carol) .size>2MB .imported<7d",
+        now(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        request.filters,
+        vec![
+            FilterExpression::Query(tag("character:alice", false)),
+            FilterExpression::Query(tag("rating:explicit", true)),
+            FilterExpression::OrExpression(vec![
+                tag("artist:bob", false),
+                tag("artist:carol", false)
+            ]),
+            FilterExpression::Query(FilterQuery::Property(PropertyQuery::FileSize(
+                ValueComparator::Greater(2_000_000)
+            ))),
+            FilterExpression::Query(FilterQuery::Property(PropertyQuery::ImportedTime(
+                ValueComparator::Greater(
+                    NaiveDate::from_ymd_opt(2022, 3, 8)
+                        .unwrap()
+                        .and_hms_opt(12, 0, 0)
+                        .unwrap()
+                )
+            ))),
+        ]
+    );
+}
+
+#[test]
+fn it_parses_nested_expressions() {
+    let request = parse_query("(a AND b) OR (c AND NOT d) -(.tags>2 OR e)").unwrap();
+
+    assert_eq!(
+        request.filters,
+        vec![
+            FilterExpression::Or(vec![
+                FilterExpression::And(vec![
+                    FilterExpression::Query(tag("a", false)),
+                    FilterExpression::Query(tag("b", false)),
+                ]),
+                FilterExpression::And(vec![
+                    FilterExpression::Query(tag("c", false)),
+                    FilterExpression::Query(tag("d", true)),
+                ]),
+            ]),
+            FilterExpression::Not(Box::new(FilterExpression::OrExpression(vec![
+                FilterQuery::Property(PropertyQuery::TagCount(ValueComparator::Greater(2))),
+                tag("e", false),
+            ]))),
+        ]
+    );
+    assert_eq!(request.to_string(), "((a b) OR (c -d)) -(.tags>2 OR e)");
+    assert_eq!(parse_query(&request.to_string()).unwrap(), request);
+}
+
+#[test]
+fn it_formats_queries_canonically() {
+    let request = parse_query_at(
+        "series:* \"-dash\" alice_(cosplay) .FileSize = 1KiB..2MiB .tags=3 .status=archived \
+         .created>2022-01-01 .cd=\"a b\" .id=4 .name=\"\\\"sunset beach\\\" pier*\" .comment=draft \
+         .sort=-imported,namespace:page",
+        now(),
+    )
+    .unwrap();
+    let text = request.to_string();
+
+    assert_eq!(
+        text,
+        "series:* \"-dash\" \"alice_(cosplay)\" .size=1KiB..2MiB .tags=3 .status=archived \
+         .created>2022-01-01 .cd=\"a b\" .id=4 .name=\"\\\"sunset beach\\\" pier*\" .comment=draft \
+         .sort=-imported,namespace:page"
+    );
+    assert_eq!(
+        request.sort_expression,
+        vec![
+            SortKey::FileImportedTime(SortDirection::Descending),
+            SortKey::Namespace(SortNamespace {
+                name: String::from("page"),
+                direction: SortDirection::Ascending
+            })
+        ]
+    );
+    assert_eq!(parse_query(&text).unwrap(), request);
+}
+
+#[test]
+fn it_parses_presence_properties() {
+    let request = parse_query(
+        ".type=video/* -.namespace=artist .untagged=yes .source=example.com .nothumbnail=true",
+    )
+    .unwrap();
+    let property = |p| FilterExpression::Query(FilterQuery::Property(p));
+
+    assert_eq!(
+        request.filters,
+        vec![
+            property(PropertyQuery::MimeType(String::from("video/*"))),
+            FilterExpression::Not(Box::new(property(PropertyQuery::Namespace(String::from(
+                "artist"
+            ))))),
+            property(PropertyQuery::Untagged(true)),
+            property(PropertyQuery::SourceDomain(String::from("example.com"))),
+            property(PropertyQuery::MissingThumbnail(true)),
+        ]
+    );
+    assert_eq!(
+        request.to_string(),
+        ".type=video/* -.namespace=artist .untagged=true .source=example.com .missing-thumbnail=true"
+    );
+    assert_eq!(parse_query(&request.to_string()).unwrap(), request);
+}
+
+#[test]
+fn it_parses_source_urls() {
+    let request = parse_query("-.url=https://example.com/posts/*").unwrap();
+
+    assert_eq!(
+        request.filters,
+        vec![FilterExpression::Not(Box::new(FilterExpression::Query(
+            FilterQuery::Property(PropertyQuery::SourceUrl(String::from(
+                "https://example.com/posts/*"
+            )))
+        )))]
+    );
+    assert_eq!(
+        request.to_string(),
+        "-.source-url=https://example.com/posts/*"
+    );
+    assert_eq!(parse_query(&request.to_string()).unwrap(), request);
+}
+
+#[test]
+fn it_reports_errors_with_spans() {
+    let error = parse_query("a (b OR c").unwrap_err();
+    assert_eq!(error.span, 2..3);
+
+    let error = parse_query("a .weight>2").unwrap_err();
+    assert_eq!(error.span, 3..9);
+
+    let error = parse_query(".size=2XB").unwrap_err();
+    assert_eq!(error.span, 6..9);
+
+    let error = parse_query("(a OR)").unwrap_err();
+    assert_eq!(error.span, 3..5);
+}
+
+#[test]
+fn it_parses_media_properties() {
+    let request = parse_query(
+        ".width>1920 .duration=1.5min..2h .bitrate<800kbps .vcodec=H264 .sort=-duration,width",
+    )
+    .unwrap();
+    let property = |p| FilterExpression::Query(FilterQuery::Property(p));
+
+    assert_eq!(
+        request.filters,
+        vec![
+            property(PropertyQuery::Width(ValueComparator::Greater(1920))),
+            property(PropertyQuery::Duration(ValueComparator::Between((
+                90_000, 7_200_000
+            )))),
+            property(PropertyQuery::Bitrate(ValueComparator::Less(800_000))),
+            property(PropertyQuery::VideoCodec(String::from("H264"))),
+        ]
+    );
+    assert_eq!(
+        request.sort_expression,
+        vec![
+            SortKey::Duration(SortDirection::Descending),
+            SortKey::Width(SortDirection::Ascending)
+        ]
+    );
+    assert_eq!(
+        request.to_string(),
+        ".width>1920 .duration=90s..2h .bitrate<800kbps .video-codec=H264 .sort=-duration,width"
+    );
+    assert_eq!(parse_query(&request.to_string()).unwrap(), request);
+}
+
+#[test]
+fn it_parses_ratings() {
+    let request = parse_query(".stars=3..5 -.fav=yes .sort=-rating").unwrap();
+    let property = |p| FilterExpression::Query(FilterQuery::Property(p));
+
+    assert_eq!(
+        request.filters,
+        vec![
+            property(PropertyQuery::Rating(ValueComparator::Between((3, 5)))),
+            FilterExpression::Not(Box::new(property(PropertyQuery::Favorite(true)))),
+        ]
+    );
+    assert_eq!(
+        request.sort_expression,
+        vec![SortKey::Rating(SortDirection::Descending)]
+    );
+    assert_eq!(
+        request.to_string(),
+        ".rating=3..5 -.favorite=true .sort=-rating"
+    );
+    assert_eq!(parse_query(&request.to_string()).unwrap(), request);
+}
+
+#[test]
+fn it_parses_custom_fields() {
+    let request = parse_query(
+        ".field:pages=100..20 .field:Price<2.5 .field:isbn=\"123\" .custom:read=true \
+         .field:published>2020-01-01 .sort=-field:pages",
+    )
+    .unwrap();
+    let field = |name: &str, comparator| {
+        FilterExpression::Query(FilterQuery::Property(PropertyQuery::CustomField(
+            CustomFieldQuery {
+                name: name.to_string(),
+                comparator,
+            },
+        )))
+    };
+
+    assert_eq!(
+        request.filters,
+        vec![
+            field(
+                "pages",
+                ValueComparator::Between((CustomFieldValue::Int(20), CustomFieldValue::Int(100)))
+            ),
+            field("price", ValueComparator::Less(CustomFieldValue::Float(2.5))),
+            field(
+                "isbn",
+                ValueComparator::Equal(CustomFieldValue::Text(String::from("123")))
+            ),
+            field("read", ValueComparator::Equal(CustomFieldValue::Bool(true))),
+            field(
+                "published",
+                ValueComparator::Greater(CustomFieldValue::Date(
+                    NaiveDate::from_ymd_opt(2020, 1, 1)
+                        .unwrap()
+                        .and_hms_opt(0, 0, 0)
+                        .unwrap()
+                ))
+            ),
+        ]
+    );
+    assert_eq!(
+        request.sort_expression,
+        vec![SortKey::CustomField(SortCustomField {
+            name: String::from("pages"),
+            direction: SortDirection::Descending
+        })]
+    );
+    assert_eq!(
+        request.to_string(),
+        ".field:pages=20..100 .field:price<2.5 .field:isbn=\"123\" .field:read=true \
+         .field:published>2020-01-01 .sort=-field:pages"
+    );
+    assert_eq!(parse_query(&request.to_string()).unwrap(), request);
+    assert!(parse_query(".page:x=1").is_err());
+}
+
+#[test]
+fn it_parses_relations() {
+    let request = parse_query(".parent=any .derived-from=12 -.alternate=3").unwrap();
+    let relation = |relation_type, related_file_id| {
+        FilterExpression::Query(FilterQuery::Property(PropertyQuery::Relation(
+            RelationQuery {
+                relation_type,
+                related_file_id,
+            },
+        )))
+    };
+
+    assert_eq!(
+        request.filters,
+        vec![
+            relation(FileRelationType::Child, None),
+            relation(FileRelationType::DerivedFrom, Some(12)),
+            FilterExpression::Not(Box::new(relation(FileRelationType::Alternate, Some(3)))),
+        ]
+    );
+    assert_eq!(
+        request.to_string(),
+        ".child-of=any .derived-from=12 -.alternate-of=3"
+    );
+    assert_eq!(parse_query(&request.to_string()).unwrap(), request);
+    assert!(parse_query(".parent=true").is_err());
+}
+
+#[test]
+fn it_parses_collections() {
+    let request = parse_query(".in-collection=4").unwrap();
+
+    assert_eq!(
+        request.filters,
+        vec![FilterExpression::Query(FilterQuery::Property(
+            PropertyQuery::InCollection(4)
+        ))]
+    );
+    assert_eq!(request.to_string(), ".collection=4");
+    assert!(parse_query(".collection=comics").is_err());
+}
@@ -1,4 +1,4 @@
-use crate::types::files::GetFileThumbnailOfSizeRequest;
+use crate::types::files::{GetFileThumbnailOfSizeRequest, UpdateFileMetadataRequest};
 use crate::types::filtering::{
     FilterExpression, FilterQuery, SortDirection, SortKey, TagQuery, ValueComparator,
 };
@@ -24,6 +24,18 @@ fn it_serializes_get_file_thumbnail_of_size_requests() {
     .unwrap();
 }
 
+#[test]
+fn it_serializes_update_file_metadata_requests() {
+    test_serialization(UpdateFileMetadataRequest {
+        file_id: FileIdentifier::ID(0),
+        name: None,
+        comment: Some(None),
+        creation_time: Some(NaiveDateTime::MIN),
+        change_time: None,
+    })
+    .unwrap();
+}
+
 #[test]
 fn it_serializes_tag_queries() {
     test_serialization(TagQuery {
@@ -42,6 +54,23 @@ fn it_serializes_filter_expressions() {
     .unwrap();
 }
 
+#[test]
+fn it_serializes_nested_filter_expressions() {
+    test_serialization(FilterExpression::Or(vec![
+        FilterExpression::And(vec![FilterExpression::Query(FilterQuery::Tag(TagQuery {
+            tag: String::from("Hello"),
+            negate: false,
+        }))]),
+        FilterExpression::Not(Box::new(FilterExpression::Query(FilterQuery::Tag(
+            TagQuery {
+                tag: String::from("World"),
+                negate: true,
+            },
+        )))),
+    ]))
+    .unwrap();
+}
+
 #[test]
 fn it_serializes_sort_keys() {
     test_serialization(SortKey::FileName(SortDirection::Descending)).unwrap();
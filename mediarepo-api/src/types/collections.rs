@@ -0,0 +1,47 @@
+use crate::types::identifier::FileIdentifier;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CollectionResponse {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub cover_file_id: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AddCollectionRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub cover_file_id: Option<i64>,
+}
+
+/// Changes the fields of a collection that are set
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateCollectionRequest {
+    pub id: i64,
+    pub name: Option<String>,
+    pub description: Option<Option<String>>,
+    pub cover_file_id: Option<Option<i64>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InsertCollectionFilesRequest {
+    pub collection_id: i64,
+    pub files: Vec<FileIdentifier>,
+    /// The position to insert the files at or `None` to append them
+    pub position: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MoveCollectionFileRequest {
+    pub collection_id: i64,
+    pub file: FileIdentifier,
+    pub position: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RemoveCollectionFilesRequest {
+    pub collection_id: i64,
+    pub files: Vec<FileIdentifier>,
+}
@@ -0,0 +1,55 @@
+use crate::types::identifier::FileIdentifier;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CustomFieldType {
+    Int,
+    Float,
+    Text,
+    Date,
+    Bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, PartialOrd)]
+pub enum CustomFieldValue {
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Date(NaiveDateTime),
+    Bool(bool),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CustomFieldResponse {
+    pub id: i64,
+    pub name: String,
+    pub field_type: CustomFieldType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AddCustomFieldRequest {
+    /// The name of the field that may only contain letters, digits, `-` and `_`
+    pub name: String,
+    pub field_type: CustomFieldType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CustomFieldValueResponse {
+    pub field_id: i64,
+    pub name: String,
+    pub value: CustomFieldValue,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetCustomFieldValuesRequest {
+    pub file_id: FileIdentifier,
+    pub values: Vec<CustomFieldValueUpdate>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CustomFieldValueUpdate {
+    pub field_id: i64,
+    /// The new value of the field or `None` to remove it
+    pub value: Option<CustomFieldValue>,
+}
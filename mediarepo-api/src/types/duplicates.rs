@@ -0,0 +1,24 @@
+use crate::types::files::FileBasicDataResponse;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DuplicateCandidateResponse {
+    pub id: i64,
+    pub distance: u32,
+    pub file_a: FileBasicDataResponse,
+    pub file_b: FileBasicDataResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ResolveDuplicateRequest {
+    pub id: i64,
+    pub resolution: DuplicateResolution,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum DuplicateResolution {
+    /// Keeps the given file and moves the other one to the trash
+    Keep { file_id: i64, merge_tags: bool },
+    /// Marks both files as not being duplicates
+    NotDuplicates,
+}
@@ -0,0 +1,55 @@
+use crate::types::filtering::FindFilesRequest;
+use crate::types::tags::{NamespaceResponse, TagResponse};
+use serde::{Deserialize, Serialize};
+
+/// Requests the facets of the files matching a search with the `tag_limit` most used tags
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SearchFacetsRequest {
+    pub query: FindFilesRequest,
+    pub tag_limit: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SearchFacetsResponse {
+    pub total: u64,
+    pub tags: Vec<TagFacet>,
+    pub namespaces: Vec<NamespaceFacet>,
+    pub mime_types: Vec<MimeTypeFacet>,
+    pub sizes: Vec<SizeBucket>,
+    pub import_months: Vec<MonthBucket>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TagFacet {
+    pub tag: TagResponse,
+    pub count: u64,
+}
+
+/// The number of files with at least one tag of the namespace
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NamespaceFacet {
+    pub namespace: NamespaceResponse,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MimeTypeFacet {
+    pub mime_type: String,
+    pub count: u64,
+}
+
+/// The number of files with a size in `min..max` bytes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SizeBucket {
+    pub min: u64,
+    pub max: Option<u64>,
+    pub count: u64,
+}
+
+/// The number of files imported in a month
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MonthBucket {
+    pub year: i32,
+    pub month: u32,
+    pub count: u64,
+}
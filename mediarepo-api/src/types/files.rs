@@ -8,6 +8,24 @@ pub struct ReadFileRequest {
     pub id: FileIdentifier,
 }
 
+/// The maximum number of bytes returned for a single range request.
+/// Longer ranges are shortened to this length
+pub const MAX_RANGE_LENGTH: u64 = 4 * 1024 * 1024;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReadFileRangeRequest {
+    pub id: FileIdentifier,
+    pub offset: u64,
+    pub length: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileRangeResponse {
+    pub offset: u64,
+    pub total_size: u64,
+    pub mime_type: String,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GetFileThumbnailsRequest {
     pub id: FileIdentifier,
@@ -44,10 +62,17 @@ pub struct FileBasicDataResponse {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FindFilesPageResponse {
+    pub files: Vec<FileBasicDataResponse>,
+    pub total: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum FileStatus {
     Imported,
     Archived,
     Deleted,
+    Quarantined,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -59,6 +84,24 @@ pub struct FileMetadataResponse {
     pub change_time: NaiveDateTime,
     pub import_time: NaiveDateTime,
     pub size: u64,
+    pub media: Option<MediaMetadataResponse>,
+}
+
+/// Technical metadata extracted from image, audio and video files
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MediaMetadataResponse {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// The duration in milliseconds
+    pub duration: Option<u64>,
+    pub frame_count: Option<u64>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    /// The average bitrate in bits per second
+    pub bitrate: Option<u64>,
+    /// The exif orientation from 1 to 8
+    pub orientation: Option<u8>,
+    pub exif: Vec<(String, String)>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -84,6 +127,31 @@ pub struct UpdateFileNameRequest {
     pub name: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateFileMetadataRequest {
+    pub file_id: FileIdentifier,
+    pub name: Option<String>,
+    /// Setting the comment to `Some(None)` removes it
+    pub comment: Option<Option<String>>,
+    pub creation_time: Option<NaiveDateTime>,
+    pub change_time: Option<NaiveDateTime>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileRatingResponse {
+    pub file_id: i64,
+    pub rating: Option<u32>,
+    pub favorite: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateFileRatingRequest {
+    pub file_id: FileIdentifier,
+    /// Setting the rating to `Some(None)` removes it
+    pub rating: Option<Option<u32>>,
+    pub favorite: Option<bool>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UpdateFileStatusRequest {
     pub file_id: FileIdentifier,
@@ -94,4 +162,36 @@ pub struct UpdateFileStatusRequest {
 pub struct AddFileRequestHeader {
     pub metadata: FileOSMetadata,
     pub tags: Vec<String>,
+    pub sources: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileUploadStatusResponse {
+    pub upload_id: String,
+    pub offset: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UploadChunkRequestHeader {
+    pub upload_id: String,
+    pub offset: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommitUploadRequest {
+    pub upload_id: String,
+    pub header: AddFileRequestHeader,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AddFileByReferenceRequest {
+    pub mode: FileImportMode,
+    pub header: AddFileRequestHeader,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum FileImportMode {
+    Copy,
+    Hardlink,
+    Reflink,
 }
@@ -0,0 +1,157 @@
+use crate::types::custom_fields::CustomFieldValue;
+use crate::types::files::FileStatus;
+use crate::types::relations::FileRelationType;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+pub use query::{parse_query, parse_query_at, QueryParseError, QueryParseResult};
+
+mod query;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FindFilesRequest {
+    pub filters: Vec<FilterExpression>,
+    pub sort_expression: Vec<SortKey>,
+}
+
+/// Requests a page of the files matching a search.
+/// The response contains the total number of matching files
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FindFilesPageRequest {
+    pub query: FindFilesRequest,
+    pub offset: u64,
+    pub limit: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum FilterExpression {
+    OrExpression(Vec<FilterQuery>),
+    Query(FilterQuery),
+    And(Vec<FilterExpression>),
+    Or(Vec<FilterExpression>),
+    Not(Box<FilterExpression>),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum FilterQuery {
+    Tag(TagQuery),
+    Property(PropertyQuery),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TagQuery {
+    pub negate: bool,
+    pub tag: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum PropertyQuery {
+    Status(FileStatus),
+    FileSize(ValueComparator<u64>),
+    ImportedTime(ValueComparator<NaiveDateTime>),
+    ChangedTime(ValueComparator<NaiveDateTime>),
+    CreatedTime(ValueComparator<NaiveDateTime>),
+    TagCount(ValueComparator<u64>),
+    Cd(String),
+    Id(i64),
+    /// Full text search on the file name. Quoted parts are matched as phrases
+    /// and words ending with `*` as prefixes
+    Name(String),
+    /// Full text search on the file comment with the same syntax as [PropertyQuery::Name]
+    Comment(String),
+    /// The mime type of the file where `*` matches any text like in `video/*`
+    MimeType(String),
+    /// Files with any tag of the namespace
+    Namespace(String),
+    /// Files without any tags or only files with tags if false
+    Untagged(bool),
+    /// Files with a source on the domain or one of its subdomains
+    SourceDomain(String),
+    /// Files without thumbnails or only files with thumbnails if false
+    MissingThumbnail(bool),
+    Width(ValueComparator<u64>),
+    Height(ValueComparator<u64>),
+    /// The duration in milliseconds
+    Duration(ValueComparator<u64>),
+    /// The bitrate in bits per second
+    Bitrate(ValueComparator<u64>),
+    VideoCodec(String),
+    AudioCodec(String),
+    /// Files with a source url matching the pattern where `*` matches any text
+    SourceUrl(String),
+    Rating(ValueComparator<u64>),
+    /// Favorite files or only files that aren't favorites if false
+    Favorite(bool),
+    CustomField(CustomFieldQuery),
+    Relation(RelationQuery),
+    /// Files that are part of the collection with the given id
+    InCollection(i64),
+}
+
+/// Compares the value of a custom field. The values are converted to the
+/// type of the field and files without a value for the field never match
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CustomFieldQuery {
+    pub name: String,
+    pub comparator: ValueComparator<CustomFieldValue>,
+}
+
+/// Files that are the given type of relation of the related file
+/// or of any file if no related file is given
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RelationQuery {
+    pub relation_type: FileRelationType,
+    pub related_file_id: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum ValueComparator<T> {
+    Less(T),
+    Equal(T),
+    Greater(T),
+    Between((T, T)),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum SortKey {
+    Namespace(SortNamespace),
+    FileName(SortDirection),
+    FileSize(SortDirection),
+    FileImportedTime(SortDirection),
+    FileCreatedTime(SortDirection),
+    FileChangeTime(SortDirection),
+    FileType(SortDirection),
+    NumTags(SortDirection),
+    Width(SortDirection),
+    Height(SortDirection),
+    Duration(SortDirection),
+    Bitrate(SortDirection),
+    Rating(SortDirection),
+    CustomField(SortCustomField),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SortNamespace {
+    pub name: String,
+    pub direction: SortDirection,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SortCustomField {
+    pub name: String,
+    pub direction: SortDirection,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, PartialEq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+impl Eq for SortDirection {}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SortingPreset {
+    pub id: i32,
+    pub keys: Vec<SortKey>,
+}
\ No newline at end of file
@@ -0,0 +1,1353 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, Timelike};
+use thiserror::Error;
+
+use crate::types::custom_fields::CustomFieldValue;
+use crate::types::files::FileStatus;
+use crate::types::filtering::{
+    CustomFieldQuery, FilterExpression, FilterQuery, FindFilesRequest, PropertyQuery,
+    RelationQuery, SortCustomField, SortDirection, SortKey, SortNamespace, TagQuery,
+    ValueComparator,
+};
+use crate::types::relations::FileRelationType;
+
+/// Byte size units ordered from the largest to the smallest
+const SIZE_UNITS: [(&str, u64); 9] = [
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", 1 << 30),
+    ("GB", 1_000_000_000),
+    ("MiB", 1 << 20),
+    ("MB", 1_000_000),
+    ("KiB", 1 << 10),
+    ("KB", 1_000),
+    ("B", 1),
+];
+
+/// Duration units in milliseconds ordered from the largest to the smallest
+const DURATION_UNITS: [(&str, u64); 4] = [
+    ("h", 60 * 60 * 1000),
+    ("min", 60 * 1000),
+    ("s", 1000),
+    ("ms", 1),
+];
+
+/// Bitrate units in bits per second ordered from the largest to the smallest
+const BITRATE_UNITS: [(&str, u64); 4] = [
+    ("Gbps", 1_000_000_000),
+    ("Mbps", 1_000_000),
+    ("kbps", 1_000),
+    ("bps", 1),
+];
+
+/// Units for relative times in seconds
+const TIME_UNITS: [(&str, i64); 7] = [
+    ("s", 1),
+    ("min", 60),
+    ("h", 60 * 60),
+    ("d", 24 * 60 * 60),
+    ("w", 7 * 24 * 60 * 60),
+    ("mo", 30 * 24 * 60 * 60),
+    ("y", 365 * 24 * 60 * 60),
+];
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATE_TIME_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"];
+
+pub type QueryParseResult<T> = Result<T, QueryParseError>;
+
+/// An error in a search query with the byte range of the input it refers to
+#[derive(Clone, Debug, Error, PartialEq)]
+#[error("{message} (at {}..{})", span.start, span.end)]
+pub struct QueryParseError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+/// Parses a search query like `character:alice -rating:explicit (artist:bob OR artist:carol) .size>2MB`.
+/// Relative times like `.imported<7d` are resolved against the current local time
+pub fn parse_query(input: &str) -> QueryParseResult<FindFilesRequest> {
+    let now = Local::now().naive_local();
+    parse_query_at(input, now.with_nanosecond(0).unwrap_or(now))
+}
+
+/// Parses a search query resolving relative times against the given time
+pub fn parse_query_at(input: &str, now: NaiveDateTime) -> QueryParseResult<FindFilesRequest> {
+    QueryParser { input, pos: 0, now }.parse()
+}
+
+enum Term {
+    Filter(FilterExpression),
+    Sort(Vec<SortKey>),
+}
+
+#[derive(Clone, Copy)]
+enum Comparator {
+    Less,
+    Equal,
+    Greater,
+}
+
+struct Value {
+    text: String,
+    span: Range<usize>,
+    quoted: bool,
+}
+
+impl Value {
+    /// Returns the span of a part of the value. Quoted values always return the whole span
+    /// as escape sequences don't map to the input directly
+    fn sub_span(&self, start: usize, end: usize) -> Range<usize> {
+        if self.quoted {
+            self.span.clone()
+        } else {
+            self.span.start + start..self.span.start + end
+        }
+    }
+}
+
+/// A part of a value with its span in the input
+type ValuePart<'a> = (&'a str, Range<usize>);
+
+struct Property {
+    name: String,
+    name_span: Range<usize>,
+    comparator: Comparator,
+    comparator_span: Range<usize>,
+    value: Value,
+}
+
+struct QueryParser<'a> {
+    input: &'a str,
+    pos: usize,
+    now: NaiveDateTime,
+}
+
+impl<'a> QueryParser<'a> {
+    fn parse(mut self) -> QueryParseResult<FindFilesRequest> {
+        let mut filters = Vec::new();
+        let mut sort_expression = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => break,
+                Some(')') => return Err(error("unmatched ')'", self.pos..self.pos + 1)),
+                _ => {}
+            }
+            if !filters.is_empty() || !sort_expression.is_empty() {
+                self.consume_keyword("and")?;
+            }
+
+            match self.parse_or_chain()? {
+                Term::Filter(expression) => filters.push(expression),
+                Term::Sort(keys) => sort_expression.extend(keys),
+            }
+        }
+
+        Ok(FindFilesRequest {
+            filters,
+            sort_expression,
+        })
+    }
+
+    /// Parses a term and all terms that are joined to it with OR
+    fn parse_or_chain(&mut self) -> QueryParseResult<Term> {
+        let start = self.pos;
+        let mut expressions = match self.parse_term()? {
+            Term::Filter(expression) => vec![expression],
+            Term::Sort(_) if self.peek_keyword("or") => {
+                return Err(error(
+                    "sort keys can't be combined with OR",
+                    start..self.pos,
+                ))
+            }
+            Term::Sort(keys) => return Ok(Term::Sort(keys)),
+        };
+
+        while self.consume_keyword("or")? {
+            let start = self.pos;
+
+            match self.parse_term()? {
+                Term::Filter(expression) => expressions.push(expression),
+                Term::Sort(_) => {
+                    return Err(error(
+                        "sort keys can't be combined with OR",
+                        start..self.pos,
+                    ))
+                }
+            }
+        }
+
+        Ok(Term::Filter(join_or(expressions)))
+    }
+
+    fn parse_term(&mut self) -> QueryParseResult<Term> {
+        let start = self.pos;
+
+        match self.peek() {
+            Some('(') => self.parse_group().map(Term::Filter),
+            Some('.') => self.parse_property(),
+            Some('-') => {
+                self.advance();
+                match self.peek() {
+                    Some(c) if !c.is_whitespace() && c != ')' => self.parse_negated(start),
+                    _ => Err(error("expected a condition after '-'", start..start + 1)),
+                }
+            }
+            _ if self.peek_keyword("not") => {
+                self.parse_word();
+                let keyword_span = start..self.pos;
+                self.skip_whitespace();
+
+                match self.peek() {
+                    Some(c) if c != ')' => self.parse_negated(start),
+                    _ => Err(error("expected a condition after 'NOT'", keyword_span)),
+                }
+            }
+            _ if self.peek_keyword("or") || self.peek_keyword("and") => {
+                let end = self.word_end(self.pos);
+                Err(error(
+                    format!(
+                        "expected a condition before '{}'",
+                        self.input[start..end].to_uppercase()
+                    ),
+                    start..end,
+                ))
+            }
+            _ => {
+                let tag = self.parse_tag()?;
+                Ok(Term::Filter(FilterExpression::Query(FilterQuery::Tag(
+                    TagQuery { negate: false, tag },
+                ))))
+            }
+        }
+    }
+
+    /// Parses the term following a negation. Negated tags are expressed with the
+    /// negation flag of the tag query so that they're understood by older daemons
+    fn parse_negated(&mut self, start: usize) -> QueryParseResult<Term> {
+        match self.parse_term()? {
+            Term::Filter(FilterExpression::Query(FilterQuery::Tag(tag))) => Ok(Term::Filter(
+                FilterExpression::Query(FilterQuery::Tag(TagQuery {
+                    negate: !tag.negate,
+                    tag: tag.tag,
+                })),
+            )),
+            Term::Filter(expression) => {
+                Ok(Term::Filter(FilterExpression::Not(Box::new(expression))))
+            }
+            Term::Sort(_) => Err(error("sort keys can't be negated", start..self.pos)),
+        }
+    }
+
+    /// Parses a parenthesized list of conditions.
+    /// Conditions are combined with AND unless they're joined with OR
+    fn parse_group(&mut self) -> QueryParseResult<FilterExpression> {
+        let open = self.pos;
+        self.advance();
+        let mut expressions = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => return Err(error("unclosed '('", open..open + 1)),
+                Some(')') if expressions.is_empty() => {
+                    return Err(error("empty group", open..self.pos + 1))
+                }
+                Some(')') => {
+                    self.advance();
+                    break;
+                }
+                _ => {}
+            }
+            if !expressions.is_empty() {
+                self.consume_keyword("and")?;
+            }
+            let start = self.pos;
+
+            match self.parse_or_chain()? {
+                Term::Filter(expression) => expressions.push(expression),
+                Term::Sort(_) => {
+                    return Err(error(
+                        "sort keys can't be used inside a group",
+                        start..self.pos,
+                    ))
+                }
+            }
+        }
+
+        if expressions.len() == 1 {
+            Ok(expressions.remove(0))
+        } else {
+            Ok(FilterExpression::And(expressions))
+        }
+    }
+
+    fn parse_tag(&mut self) -> QueryParseResult<String> {
+        if self.peek() == Some('"') {
+            self.parse_quoted()
+        } else {
+            Ok(self.parse_word().to_string())
+        }
+    }
+
+    fn parse_property(&mut self) -> QueryParseResult<Term> {
+        let start = self.pos;
+        self.advance();
+        let name_start = self.pos;
+        let name = self
+            .take_while(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == ':')
+            .to_string();
+
+        if name.is_empty() {
+            return Err(error(
+                "expected a property name after '.'",
+                start..start + 1,
+            ));
+        }
+        let name_span = name_start..self.pos;
+        self.skip_whitespace();
+
+        let comparator_start = self.pos;
+        let comparator = self.take_while(|c| "<>=!".contains(c));
+        let comparator = match comparator {
+            "<" => Comparator::Less,
+            "=" | "==" => Comparator::Equal,
+            ">" => Comparator::Greater,
+            "" => {
+                return Err(error(
+                    format!("expected '<', '>' or '=' after property '{}'", name),
+                    name_span,
+                ))
+            }
+            other => {
+                return Err(error(
+                    format!("unknown comparator '{}', expected '<', '>' or '='", other),
+                    comparator_start..self.pos,
+                ))
+            }
+        };
+        let comparator_span = comparator_start..self.pos;
+        self.skip_whitespace();
+
+        let value_start = self.pos;
+        let (text, quoted) = match self.peek() {
+            Some('"') => (self.parse_quoted()?, true),
+            Some(c) if !c.is_whitespace() && c != ')' => (self.parse_word().to_string(), false),
+            _ => {
+                return Err(error(
+                    format!("expected a value for property '{}'", name),
+                    comparator_span,
+                ))
+            }
+        };
+        let property = Property {
+            name,
+            name_span,
+            comparator,
+            comparator_span,
+            value: Value {
+                text,
+                span: value_start..self.pos,
+                quoted,
+            },
+        };
+
+        self.build_property(property)
+    }
+
+    fn build_property(&self, property: Property) -> QueryParseResult<Term> {
+        let normalized = property.name.to_lowercase().replace(&['-', '_'][..], "");
+        let query = match normalized.as_str() {
+            _ if property.name.contains(':') => parse_custom_field(&property)?,
+            "status" => {
+                expect_equal(&property)?;
+                PropertyQuery::Status(parse_status(&property.value)?)
+            }
+            "size" | "filesize" => PropertyQuery::FileSize(compare_values(&property, parse_size)?),
+            "imported" | "importedat" | "importedtime" | "importeddate" => {
+                PropertyQuery::ImportedTime(self.compare_times(&property)?)
+            }
+            "changed" | "changedat" | "changedtime" | "changeddate" => {
+                PropertyQuery::ChangedTime(self.compare_times(&property)?)
+            }
+            "created" | "createdat" | "createdtime" | "createddate" => {
+                PropertyQuery::CreatedTime(self.compare_times(&property)?)
+            }
+            "tags" | "tagcount" => PropertyQuery::TagCount(compare_values(&property, parse_count)?),
+            "cd" | "contentdescriptor" => {
+                expect_equal(&property)?;
+                PropertyQuery::Cd(property.value.text)
+            }
+            "name" | "filename" => {
+                expect_equal(&property)?;
+                PropertyQuery::Name(property.value.text)
+            }
+            "comment" => {
+                expect_equal(&property)?;
+                PropertyQuery::Comment(property.value.text)
+            }
+            "type" | "mime" | "mimetype" => {
+                expect_equal(&property)?;
+                PropertyQuery::MimeType(property.value.text)
+            }
+            "namespace" => {
+                expect_equal(&property)?;
+                PropertyQuery::Namespace(property.value.text)
+            }
+            "untagged" => {
+                expect_equal(&property)?;
+                PropertyQuery::Untagged(parse_bool(&property.value)?)
+            }
+            "source" | "sourcedomain" => {
+                expect_equal(&property)?;
+                PropertyQuery::SourceDomain(property.value.text)
+            }
+            "missingthumbnail" | "nothumbnail" => {
+                expect_equal(&property)?;
+                PropertyQuery::MissingThumbnail(parse_bool(&property.value)?)
+            }
+            "width" => PropertyQuery::Width(compare_values(&property, parse_count)?),
+            "height" => PropertyQuery::Height(compare_values(&property, parse_count)?),
+            "duration" | "length" => {
+                PropertyQuery::Duration(compare_values(&property, parse_duration)?)
+            }
+            "bitrate" => PropertyQuery::Bitrate(compare_values(&property, parse_bitrate)?),
+            "rating" | "stars" => PropertyQuery::Rating(compare_values(&property, parse_count)?),
+            "favorite" | "fav" => {
+                expect_equal(&property)?;
+                PropertyQuery::Favorite(parse_bool(&property.value)?)
+            }
+            "videocodec" | "vcodec" => {
+                expect_equal(&property)?;
+                PropertyQuery::VideoCodec(property.value.text)
+            }
+            "audiocodec" | "acodec" => {
+                expect_equal(&property)?;
+                PropertyQuery::AudioCodec(property.value.text)
+            }
+            "sourceurl" | "url" => {
+                expect_equal(&property)?;
+                PropertyQuery::SourceUrl(property.value.text)
+            }
+            "parentof" | "child" => parse_relation(&property, FileRelationType::Parent)?,
+            "childof" | "parent" => parse_relation(&property, FileRelationType::Child)?,
+            "alternateof" | "alternate" => parse_relation(&property, FileRelationType::Alternate)?,
+            "derivedfrom" => parse_relation(&property, FileRelationType::DerivedFrom)?,
+            "duplicateof" => parse_relation(&property, FileRelationType::DuplicateOf)?,
+            "collection" | "incollection" => {
+                expect_equal(&property)?;
+                let id = property.value.text.parse::<i64>().map_err(|_| {
+                    error(
+                        format!("invalid collection id '{}'", property.value.text),
+                        property.value.span.clone(),
+                    )
+                })?;
+                PropertyQuery::InCollection(id)
+            }
+            "id" | "fileid" => {
+                expect_equal(&property)?;
+                let id = property.value.text.parse::<i64>().map_err(|_| {
+                    error(
+                        format!("invalid file id '{}'", property.value.text),
+                        property.value.span.clone(),
+                    )
+                })?;
+                PropertyQuery::Id(id)
+            }
+            "sort" => {
+                expect_equal(&property)?;
+                return parse_sort_keys(&property.value).map(Term::Sort);
+            }
+            _ => {
+                return Err(error(
+                    format!("unknown property '{}'", property.name),
+                    property.name_span,
+                ))
+            }
+        };
+
+        Ok(Term::Filter(FilterExpression::Query(
+            FilterQuery::Property(query),
+        )))
+    }
+
+    /// Compares a time property. Relative times describe an age so `.imported<7d`
+    /// matches files that were imported less than seven days ago
+    fn compare_times(
+        &self,
+        property: &Property,
+    ) -> QueryParseResult<ValueComparator<NaiveDateTime>> {
+        let value = &property.value;
+
+        if let Some((start, end)) = split_range(value)? {
+            let (start, _) = self.parse_time(value, start)?;
+            let (end, _) = self.parse_time(value, end)?;
+
+            return Ok(ValueComparator::Between((start.min(end), start.max(end))));
+        }
+        let (time, relative) =
+            self.parse_time(value, (&value.text, value.sub_span(0, value.text.len())))?;
+
+        match (property.comparator, relative) {
+            (Comparator::Equal, true) => Err(error(
+                "relative times can only be compared with '<' or '>'",
+                property.comparator_span.clone(),
+            )),
+            (Comparator::Less, true) => Ok(ValueComparator::Greater(time)),
+            (Comparator::Greater, true) => Ok(ValueComparator::Less(time)),
+            (comparator, false) => Ok(to_value_comparator(comparator, time)),
+        }
+    }
+
+    /// Parses an absolute date or a relative time like `7d`.
+    /// Returns the time and whether it was relative
+    fn parse_time(
+        &self,
+        value: &Value,
+        (text, span): ValuePart<'_>,
+    ) -> QueryParseResult<(NaiveDateTime, bool)> {
+        if let Some(time) = parse_date_time(text) {
+            return Ok((time, false));
+        }
+        let digits = text
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        let (amount, unit) = text.split_at(digits);
+        let seconds = TIME_UNITS
+            .iter()
+            .find(|(name, _)| *name == unit)
+            .map(|(_, seconds)| *seconds);
+
+        match (amount.parse::<i64>(), seconds) {
+            (Ok(amount), Some(seconds)) => {
+                let duration = amount
+                    .checked_mul(seconds)
+                    .filter(|seconds| *seconds <= i64::MAX / 1000)
+                    .map(Duration::seconds)
+                    .ok_or_else(|| error(format!("time '{}' is too large", text), span.clone()))?;
+                let time = self
+                    .now
+                    .checked_sub_signed(duration)
+                    .ok_or_else(|| error(format!("time '{}' is too large", text), span))?;
+
+                Ok((time, true))
+            }
+            _ => Err(error(
+                format!(
+                    "invalid time '{}', expected a date like 2022-01-31 or an age like 7d",
+                    text
+                ),
+                if value.quoted {
+                    value.span.clone()
+                } else {
+                    span
+                },
+            )),
+        }
+    }
+
+    fn parse_quoted(&mut self) -> QueryParseResult<String> {
+        let open = self.pos;
+        self.advance();
+        let mut text = String::new();
+
+        loop {
+            match self.advance() {
+                None => return Err(error("unclosed '\"'", open..self.input.len())),
+                Some('"') => return Ok(text),
+                Some('\\') => match self.advance() {
+                    Some(c) => text.push(c),
+                    None => return Err(error("unclosed '\"'", open..self.input.len())),
+                },
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    /// Reads a word until the next whitespace or unbalanced closing parenthesis
+    /// so that tags like `alice_(cosplay)` can be written without quotes
+    fn parse_word(&mut self) -> &'a str {
+        let start = self.pos;
+        self.pos = self.word_end(start);
+
+        &self.input[start..self.pos]
+    }
+
+    fn word_end(&self, start: usize) -> usize {
+        let mut depth = 0;
+
+        for (i, c) in self.input[start..].char_indices() {
+            match c {
+                c if c.is_whitespace() => return start + i,
+                '(' => depth += 1,
+                ')' if depth == 0 => return start + i,
+                ')' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        self.input.len()
+    }
+
+    /// Consumes a keyword like OR and the whitespace around it.
+    /// Returns false without consuming anything if the next word isn't the keyword
+    fn consume_keyword(&mut self, keyword: &str) -> QueryParseResult<bool> {
+        let start = self.pos;
+        self.skip_whitespace();
+
+        if !self.peek_keyword(keyword) {
+            self.pos = start;
+            return Ok(false);
+        }
+        let keyword_start = self.pos;
+        self.parse_word();
+        let keyword_span = keyword_start..self.pos;
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(c) if c != ')' && !self.peek_keyword("or") && !self.peek_keyword("and") => {
+                Ok(true)
+            }
+            _ => Err(error(
+                format!("expected a condition after '{}'", keyword.to_uppercase()),
+                keyword_span,
+            )),
+        }
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        let rest = &self.input[self.pos..];
+        let rest = &rest[rest.len() - rest.trim_start().len()..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '(' || c == ')')
+            .unwrap_or(rest.len());
+
+        rest[..end].eq_ignore_ascii_case(keyword)
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, predicate: F) -> &'a str {
+        let start = self.pos;
+        let rest = &self.input[start..];
+        let len = rest.find(|c: char| !predicate(c)).unwrap_or(rest.len());
+        self.pos += len;
+
+        &self.input[start..self.pos]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+
+        Some(c)
+    }
+}
+
+fn error<S: ToString>(message: S, span: Range<usize>) -> QueryParseError {
+    QueryParseError {
+        message: message.to_string(),
+        span,
+    }
+}
+
+/// Joins expressions with OR. Lists of plain queries are expressed
+/// as an [FilterExpression::OrExpression] so that they're understood by older daemons
+fn join_or(mut expressions: Vec<FilterExpression>) -> FilterExpression {
+    if expressions.len() == 1 {
+        return expressions.remove(0);
+    }
+    let expressions: Vec<FilterExpression> = expressions
+        .into_iter()
+        .flat_map(|expression| match expression {
+            FilterExpression::OrExpression(queries) => {
+                queries.into_iter().map(FilterExpression::Query).collect()
+            }
+            FilterExpression::Or(expressions) => expressions,
+            expression => vec![expression],
+        })
+        .collect();
+
+    if expressions
+        .iter()
+        .all(|e| matches!(e, FilterExpression::Query(_)))
+    {
+        FilterExpression::OrExpression(
+            expressions
+                .into_iter()
+                .filter_map(|e| match e {
+                    FilterExpression::Query(query) => Some(query),
+                    _ => None,
+                })
+                .collect(),
+        )
+    } else {
+        FilterExpression::Or(expressions)
+    }
+}
+
+fn to_value_comparator<T>(comparator: Comparator, value: T) -> ValueComparator<T> {
+    match comparator {
+        Comparator::Less => ValueComparator::Less(value),
+        Comparator::Equal => ValueComparator::Equal(value),
+        Comparator::Greater => ValueComparator::Greater(value),
+    }
+}
+
+fn expect_equal(property: &Property) -> QueryParseResult<()> {
+    match property.comparator {
+        Comparator::Equal => Ok(()),
+        _ => Err(error(
+            format!("property '{}' can only be compared with '='", property.name),
+            property.comparator_span.clone(),
+        )),
+    }
+}
+
+/// Parses a custom field property like `.field:pages>100`
+fn parse_custom_field(property: &Property) -> QueryParseResult<PropertyQuery> {
+    let (prefix, name) = property.name.split_once(':').unwrap_or_default();
+
+    if !matches!(prefix.to_lowercase().as_str(), "field" | "custom") || name.is_empty() {
+        return Err(error(
+            format!(
+                "unknown property '{}', expected a custom field like field:<name>",
+                property.name
+            ),
+            property.name_span.clone(),
+        ));
+    }
+
+    Ok(PropertyQuery::CustomField(CustomFieldQuery {
+        name: name.to_lowercase(),
+        comparator: compare_field_values(property)?,
+    }))
+}
+
+/// Compares a custom field with values whose types are inferred from their text.
+/// Quoted values are always compared as text
+fn compare_field_values(
+    property: &Property,
+) -> QueryParseResult<ValueComparator<CustomFieldValue>> {
+    let value = &property.value;
+
+    if value.quoted {
+        return Ok(to_value_comparator(
+            property.comparator,
+            CustomFieldValue::Text(value.text.clone()),
+        ));
+    }
+    match split_range(value)? {
+        Some(((start, _), (end, _))) => {
+            if let Comparator::Equal = property.comparator {
+                let start = parse_field_value(start);
+                let end = parse_field_value(end);
+
+                if start <= end {
+                    Ok(ValueComparator::Between((start, end)))
+                } else {
+                    Ok(ValueComparator::Between((end, start)))
+                }
+            } else {
+                Err(error(
+                    "ranges can only be compared with '='",
+                    property.comparator_span.clone(),
+                ))
+            }
+        }
+        None => Ok(to_value_comparator(
+            property.comparator,
+            parse_field_value(&value.text),
+        )),
+    }
+}
+
+/// Infers the type of an unquoted custom field value
+fn parse_field_value(text: &str) -> CustomFieldValue {
+    if let Ok(value) = text.parse::<i64>() {
+        CustomFieldValue::Int(value)
+    } else if let Some(value) = text.parse::<f64>().ok().filter(|v| v.is_finite()) {
+        CustomFieldValue::Float(value)
+    } else if let Some(time) = parse_date_time(text) {
+        CustomFieldValue::Date(time)
+    } else {
+        match text.to_lowercase().as_str() {
+            "true" => CustomFieldValue::Bool(true),
+            "false" => CustomFieldValue::Bool(false),
+            _ => CustomFieldValue::Text(text.to_string()),
+        }
+    }
+}
+
+/// Splits a range value like `1MB..2MB` into its bounds with their spans
+fn split_range(value: &Value) -> QueryParseResult<Option<(ValuePart<'_>, ValuePart<'_>)>> {
+    let index = match value.text.find("..") {
+        Some(index) => index,
+        None => return Ok(None),
+    };
+    let (start, end) = (&value.text[..index], &value.text[index + 2..]);
+
+    if start.is_empty() || end.is_empty() {
+        return Err(error(
+            "ranges need a start and an end like 1MB..2MB",
+            value.span.clone(),
+        ));
+    }
+
+    Ok(Some((
+        (start, value.sub_span(0, index)),
+        (end, value.sub_span(index + 2, value.text.len())),
+    )))
+}
+
+fn compare_values<T, F>(property: &Property, parse: F) -> QueryParseResult<ValueComparator<T>>
+where
+    T: Ord,
+    F: Fn(&str, Range<usize>) -> QueryParseResult<T>,
+{
+    let value = &property.value;
+
+    match split_range(value)? {
+        Some(((start, start_span), (end, end_span))) => {
+            if let Comparator::Equal = property.comparator {
+                let start = parse(start, start_span)?;
+                let end = parse(end, end_span)?;
+
+                if start <= end {
+                    Ok(ValueComparator::Between((start, end)))
+                } else {
+                    Ok(ValueComparator::Between((end, start)))
+                }
+            } else {
+                Err(error(
+                    "ranges can only be compared with '='",
+                    property.comparator_span.clone(),
+                ))
+            }
+        }
+        None => Ok(to_value_comparator(
+            property.comparator,
+            parse(&value.text, value.span.clone())?,
+        )),
+    }
+}
+
+fn parse_size(text: &str, span: Range<usize>) -> QueryParseResult<u64> {
+    parse_amount(text, &SIZE_UNITS, 1).ok_or_else(|| {
+        error(
+            format!("invalid size '{}', expected a size like 2MB", text),
+            span,
+        )
+    })
+}
+
+/// Parses a duration in milliseconds. Durations without a unit are in seconds
+fn parse_duration(text: &str, span: Range<usize>) -> QueryParseResult<u64> {
+    parse_amount(text, &DURATION_UNITS, 1000).ok_or_else(|| {
+        error(
+            format!(
+                "invalid duration '{}', expected a duration like 90s or 5min",
+                text
+            ),
+            span,
+        )
+    })
+}
+
+fn parse_bitrate(text: &str, span: Range<usize>) -> QueryParseResult<u64> {
+    parse_amount(text, &BITRATE_UNITS, 1).ok_or_else(|| {
+        error(
+            format!("invalid bitrate '{}', expected a bitrate like 5Mbps", text),
+            span,
+        )
+    })
+}
+
+/// Parses an amount with an optional unit like `1.5MB`.
+/// Amounts without a unit are multiplied with the default multiplier
+fn parse_amount(text: &str, units: &[(&str, u64)], default_multiplier: u64) -> Option<u64> {
+    let digits = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (amount, unit) = text.split_at(digits);
+    let multiplier = if unit.is_empty() {
+        Some(default_multiplier)
+    } else {
+        units
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+            .map(|(_, multiplier)| *multiplier)
+    };
+
+    match (amount.parse::<f64>(), multiplier) {
+        (Ok(amount), Some(multiplier)) if amount.is_finite() => {
+            Some((amount * multiplier as f64).round() as u64)
+        }
+        _ => None,
+    }
+}
+
+fn parse_count(text: &str, span: Range<usize>) -> QueryParseResult<u64> {
+    text.parse::<u64>()
+        .map_err(|_| error(format!("invalid number '{}'", text), span))
+}
+
+fn parse_status(value: &Value) -> QueryParseResult<FileStatus> {
+    match value.text.to_lowercase().as_str() {
+        "imported" => Ok(FileStatus::Imported),
+        "archived" => Ok(FileStatus::Archived),
+        "deleted" => Ok(FileStatus::Deleted),
+        "quarantined" => Ok(FileStatus::Quarantined),
+        _ => Err(error(
+            format!(
+                "unknown status '{}', expected imported, archived, deleted or quarantined",
+                value.text
+            ),
+            value.span.clone(),
+        )),
+    }
+}
+
+/// Parses a relation to the file with the given id or to any file
+fn parse_relation(
+    property: &Property,
+    relation_type: FileRelationType,
+) -> QueryParseResult<PropertyQuery> {
+    expect_equal(property)?;
+    let value = &property.value;
+    let related_file_id = if value.text.eq_ignore_ascii_case("any") {
+        None
+    } else {
+        let id = value.text.parse::<i64>().map_err(|_| {
+            error(
+                format!("invalid value '{}', expected a file id or any", value.text),
+                value.span.clone(),
+            )
+        })?;
+        Some(id)
+    };
+
+    Ok(PropertyQuery::Relation(RelationQuery {
+        relation_type,
+        related_file_id,
+    }))
+}
+
+fn parse_bool(value: &Value) -> QueryParseResult<bool> {
+    match value.text.to_lowercase().as_str() {
+        "true" | "yes" => Ok(true),
+        "false" | "no" => Ok(false),
+        _ => Err(error(
+            format!("invalid value '{}', expected true or false", value.text),
+            value.span.clone(),
+        )),
+    }
+}
+
+fn parse_date_time(text: &str) -> Option<NaiveDateTime> {
+    DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, DATE_FORMAT)
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+}
+
+/// Parses a comma separated list of sort keys like `-imported,namespace:page`.
+/// Keys prefixed with `-` are sorted in descending order
+fn parse_sort_keys(value: &Value) -> QueryParseResult<Vec<SortKey>> {
+    let mut keys = Vec::new();
+    let mut offset = 0;
+
+    for part in value.text.split(',') {
+        let span = value.sub_span(offset, offset + part.len());
+        offset += part.len() + 1;
+
+        let (direction, key) = match part.strip_prefix('-') {
+            Some(key) => (SortDirection::Descending, key),
+            None => (
+                SortDirection::Ascending,
+                part.strip_prefix('+').unwrap_or(part),
+            ),
+        };
+        let key = match key.to_lowercase().as_str() {
+            "name" => SortKey::FileName(direction),
+            "size" => SortKey::FileSize(direction),
+            "imported" => SortKey::FileImportedTime(direction),
+            "created" => SortKey::FileCreatedTime(direction),
+            "changed" => SortKey::FileChangeTime(direction),
+            "type" => SortKey::FileType(direction),
+            "tags" => SortKey::NumTags(direction),
+            "width" => SortKey::Width(direction),
+            "height" => SortKey::Height(direction),
+            "duration" => SortKey::Duration(direction),
+            "bitrate" => SortKey::Bitrate(direction),
+            "rating" => SortKey::Rating(direction),
+            _ => match (key.strip_prefix("namespace:"), key.strip_prefix("field:")) {
+                (Some(name), _) if !name.is_empty() => SortKey::Namespace(SortNamespace {
+                    name: name.to_string(),
+                    direction,
+                }),
+                (_, Some(name)) if !name.is_empty() => SortKey::CustomField(SortCustomField {
+                    name: name.to_lowercase(),
+                    direction,
+                }),
+                _ => {
+                    return Err(error(
+                        format!(
+                            "unknown sort key '{}', expected name, size, imported, created, changed, type, tags, width, height, duration, bitrate, rating, namespace:<name> or field:<name>",
+                            key
+                        ),
+                        span,
+                    ))
+                }
+            },
+        };
+        keys.push(key);
+    }
+
+    Ok(keys)
+}
+
+/// Writes a tag that is quoted if it would otherwise be parsed as a keyword or property
+fn write_tag(f: &mut Formatter<'_>, tag: &str) -> fmt::Result {
+    let needs_quotes = ["or", "and", "not"]
+        .iter()
+        .any(|keyword| tag.eq_ignore_ascii_case(keyword))
+        || tag.starts_with(&['-', '.'][..]);
+    write_quoted(f, tag, needs_quotes)
+}
+
+/// Writes a text and quotes it if it isn't a single word
+fn write_quoted(f: &mut Formatter<'_>, text: &str, force: bool) -> fmt::Result {
+    let needs_quotes = force
+        || text.is_empty()
+        || text.starts_with('"')
+        || text
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '(' | ')'));
+
+    if needs_quotes {
+        write!(f, "\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        f.write_str(text)
+    }
+}
+
+/// Writes an amount with the largest unit that divides it without a remainder
+fn write_amount(f: &mut Formatter<'_>, amount: u64, units: &[(&str, u64)]) -> fmt::Result {
+    let (name, multiplier) = units
+        .iter()
+        .find(|(_, multiplier)| amount >= *multiplier && amount.is_multiple_of(*multiplier))
+        .unwrap_or(&units[units.len() - 1]);
+
+    write!(f, "{}{}", amount / multiplier, name)
+}
+
+/// Writes a custom field value so that parsing it infers the same type
+fn write_field_value(f: &mut Formatter<'_>, value: &CustomFieldValue) -> fmt::Result {
+    match value {
+        CustomFieldValue::Int(value) => write!(f, "{}", value),
+        CustomFieldValue::Float(value) => write!(f, "{:?}", value),
+        CustomFieldValue::Text(text) => {
+            let needs_quotes = text.contains("..")
+                || !matches!(parse_field_value(text), CustomFieldValue::Text(_));
+            write_quoted(f, text, needs_quotes)
+        }
+        CustomFieldValue::Date(time) => write_time(f, time),
+        CustomFieldValue::Bool(value) => write!(f, "{}", value),
+    }
+}
+
+fn write_time(f: &mut Formatter<'_>, time: &NaiveDateTime) -> fmt::Result {
+    if time.num_seconds_from_midnight() == 0 && time.nanosecond() == 0 {
+        write!(f, "{}", time.format(DATE_FORMAT))
+    } else if time.nanosecond() == 0 {
+        write!(f, "{}", time.format("%Y-%m-%dT%H:%M:%S"))
+    } else {
+        write!(f, "{}", time.format("%Y-%m-%dT%H:%M:%S%.f"))
+    }
+}
+
+fn write_comparator<T, F>(
+    f: &mut Formatter<'_>,
+    comparator: &ValueComparator<T>,
+    write_value: F,
+) -> fmt::Result
+where
+    F: Fn(&mut Formatter<'_>, &T) -> fmt::Result,
+{
+    match comparator {
+        ValueComparator::Less(value) => {
+            f.write_str("<")?;
+            write_value(f, value)
+        }
+        ValueComparator::Equal(value) => {
+            f.write_str("=")?;
+            write_value(f, value)
+        }
+        ValueComparator::Greater(value) => {
+            f.write_str(">")?;
+            write_value(f, value)
+        }
+        ValueComparator::Between((start, end)) => {
+            f.write_str("=")?;
+            write_value(f, start)?;
+            f.write_str("..")?;
+            write_value(f, end)
+        }
+    }
+}
+
+fn sort_direction_prefix(direction: &SortDirection) -> &'static str {
+    match direction {
+        SortDirection::Ascending => "",
+        SortDirection::Descending => "-",
+    }
+}
+
+impl Display for SortKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (direction, name) = match self {
+            SortKey::Namespace(namespace) => {
+                return write!(
+                    f,
+                    "{}namespace:{}",
+                    sort_direction_prefix(&namespace.direction),
+                    namespace.name
+                )
+            }
+            SortKey::CustomField(field) => {
+                return write!(
+                    f,
+                    "{}field:{}",
+                    sort_direction_prefix(&field.direction),
+                    field.name
+                )
+            }
+            SortKey::FileName(direction) => (direction, "name"),
+            SortKey::FileSize(direction) => (direction, "size"),
+            SortKey::FileImportedTime(direction) => (direction, "imported"),
+            SortKey::FileCreatedTime(direction) => (direction, "created"),
+            SortKey::FileChangeTime(direction) => (direction, "changed"),
+            SortKey::FileType(direction) => (direction, "type"),
+            SortKey::NumTags(direction) => (direction, "tags"),
+            SortKey::Width(direction) => (direction, "width"),
+            SortKey::Height(direction) => (direction, "height"),
+            SortKey::Duration(direction) => (direction, "duration"),
+            SortKey::Bitrate(direction) => (direction, "bitrate"),
+            SortKey::Rating(direction) => (direction, "rating"),
+        };
+
+        write!(f, "{}{}", sort_direction_prefix(direction), name)
+    }
+}
+
+impl Display for PropertyQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertyQuery::Status(status) => {
+                let status = match status {
+                    FileStatus::Imported => "imported",
+                    FileStatus::Archived => "archived",
+                    FileStatus::Deleted => "deleted",
+                    FileStatus::Quarantined => "quarantined",
+                };
+                write!(f, ".status={}", status)
+            }
+            PropertyQuery::FileSize(comparator) => {
+                f.write_str(".size")?;
+                write_comparator(f, comparator, |f, size| write_amount(f, *size, &SIZE_UNITS))
+            }
+            PropertyQuery::ImportedTime(comparator) => {
+                f.write_str(".imported")?;
+                write_comparator(f, comparator, write_time)
+            }
+            PropertyQuery::ChangedTime(comparator) => {
+                f.write_str(".changed")?;
+                write_comparator(f, comparator, write_time)
+            }
+            PropertyQuery::CreatedTime(comparator) => {
+                f.write_str(".created")?;
+                write_comparator(f, comparator, write_time)
+            }
+            PropertyQuery::TagCount(comparator) => {
+                f.write_str(".tags")?;
+                write_comparator(f, comparator, |f, count| write!(f, "{}", count))
+            }
+            PropertyQuery::Cd(cd) => {
+                f.write_str(".cd=")?;
+                write_quoted(f, cd, false)
+            }
+            PropertyQuery::Id(id) => write!(f, ".id={}", id),
+            PropertyQuery::Name(text) => {
+                f.write_str(".name=")?;
+                write_quoted(f, text, false)
+            }
+            PropertyQuery::Comment(text) => {
+                f.write_str(".comment=")?;
+                write_quoted(f, text, false)
+            }
+            PropertyQuery::MimeType(mime_type) => {
+                f.write_str(".type=")?;
+                write_quoted(f, mime_type, false)
+            }
+            PropertyQuery::Namespace(namespace) => {
+                f.write_str(".namespace=")?;
+                write_quoted(f, namespace, false)
+            }
+            PropertyQuery::Untagged(untagged) => write!(f, ".untagged={}", untagged),
+            PropertyQuery::SourceDomain(domain) => {
+                f.write_str(".source=")?;
+                write_quoted(f, domain, false)
+            }
+            PropertyQuery::MissingThumbnail(missing) => {
+                write!(f, ".missing-thumbnail={}", missing)
+            }
+            PropertyQuery::Width(comparator) => {
+                f.write_str(".width")?;
+                write_comparator(f, comparator, |f, width| write!(f, "{}", width))
+            }
+            PropertyQuery::Height(comparator) => {
+                f.write_str(".height")?;
+                write_comparator(f, comparator, |f, height| write!(f, "{}", height))
+            }
+            PropertyQuery::Duration(comparator) => {
+                f.write_str(".duration")?;
+                write_comparator(f, comparator, |f, duration| {
+                    write_amount(f, *duration, &DURATION_UNITS)
+                })
+            }
+            PropertyQuery::Bitrate(comparator) => {
+                f.write_str(".bitrate")?;
+                write_comparator(f, comparator, |f, bitrate| {
+                    write_amount(f, *bitrate, &BITRATE_UNITS)
+                })
+            }
+            PropertyQuery::VideoCodec(codec) => {
+                f.write_str(".video-codec=")?;
+                write_quoted(f, codec, false)
+            }
+            PropertyQuery::AudioCodec(codec) => {
+                f.write_str(".audio-codec=")?;
+                write_quoted(f, codec, false)
+            }
+            PropertyQuery::SourceUrl(url) => {
+                f.write_str(".source-url=")?;
+                write_quoted(f, url, false)
+            }
+            PropertyQuery::Rating(comparator) => {
+                f.write_str(".rating")?;
+                write_comparator(f, comparator, |f, rating| write!(f, "{}", rating))
+            }
+            PropertyQuery::Favorite(favorite) => write!(f, ".favorite={}", favorite),
+            PropertyQuery::CustomField(query) => {
+                write!(f, ".field:{}", query.name)?;
+                write_comparator(f, &query.comparator, write_field_value)
+            }
+            PropertyQuery::InCollection(id) => write!(f, ".collection={}", id),
+            PropertyQuery::Relation(query) => {
+                let name = match query.relation_type {
+                    FileRelationType::Alternate => "alternate-of",
+                    FileRelationType::Parent => "parent-of",
+                    FileRelationType::Child => "child-of",
+                    FileRelationType::DerivedFrom => "derived-from",
+                    FileRelationType::DuplicateOf => "duplicate-of",
+                };
+                match query.related_file_id {
+                    Some(id) => write!(f, ".{}={}", name, id),
+                    None => write!(f, ".{}=any", name),
+                }
+            }
+        }
+    }
+}
+
+impl Display for FilterQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterQuery::Tag(tag) => {
+                if tag.negate {
+                    f.write_str("-")?;
+                }
+                write_tag(f, &tag.tag)
+            }
+            FilterQuery::Property(property) => property.fmt(f),
+        }
+    }
+}
+
+impl Display for FilterExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterExpression::Query(query) => query.fmt(f),
+            FilterExpression::OrExpression(queries) if queries.len() == 1 => queries[0].fmt(f),
+            FilterExpression::OrExpression(queries) => write_joined(f, queries, " OR "),
+            FilterExpression::Or(expressions) if expressions.len() == 1 => expressions[0].fmt(f),
+            FilterExpression::Or(expressions) => write_joined(f, expressions, " OR "),
+            FilterExpression::And(expressions) if expressions.len() == 1 => expressions[0].fmt(f),
+            FilterExpression::And(expressions) => write_joined(f, expressions, " "),
+            FilterExpression::Not(expression) => match expression.as_ref() {
+                FilterExpression::Query(FilterQuery::Tag(tag)) => FilterQuery::Tag(TagQuery {
+                    negate: !tag.negate,
+                    tag: tag.tag.clone(),
+                })
+                .fmt(f),
+                expression => write!(f, "-{}", expression),
+            },
+        }
+    }
+}
+
+/// Writes a parenthesized list of items joined with the given separator
+fn write_joined<T: Display>(f: &mut Formatter<'_>, items: &[T], separator: &str) -> fmt::Result {
+    f.write_str("(")?;
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(separator)?;
+        }
+        item.fmt(f)?;
+    }
+    f.write_str(")")
+}
+
+/// Formats the request as the canonical query text that [parse_query] accepts
+impl Display for FindFilesRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let filters = self.filters.iter().filter(|e| match e {
+            FilterExpression::OrExpression(queries) => !queries.is_empty(),
+            FilterExpression::Or(expressions) | FilterExpression::And(expressions) => {
+                !expressions.is_empty()
+            }
+            _ => true,
+        });
+
+        let mut separator = "";
+
+        for expression in filters {
+            f.write_str(separator)?;
+            expression.fmt(f)?;
+            separator = " ";
+        }
+        if !self.sort_expression.is_empty() {
+            let keys: Vec<String> = self.sort_expression.iter().map(|k| k.to_string()).collect();
+            f.write_str(separator)?;
+            f.write_str(".sort=")?;
+            write_quoted(f, &keys.join(","), false)?;
+        }
+
+        Ok(())
+    }
+}
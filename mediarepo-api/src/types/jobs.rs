@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -13,4 +14,50 @@ pub enum JobType {
     GenerateThumbnails,
     CheckIntegrity,
     Vacuum,
+    VerifyFiles,
+    GarbageCollect { dry_run: bool },
+    GeneratePerceptualHashes,
+    FindDuplicates,
+    ReencodeThumbnails,
+    MigrateThumbnails,
+    CompactThumbnails,
+    GenerateMediaMetadata,
+    ExtractEmbeddedTags,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IntegrityFindingResponse {
+    pub id: i64,
+    pub kind: IntegrityFindingKind,
+    pub storage_key: String,
+    pub cd: Option<String>,
+    pub detected_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntegrityFindingKind {
+    Missing,
+    Mismatch,
+    Stray,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GarbageCollectionResponse {
+    pub dry_run: bool,
+    pub orphans: Vec<OrphanResponse>,
+    pub total_size: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OrphanResponse {
+    pub kind: OrphanKind,
+    pub key: String,
+    pub size: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrphanKind {
+    File,
+    Thumbnails,
+    Upload,
 }
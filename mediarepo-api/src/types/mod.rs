@@ -1,7 +1,14 @@
+pub mod collections;
+pub mod custom_fields;
+pub mod duplicates;
+pub mod facets;
 pub mod files;
 pub mod filtering;
 pub mod identifier;
 pub mod jobs;
 pub mod misc;
 pub mod repo;
+pub mod searches;
+pub mod relations;
+pub mod sources;
 pub mod tags;
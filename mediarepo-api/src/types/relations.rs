@@ -0,0 +1,26 @@
+use crate::types::identifier::FileIdentifier;
+use serde::{Deserialize, Serialize};
+
+/// The type of a relation that reads as "the file is the `<type>` of the related file"
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FileRelationType {
+    Alternate,
+    Parent,
+    Child,
+    DerivedFrom,
+    DuplicateOf,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileRelationResponse {
+    pub file_id: i64,
+    pub related_file_id: i64,
+    pub relation_type: FileRelationType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChangeFileRelationRequest {
+    pub file_id: FileIdentifier,
+    pub related_file_id: FileIdentifier,
+    pub relation_type: FileRelationType,
+}
@@ -0,0 +1,34 @@
+use crate::types::filtering::FindFilesRequest;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: i64,
+    pub name: String,
+    pub query: FindFilesRequest,
+    pub sorting_preset_id: Option<i32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AddSavedSearchRequest {
+    pub name: String,
+    pub query: FindFilesRequest,
+    pub sorting_preset_id: Option<i32>,
+}
+
+/// Changes the fields of a saved search that are set
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateSavedSearchRequest {
+    pub id: i64,
+    pub name: Option<String>,
+    pub query: Option<FindFilesRequest>,
+    pub sorting_preset_id: Option<Option<i32>>,
+}
+
+/// Requests a page of the files matching a saved search
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunSavedSearchRequest {
+    pub id: i64,
+    pub offset: u64,
+    pub limit: u64,
+}
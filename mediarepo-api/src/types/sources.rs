@@ -0,0 +1,14 @@
+use crate::types::identifier::FileIdentifier;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SourceResponse {
+    pub id: i64,
+    pub url: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChangeFileSourcesRequest {
+    pub file_id: FileIdentifier,
+    pub urls: Vec<String>,
+}
@@ -1,4 +1,4 @@
-use multihash::{Code, MultihashDigest};
+use multihash::{Code, Hasher, MultihashDigest, Sha2_256};
 
 use crate::error::RepoResult;
 
@@ -7,6 +7,27 @@ pub fn create_content_descriptor(bytes: &[u8]) -> Vec<u8> {
     Code::Sha2_256.digest(bytes).to_bytes()
 }
 
+/// Incrementally creates a content descriptor for data that is
+/// not available at once. The result is identical to [create_content_descriptor]
+#[derive(Debug, Default)]
+pub struct ContentDescriptorBuilder {
+    hasher: Sha2_256,
+}
+
+impl ContentDescriptorBuilder {
+    /// Feeds the next part of the content into the descriptor
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.hasher.update(bytes);
+    }
+
+    /// Creates the content descriptor for all content that has been added
+    pub fn finalize(mut self) -> RepoResult<Vec<u8>> {
+        let multihash = Code::Sha2_256.wrap(self.hasher.finalize())?;
+
+        Ok(multihash.to_bytes())
+    }
+}
+
 /// Encodes a content descriptor while respecting the version
 pub fn encode_content_descriptor(descriptor: &[u8]) -> String {
     if is_v1_content_descriptor(descriptor) {
@@ -17,6 +17,9 @@ pub enum RepoError {
     #[error(transparent)]
     Multibase(#[from] multibase::Error),
 
+    #[error(transparent)]
+    Multihash(#[from] multihash::Error),
+
     #[error("Config Error: {0}")]
     TomlDe(#[from] toml::de::Error),
 
@@ -32,6 +35,12 @@ pub enum RepoError {
     #[error(transparent)]
     Thumbnailer(#[from] thumbnailer::error::ThumbError),
 
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+
+    #[error(transparent)]
+    Media(#[from] symphonia::core::errors::Error),
+
     #[error("no free tcp port available")]
     PortUnavailable,
 
@@ -46,6 +55,24 @@ pub enum RepoError {
 
     #[error("bincode de-/serialization failed {0}")]
     Bincode(#[from] bincode::Error),
+
+    #[error(transparent)]
+    S3(#[from] s3::error::S3Error),
+
+    #[error(transparent)]
+    S3Credentials(#[from] s3::creds::error::CredentialsError),
+
+    #[error("storage request for {path} failed with status {status}")]
+    Storage { path: String, status: u16 },
+
+    #[error("the thumbnail {0} does not exist")]
+    ThumbnailNotFound(String),
+
+    #[error("the upload {0} does not exist")]
+    UploadNotFound(String),
+
+    #[error("received chunk at offset {received} but the upload is at offset {expected}")]
+    UploadOffsetMismatch { expected: u64, received: u64 },
 }
 
 #[derive(Error, Debug)]
@@ -0,0 +1,148 @@
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use tokio::fs;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, BufReader};
+
+use crate::error::RepoResult;
+use crate::fs::blob_store::{BlobMetadata, BlobReader, BlobStore};
+use crate::utils::get_folder_size;
+
+/// Stores blobs as files in a local directory
+#[derive(Clone, Debug)]
+pub struct LocalBlobStore {
+    path: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn key_to_path(&self, key: &str) -> PathBuf {
+        let mut path = self.path.clone();
+        path.extend(key.split('/'));
+
+        path
+    }
+
+    async fn open(&self, key: &str) -> RepoResult<BufReader<File>> {
+        let path = self.key_to_path(key);
+        tracing::debug!("Opening file {:?}", path);
+        let file = OpenOptions::new().read(true).open(path).await?;
+
+        Ok(BufReader::new(file))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn add(&self, key: &str, source: &Path) -> RepoResult<()> {
+        let path = self.key_to_path(key);
+        let parent = path.parent().unwrap();
+
+        if !parent.exists() {
+            fs::create_dir_all(parent).await?;
+        }
+        if fs::rename(source, &path).await.is_err() {
+            // the source might be located on a different file system
+            fs::copy(source, &path).await?;
+            fs::remove_file(source).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> RepoResult<BlobReader> {
+        let reader = self.open(key).await?;
+
+        Ok(Box::new(reader))
+    }
+
+    async fn get_range(&self, key: &str, offset: u64, length: u64) -> RepoResult<(Vec<u8>, u64)> {
+        let mut reader = self.open(key).await?;
+        let total_size = reader.get_ref().metadata().await?.len();
+        let length = length.min(total_size.saturating_sub(offset));
+        let mut buf = Vec::with_capacity(length as usize);
+
+        if length > 0 {
+            reader.seek(SeekFrom::Start(offset)).await?;
+            reader.take(length).read_to_end(&mut buf).await?;
+        }
+
+        Ok((buf, total_size))
+    }
+
+    async fn rename(&self, src_key: &str, dst_key: &str) -> RepoResult<()> {
+        let src_path = self.key_to_path(src_key);
+        if !src_path.exists() {
+            tracing::warn!("file {:?} doesn't exist", src_path);
+            return Ok(());
+        }
+        let dst_path = self.key_to_path(dst_key);
+        let dst_parent = dst_path.parent().unwrap();
+        if !dst_parent.exists() {
+            fs::create_dir_all(dst_parent).await?;
+        }
+        fs::rename(src_path, dst_path).await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> RepoResult<()> {
+        let path = self.key_to_path(key);
+        if !path.exists() {
+            tracing::warn!("file {:?} doesn't exist", path);
+            return Ok(());
+        }
+        fs::remove_file(path).await?;
+
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> RepoResult<bool> {
+        Ok(self.key_to_path(key).is_file())
+    }
+
+    async fn metadata(&self, key: &str) -> RepoResult<BlobMetadata> {
+        let metadata = fs::metadata(self.key_to_path(key)).await?;
+
+        Ok(BlobMetadata {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+
+    async fn list(&self) -> RepoResult<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut unchecked_dirs = vec![(self.path.clone(), String::new())];
+
+        while let Some((dir, prefix)) = unchecked_dirs.pop() {
+            let mut entries = fs::read_dir(dir).await?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let key = format!("{}{}", prefix, name);
+
+                if entry.file_type().await?.is_dir() {
+                    unchecked_dirs.push((entry.path(), format!("{}/", key)));
+                } else {
+                    keys.push(key);
+                }
+            }
+        }
+
+        Ok(keys)
+    }
+
+    #[inline]
+    async fn size(&self) -> RepoResult<u64> {
+        get_folder_size(self.path.to_owned()).await
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use tokio::io::AsyncRead;
+
+use crate::error::RepoResult;
+use crate::settings::{StorageBackend, StorageSettings};
+
+pub use local::LocalBlobStore;
+pub use s3::S3BlobStore;
+
+mod local;
+mod s3;
+
+pub type BlobReader = Box<dyn AsyncRead + Unpin + Send>;
+
+/// Information about a stored blob
+#[derive(Clone, Debug)]
+pub struct BlobMetadata {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// A store that persists binary blobs under string keys.
+/// Keys use `/` as separator independent of the backend
+#[async_trait]
+pub trait BlobStore: Debug + Send + Sync {
+    /// Stores the file at the given local path under the key.
+    /// The local file is consumed by this operation
+    async fn add(&self, key: &str, source: &Path) -> RepoResult<()>;
+
+    /// Returns a reader for the contents of a blob
+    async fn get(&self, key: &str) -> RepoResult<BlobReader>;
+
+    /// Reads up to `length` bytes of a blob starting at `offset`.
+    /// Returns the bytes together with the total size of the blob
+    async fn get_range(&self, key: &str, offset: u64, length: u64) -> RepoResult<(Vec<u8>, u64)>;
+
+    /// Moves a blob to a different key
+    async fn rename(&self, src_key: &str, dst_key: &str) -> RepoResult<()>;
+
+    /// Deletes a blob. Deleting a blob that doesn't exist is not an error
+    async fn delete(&self, key: &str) -> RepoResult<()>;
+
+    /// Checks if a blob with the given key exists
+    async fn exists(&self, key: &str) -> RepoResult<bool>;
+
+    /// Returns the size and modification time of a blob
+    async fn metadata(&self, key: &str) -> RepoResult<BlobMetadata>;
+
+    /// Returns the keys of all stored blobs
+    async fn list(&self) -> RepoResult<Vec<String>>;
+
+    /// Returns the combined size of all stored blobs
+    async fn size(&self) -> RepoResult<u64>;
+
+    /// Returns if blobs are kept as files on the local file system.
+    /// Only local stores can keep hardlinks and reflinks of imported files
+    fn is_local(&self) -> bool;
+}
+
+/// Creates the blob store for file contents that is configured in the settings
+pub fn create_blob_store(
+    settings: &StorageSettings,
+    files_dir: PathBuf,
+) -> RepoResult<Arc<dyn BlobStore>> {
+    let store: Arc<dyn BlobStore> = match settings.backend {
+        StorageBackend::Local => Arc::new(LocalBlobStore::new(files_dir)),
+        StorageBackend::S3 => Arc::new(S3BlobStore::new(&settings.s3)?),
+    };
+
+    Ok(store)
+}
@@ -0,0 +1,210 @@
+use std::io::Cursor;
+use std::path::Path;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use s3::creds::Credentials;
+use s3::error::S3Error;
+use s3::{Bucket, Region};
+use tokio::fs;
+use tokio::fs::File;
+
+use crate::error::{RepoError, RepoResult};
+use crate::fs::blob_store::{BlobMetadata, BlobReader, BlobStore};
+use crate::settings::S3StorageSettings;
+
+/// Stores blobs as objects in an S3 compatible object storage
+#[derive(Clone, Debug)]
+pub struct S3BlobStore {
+    bucket: Bucket,
+    prefix: String,
+}
+
+impl S3BlobStore {
+    pub fn new(settings: &S3StorageSettings) -> RepoResult<Self> {
+        let region = if let Some(endpoint) = &settings.endpoint {
+            Region::Custom {
+                region: settings.region.clone(),
+                endpoint: endpoint.clone(),
+            }
+        } else {
+            settings.region.parse().map_err(S3Error::from)?
+        };
+        let credentials = Credentials::new(
+            settings.access_key.as_deref(),
+            settings.secret_key.as_deref(),
+            None,
+            None,
+            None,
+        )?;
+        let mut bucket = Bucket::new(&settings.bucket, region, credentials)?;
+
+        if settings.path_style {
+            bucket = bucket.with_path_style();
+        }
+        let prefix = settings.prefix.trim_matches('/');
+        let prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", prefix)
+        };
+
+        Ok(Self { bucket, prefix })
+    }
+
+    fn object_path(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    async fn object_size(&self, path: &str) -> RepoResult<u64> {
+        let (head, status) = self.bucket.head_object(path).await?;
+        check_status(status, path)?;
+
+        Ok(head.content_length.unwrap_or(0) as u64)
+    }
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn add(&self, key: &str, source: &Path) -> RepoResult<()> {
+        let path = self.object_path(key);
+        let mut file = File::open(source).await?;
+        let status = self.bucket.put_object_stream(&mut file, &path).await?;
+        check_status(status, &path)?;
+        fs::remove_file(source).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> RepoResult<BlobReader> {
+        let path = self.object_path(key);
+        tracing::debug!("Fetching object {}", path);
+        let response = self.bucket.get_object(&path).await?;
+        check_status(response.status_code(), &path)?;
+
+        Ok(Box::new(Cursor::new(response.to_vec())))
+    }
+
+    async fn get_range(&self, key: &str, offset: u64, length: u64) -> RepoResult<(Vec<u8>, u64)> {
+        let path = self.object_path(key);
+        let total_size = self.object_size(&path).await?;
+        let length = length.min(total_size.saturating_sub(offset));
+
+        // closed ranges have to span at least two bytes so a single byte can only
+        // be requested on its own if it's the last one using an open range
+        let end = match length {
+            0 => return Ok((Vec::new(), total_size)),
+            1 if offset + 1 == total_size => None,
+            1 => Some(offset + 1),
+            _ => Some(offset + length - 1),
+        };
+        let response = self.bucket.get_object_range(&path, offset, end).await?;
+        check_status(response.status_code(), &path)?;
+        let mut bytes = response.to_vec();
+
+        if length == 1 {
+            bytes.truncate(1);
+        }
+
+        Ok((bytes, total_size))
+    }
+
+    async fn rename(&self, src_key: &str, dst_key: &str) -> RepoResult<()> {
+        let src_path = self.object_path(src_key);
+        let dst_path = self.object_path(dst_key);
+        if !self.exists(src_key).await? {
+            tracing::warn!("object {} doesn't exist", src_path);
+            return Ok(());
+        }
+        let status = self
+            .bucket
+            .copy_object_internal(&src_path, &dst_path)
+            .await?;
+        check_status(status, &dst_path)?;
+        self.delete(src_key).await
+    }
+
+    async fn delete(&self, key: &str) -> RepoResult<()> {
+        let path = self.object_path(key);
+        let response = self.bucket.delete_object(&path).await?;
+
+        if response.status_code() == 404 {
+            tracing::warn!("object {} doesn't exist", path);
+            Ok(())
+        } else {
+            check_status(response.status_code(), &path)
+        }
+    }
+
+    async fn exists(&self, key: &str) -> RepoResult<bool> {
+        let path = self.object_path(key);
+        let (_, status) = self.bucket.head_object(&path).await?;
+
+        if status == 404 {
+            Ok(false)
+        } else {
+            check_status(status, &path)?;
+            Ok(true)
+        }
+    }
+
+    async fn metadata(&self, key: &str) -> RepoResult<BlobMetadata> {
+        let path = self.object_path(key);
+        let (head, status) = self.bucket.head_object(&path).await?;
+        check_status(status, &path)?;
+        let modified = head
+            .last_modified
+            .and_then(|date| chrono::DateTime::parse_from_rfc2822(&date).ok())
+            .map(SystemTime::from);
+
+        Ok(BlobMetadata {
+            size: head.content_length.unwrap_or(0) as u64,
+            modified,
+        })
+    }
+
+    async fn list(&self) -> RepoResult<Vec<String>> {
+        let keys = self
+            .bucket
+            .list(self.prefix.clone(), None)
+            .await?
+            .into_iter()
+            .flat_map(|result| result.contents)
+            .map(|object| object.key[self.prefix.len()..].to_string())
+            .collect();
+
+        Ok(keys)
+    }
+
+    async fn size(&self) -> RepoResult<u64> {
+        let size = self
+            .bucket
+            .list(self.prefix.clone(), None)
+            .await?
+            .into_iter()
+            .flat_map(|result| result.contents)
+            .map(|object| object.size)
+            .sum();
+
+        Ok(size)
+    }
+
+    fn is_local(&self) -> bool {
+        false
+    }
+}
+
+fn check_status(status: u16, path: &str) -> RepoResult<()> {
+    match status {
+        200..=299 => Ok(()),
+        404 => Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("object {} doesn't exist", path),
+        )
+        .into()),
+        status => Err(RepoError::Storage {
+            path: path.to_string(),
+            status,
+        }),
+    }
+}
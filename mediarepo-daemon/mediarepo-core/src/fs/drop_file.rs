@@ -15,6 +15,7 @@ impl DropFile {
             .write(true)
             .read(true)
             .create(true)
+            .truncate(false)
             .open(path.as_ref())
             .await?;
         Ok((file, Self::from_path(path)))
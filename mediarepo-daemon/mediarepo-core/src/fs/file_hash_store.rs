@@ -1,62 +1,331 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::io::{ErrorKind, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use tokio::fs;
 use tokio::fs::{File, OpenOptions};
-use tokio::io::{AsyncRead, AsyncReadExt, BufReader};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
-use crate::content_descriptor::{create_content_descriptor, encode_content_descriptor};
-use crate::error::RepoResult;
-use crate::utils::get_folder_size;
+use crate::content_descriptor::{
+    decode_content_descriptor, encode_content_descriptor, ContentDescriptorBuilder,
+};
+use crate::error::{RepoError, RepoResult};
+use crate::fs::blob_store::{BlobMetadata, BlobReader, BlobStore};
+use crate::fs::link::{link_or_copy, ImportMode};
 
+const UPLOADS_FOLDER_NAME: &str = "uploads";
+const COPY_BUFFER_SIZE: usize = 64 * 1024;
+/// Uploads that haven't received data for this duration are considered abandoned
+pub const UPLOAD_EXPIRY: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// Stores files by their content descriptor in a [BlobStore].
+/// Uploads are staged in a local directory before being moved into the store
 #[derive(Clone, Debug)]
 pub struct FileHashStore {
     path: PathBuf,
+    backend: Arc<dyn BlobStore>,
+    uploads: Arc<Mutex<HashMap<String, Arc<Mutex<PendingUpload>>>>>,
+}
+
+/// The state of an upload that has been started but not yet committed
+#[derive(Debug, Default)]
+struct PendingUpload {
+    descriptor: ContentDescriptorBuilder,
+    size: u64,
 }
 
 impl FileHashStore {
-    pub fn new(path: PathBuf) -> Self {
-        Self { path }
+    pub fn new(path: PathBuf, backend: Arc<dyn BlobStore>) -> Self {
+        Self {
+            path,
+            backend,
+            uploads: Default::default(),
+        }
     }
 
     /// Adds a file that can be read to the hash store and returns the resulting hash identifier
     pub async fn add_file<R: AsyncRead + Unpin>(
         &self,
         mut reader: R,
-        extension: Option<&str>,
     ) -> RepoResult<Vec<u8>> {
-        let mut buf = Vec::new();
-        reader.read_to_end(&mut buf).await?;
-        let descriptor = create_content_descriptor(&buf);
-        let file_path = self.descriptor_to_file_path(&descriptor);
-        let folder_path = file_path.parent().unwrap();
+        let staging_id = generate_upload_id();
+        let staging_path = self.upload_path(&staging_id).await?;
+        let mut upload = self.track_staging_file(&staging_id).await;
+
+        let result = match write_to_staging_file(&staging_path, &mut reader, &mut upload).await {
+            Ok(_) => match std::mem::take(&mut upload.descriptor).finalize() {
+                Ok(descriptor) => self
+                    .move_into_store(&staging_path, &descriptor)
+                    .await
+                    .map(|_| descriptor),
+                Err(e) => Err(e),
+            },
+            Err(e) => {
+                let _ = fs::remove_file(&staging_path).await;
+                Err(e)
+            }
+        };
+        self.uploads.lock().await.remove(&staging_id);
+
+        result
+    }
+
+    /// Adds a file from the local file system by linking it into the store if possible.
+    /// Returns the hash identifier, the size of the file and the mode that was used.
+    /// If the store already contains the file it is left unchanged
+    pub async fn add_file_by_reference(
+        &self,
+        path: &Path,
+        mode: ImportMode,
+    ) -> RepoResult<(Vec<u8>, u64, ImportMode)> {
+        let staging_id = generate_upload_id();
+        let staging_path = self.upload_path(&staging_id).await?;
+        let requested_mode = if self.backend.is_local() {
+            mode
+        } else {
+            ImportMode::Copy
+        };
+        // linked files keep the modification time of the source so the staging
+        // file is tracked to prevent it from being removed as an expired upload
+        let _guard = self.track_staging_file(&staging_id).await;
+        let result = self
+            .link_into_store(path, &staging_path, requested_mode)
+            .await;
+        self.uploads.lock().await.remove(&staging_id);
+
+        result
+    }
+
+    async fn link_into_store(
+        &self,
+        path: &Path,
+        staging_path: &Path,
+        mode: ImportMode,
+    ) -> RepoResult<(Vec<u8>, u64, ImportMode)> {
+        let used_mode = link_or_copy(path, staging_path, mode).await?;
+        let upload = match read_staging_file(staging_path).await {
+            Ok(upload) => upload,
+            Err(e) => {
+                let _ = fs::remove_file(staging_path).await;
+                return Err(e);
+            }
+        };
+        let size = upload.size;
+        let descriptor = upload.descriptor.finalize()?;
 
-        if !folder_path.exists() {
-            fs::create_dir(folder_path).await?;
+        if self.backend.exists(&descriptor_to_key(&descriptor)).await? {
+            fs::remove_file(staging_path).await?;
+            return Ok((descriptor, size, ImportMode::Copy));
         }
-        let mut file_path = self.descriptor_to_file_path(&descriptor);
-        if let Some(extension) = extension {
-            file_path.set_extension(extension);
+        self.move_into_store(staging_path, &descriptor).await?;
+
+        Ok((descriptor, size, used_mode))
+    }
+
+    /// Starts a new chunked upload and returns its id.
+    /// Uploads that haven't received any data for [UPLOAD_EXPIRY] are removed
+    pub async fn begin_upload(&self) -> RepoResult<String> {
+        if let Err(e) = self.expire_uploads(UPLOAD_EXPIRY).await {
+            tracing::warn!("failed to remove expired uploads: {}", e);
         }
-        fs::write(file_path, buf).await?;
+        let upload_id = generate_upload_id();
+        let staging_path = self.upload_path(&upload_id).await?;
+        File::create(staging_path).await?;
+        self.uploads.lock().await.insert(
+            upload_id.clone(),
+            Arc::new(Mutex::new(PendingUpload::default())),
+        );
 
-        Ok(descriptor)
+        Ok(upload_id)
     }
 
-    /// Returns the file extension and a reader for the file by hash
-    pub async fn get_file(
+    /// Returns the number of bytes that have been received for an upload
+    pub async fn upload_offset(&self, upload_id: &str) -> RepoResult<u64> {
+        let upload = self.pending_upload(upload_id).await?;
+        let offset = upload.lock().await.size;
+
+        Ok(offset)
+    }
+
+    /// Appends a chunk to an upload and returns the new offset.
+    /// The offset of the chunk has to match the number of bytes already received
+    pub async fn append_upload_chunk(
         &self,
-        descriptor: &[u8],
-    ) -> RepoResult<(Option<String>, BufReader<File>)> {
-        let file_path = self.descriptor_to_file_path(descriptor);
-        tracing::debug!("Opening file {:?}", file_path);
-        let extension = file_path
-            .extension()
-            .and_then(|s| s.to_str())
-            .map(|s| s.to_string());
-        let file = OpenOptions::new().read(true).open(file_path).await?;
-        let reader = BufReader::new(file);
+        upload_id: &str,
+        offset: u64,
+        bytes: &[u8],
+    ) -> RepoResult<u64> {
+        let upload = self.pending_upload(upload_id).await?;
+        let mut upload = upload.lock().await;
+
+        if offset != upload.size {
+            return Err(RepoError::UploadOffsetMismatch {
+                expected: upload.size,
+                received: offset,
+            });
+        }
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(self.upload_path(upload_id).await?)
+            .await?;
+        // a previously failed write might have left a partial chunk behind
+        file.set_len(upload.size).await?;
+        file.seek(SeekFrom::Start(upload.size)).await?;
+
+        if let Err(e) = write_chunk(&mut file, bytes).await {
+            let _ = file.set_len(upload.size).await;
+            return Err(e);
+        }
+        upload.descriptor.update(bytes);
+        upload.size += bytes.len() as u64;
+
+        Ok(upload.size)
+    }
+
+    /// Moves a finished upload into the store and returns the
+    /// resulting hash identifier together with the size of the file
+    pub async fn commit_upload(&self, upload_id: &str) -> RepoResult<(Vec<u8>, u64)> {
+        let upload = self.pending_upload(upload_id).await?;
+        let mut upload = upload.lock().await;
+        let staging_path = self.upload_path(upload_id).await?;
+
+        if !staging_path.exists() {
+            // the upload has been committed or aborted while waiting for the lock
+            return Err(RepoError::UploadNotFound(upload_id.to_string()));
+        }
+        let descriptor = std::mem::take(&mut upload.descriptor).finalize()?;
+        let size = upload.size;
+        self.uploads.lock().await.remove(upload_id);
+        self.move_into_store(&staging_path, &descriptor).await?;
+
+        Ok((descriptor, size))
+    }
+
+    /// Discards an upload and all data received for it
+    pub async fn abort_upload(&self, upload_id: &str) -> RepoResult<()> {
+        let upload = self.pending_upload(upload_id).await?;
+        let _upload = upload.lock().await;
+        self.uploads.lock().await.remove(upload_id);
+        fs::remove_file(self.upload_path(upload_id).await?).await?;
 
-        Ok((extension, reader))
+        Ok(())
+    }
+
+    /// Removes all uploads that haven't received any data for the given duration.
+    /// Returns the ids of the removed uploads
+    pub async fn expire_uploads(&self, max_age: Duration) -> RepoResult<Vec<String>> {
+        let mut expired = Vec::new();
+
+        for (upload_id, metadata) in self.list_uploads().await? {
+            if !is_older_than(metadata.modified, max_age) {
+                continue;
+            }
+            let mut uploads = self.uploads.lock().await;
+
+            if let Some(upload) = uploads.get(&upload_id) {
+                if upload.try_lock().is_err() {
+                    // a chunk is being written right now
+                    continue;
+                }
+                uploads.remove(&upload_id);
+            }
+            match fs::remove_file(self.upload_path(&upload_id).await?).await {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+            tracing::debug!("removed expired upload {}", upload_id);
+            expired.push(upload_id);
+        }
+
+        Ok(expired)
+    }
+
+    /// Returns the ids of all staged uploads together with the size
+    /// and the time the last data has been received
+    pub async fn list_uploads(&self) -> RepoResult<Vec<(String, BlobMetadata)>> {
+        let mut path = self.path.clone();
+        path.push(UPLOADS_FOLDER_NAME);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let mut entries = fs::read_dir(path).await?;
+        let mut uploads = Vec::new();
+
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if !metadata.is_file() {
+                continue;
+            }
+            if let Some(upload_id) = entry
+                .file_name()
+                .to_str()
+                .filter(|id| is_valid_upload_id(id))
+            {
+                uploads.push((
+                    upload_id.to_string(),
+                    BlobMetadata {
+                        size: metadata.len(),
+                        modified: metadata.modified().ok(),
+                    },
+                ));
+            }
+        }
+
+        Ok(uploads)
+    }
+
+    /// Returns the staged uploads that aren't known to the store (e.g. left behind
+    /// by a crash) and haven't been modified for the given duration
+    pub async fn find_abandoned_uploads(
+        &self,
+        min_age: Duration,
+    ) -> RepoResult<Vec<(String, BlobMetadata)>> {
+        let staged = self.list_uploads().await?;
+        let uploads = self.uploads.lock().await;
+        let abandoned = staged
+            .into_iter()
+            .filter(|(id, metadata)| {
+                !uploads.contains_key(id) && is_older_than(metadata.modified, min_age)
+            })
+            .collect();
+
+        Ok(abandoned)
+    }
+
+    /// Deletes the staging file of an abandoned upload.
+    /// Uploads that have been resumed in the meantime are kept
+    pub async fn delete_abandoned_upload(&self, upload_id: &str) -> RepoResult<()> {
+        let uploads = self.uploads.lock().await;
+
+        if uploads.contains_key(upload_id) {
+            tracing::debug!("keeping resumed upload {}", upload_id);
+            return Ok(());
+        }
+        match fs::remove_file(self.upload_path(upload_id).await?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a reader for the file by hash
+    pub async fn get_file(&self, descriptor: &[u8]) -> RepoResult<BlobReader> {
+        self.backend.get(&descriptor_to_key(descriptor)).await
+    }
+
+    /// Reads up to `length` bytes of a file by hash starting at `offset`.
+    /// Returns the bytes together with the total size of the file
+    pub async fn get_file_range(
+        &self,
+        descriptor: &[u8],
+        offset: u64,
+        length: u64,
+    ) -> RepoResult<(Vec<u8>, u64)> {
+        self.backend
+            .get_range(&descriptor_to_key(descriptor), offset, length)
+            .await
     }
 
     /// Renames a file
@@ -65,51 +334,220 @@ impl FileHashStore {
         src_descriptor: &[u8],
         dst_descriptor: &[u8],
     ) -> RepoResult<()> {
-        let src_path = self.descriptor_to_file_path(src_descriptor);
-        if !src_path.exists() {
-            tracing::warn!("file {:?} doesn't exist", src_path);
-            return Ok(());
-        }
-        let dst_path = self.descriptor_to_file_path(dst_descriptor);
-        let dst_parent = dst_path.parent().unwrap();
-        if !dst_parent.exists() {
-            fs::create_dir(dst_parent).await?;
-        }
-        fs::rename(src_path, dst_path).await?;
-
-        Ok(())
+        self.backend
+            .rename(
+                &descriptor_to_key(src_descriptor),
+                &descriptor_to_key(dst_descriptor),
+            )
+            .await
     }
 
     pub async fn delete_file(&self, descriptor: &[u8]) -> RepoResult<()> {
-        let path = self.descriptor_to_file_path(descriptor);
-        if !path.exists() {
-            tracing::warn!("file {:?} doesn't exist", path);
-            return Ok(());
+        self.backend.delete(&descriptor_to_key(descriptor)).await
+    }
+
+    /// Computes the content descriptor of a stored file from its contents.
+    /// Returns None if the file doesn't exist in the store
+    pub async fn hash_file(&self, descriptor: &[u8]) -> RepoResult<Option<Vec<u8>>> {
+        let mut reader = match self.get_file(descriptor).await {
+            Ok(reader) => reader,
+            Err(RepoError::Io(e)) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let mut builder = ContentDescriptorBuilder::default();
+        let mut buf = vec![0u8; COPY_BUFFER_SIZE];
+
+        loop {
+            let read = reader.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            builder.update(&buf[..read]);
         }
-        fs::remove_file(path).await?;
 
-        Ok(())
+        builder.finalize().map(Some)
+    }
+
+    /// Returns the keys of all stored files together with the
+    /// descriptor they are stored under if the key is a valid descriptor
+    pub async fn list_files(&self) -> RepoResult<Vec<(String, Option<Vec<u8>>)>> {
+        let uploads_prefix = format!("{}/", UPLOADS_FOLDER_NAME);
+        let files = self
+            .backend
+            .list()
+            .await?
+            .into_iter()
+            .filter(|key| !key.starts_with(&uploads_prefix))
+            .map(|key| {
+                let descriptor = key_to_descriptor(&key);
+                (key, descriptor)
+            })
+            .collect();
+
+        Ok(files)
+    }
+
+    /// Returns the size and modification time of the file stored under the given key
+    #[inline]
+    pub async fn get_key_metadata(&self, key: &str) -> RepoResult<BlobMetadata> {
+        self.backend.metadata(key).await
     }
 
-    /// Scans the size of the folder
+    /// Deletes the file stored under the given key
+    #[inline]
+    pub async fn delete_key(&self, key: &str) -> RepoResult<()> {
+        self.backend.delete(key).await
+    }
+
+    /// Returns the key a file with the given descriptor is stored under
+    #[inline]
+    pub fn file_key(&self, descriptor: &[u8]) -> String {
+        descriptor_to_key(descriptor)
+    }
+
+    /// Returns the size of all stored files
     #[inline]
     pub async fn get_size(&self) -> RepoResult<u64> {
-        get_folder_size(self.path.to_owned()).await
+        self.backend.size().await
+    }
+
+    /// Returns the state of an upload. Uploads that aren't known
+    /// (e.g. because the daemon restarted) are restored from the data already received
+    async fn pending_upload(&self, upload_id: &str) -> RepoResult<Arc<Mutex<PendingUpload>>> {
+        let mut uploads = self.uploads.lock().await;
+
+        if let Some(upload) = uploads.get(upload_id) {
+            return Ok(upload.clone());
+        }
+        let staging_path = self.upload_path(upload_id).await?;
+        if !staging_path.exists() {
+            return Err(RepoError::UploadNotFound(upload_id.to_string()));
+        }
+        tracing::debug!("restoring upload {} from {:?}", upload_id, staging_path);
+        let upload = Arc::new(Mutex::new(read_staging_file(&staging_path).await?));
+        uploads.insert(upload_id.to_string(), upload.clone());
+
+        Ok(upload)
     }
 
-    fn descriptor_to_file_path(&self, descriptor: &[u8]) -> PathBuf {
-        let descriptor_string = encode_content_descriptor(descriptor);
-        let mut path = self.descriptor_string_to_folder_path(&descriptor_string);
-        path.push(descriptor_string);
+    /// Registers a staging file that is written by an import as a locked upload
+    /// so that it is neither expired nor reported as abandoned while in use
+    async fn track_staging_file(&self, staging_id: &str) -> OwnedMutexGuard<PendingUpload> {
+        let upload = Arc::new(Mutex::new(PendingUpload::default()));
+        let guard = upload.clone().lock_owned().await;
+        self.uploads
+            .lock()
+            .await
+            .insert(staging_id.to_string(), upload);
 
-        path
+        guard
     }
 
-    fn descriptor_string_to_folder_path(&self, descriptor: &str) -> PathBuf {
-        assert!(descriptor.len() >= 2);
+    /// Returns the path of the staging file for an upload
+    async fn upload_path(&self, upload_id: &str) -> RepoResult<PathBuf> {
+        if !is_valid_upload_id(upload_id) {
+            return Err(RepoError::UploadNotFound(upload_id.to_string()));
+        }
         let mut path = self.path.clone();
-        path.push(&descriptor[descriptor.len() - 3..descriptor.len() - 1]);
+        path.push(UPLOADS_FOLDER_NAME);
+
+        if !path.exists() {
+            fs::create_dir_all(&path).await?;
+        }
+        path.push(upload_id);
 
-        path
+        Ok(path)
     }
+
+    async fn move_into_store(&self, staging_path: &Path, descriptor: &[u8]) -> RepoResult<()> {
+        self.backend
+            .add(&descriptor_to_key(descriptor), staging_path)
+            .await
+    }
+}
+
+/// Returns the key of a file in the form `<folder>/<descriptor>`
+fn descriptor_to_key(descriptor: &[u8]) -> String {
+    let descriptor_string = encode_content_descriptor(descriptor);
+    assert!(descriptor_string.len() >= 3);
+    let folder = &descriptor_string[descriptor_string.len() - 3..descriptor_string.len() - 1];
+
+    format!("{}/{}", folder, descriptor_string)
+}
+
+/// Returns the descriptor for a key if it points to a valid location
+fn key_to_descriptor(key: &str) -> Option<Vec<u8>> {
+    let (_, descriptor_string) = key.split_once('/')?;
+    if descriptor_string.len() < 3 {
+        return None;
+    }
+    let descriptor = decode_content_descriptor(descriptor_string).ok()?;
+
+    if descriptor_to_key(&descriptor) == key {
+        Some(descriptor)
+    } else {
+        None
+    }
+}
+
+fn is_older_than(modified: Option<SystemTime>, age: Duration) -> bool {
+    modified
+        .and_then(|m| m.elapsed().ok())
+        .map(|elapsed| elapsed > age)
+        .unwrap_or(false)
+}
+
+fn is_valid_upload_id(upload_id: &str) -> bool {
+    !upload_id.is_empty() && upload_id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn generate_upload_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Reads a staging file to restore the state of the upload
+async fn read_staging_file(path: &Path) -> RepoResult<PendingUpload> {
+    let mut upload = PendingUpload::default();
+    let mut reader = BufReader::new(File::open(path).await?);
+    let mut buf = vec![0u8; COPY_BUFFER_SIZE];
+
+    loop {
+        let read = reader.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        upload.descriptor.update(&buf[..read]);
+        upload.size += read as u64;
+    }
+
+    Ok(upload)
+}
+
+async fn write_chunk(file: &mut File, bytes: &[u8]) -> RepoResult<()> {
+    file.write_all(bytes).await?;
+    file.flush().await?;
+
+    Ok(())
+}
+
+async fn write_to_staging_file<R: AsyncRead + Unpin>(
+    path: &Path,
+    reader: &mut R,
+    upload: &mut PendingUpload,
+) -> RepoResult<()> {
+    let mut file = File::create(path).await?;
+    let mut buf = vec![0u8; COPY_BUFFER_SIZE];
+
+    loop {
+        let read = reader.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        upload.descriptor.update(&buf[..read]);
+        upload.size += read as u64;
+        file.write_all(&buf[..read]).await?;
+    }
+    file.flush().await?;
+
+    Ok(())
 }
@@ -0,0 +1,72 @@
+use std::path::Path;
+
+use mediarepo_api::types::files::FileImportMode;
+use tokio::fs;
+
+use crate::error::RepoResult;
+
+/// The way the contents of a file are transferred into the store
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportMode {
+    /// The contents are copied
+    Copy,
+    /// The stored file is a hardlink to the imported file
+    Hardlink,
+    /// The stored file shares its data with the imported file until one of them is modified
+    Reflink,
+}
+
+impl From<FileImportMode> for ImportMode {
+    fn from(mode: FileImportMode) -> Self {
+        match mode {
+            FileImportMode::Copy => Self::Copy,
+            FileImportMode::Hardlink => Self::Hardlink,
+            FileImportMode::Reflink => Self::Reflink,
+        }
+    }
+}
+
+/// Creates the file at `dst` from the file at `src` using the given mode.
+/// Falls back to copying the file if the mode isn't supported by the file system.
+/// Returns the mode that was used
+pub async fn link_or_copy(src: &Path, dst: &Path, mode: ImportMode) -> RepoResult<ImportMode> {
+    let linked = match mode {
+        ImportMode::Copy => false,
+        ImportMode::Hardlink => fs::hard_link(src, dst).await.is_ok(),
+        ImportMode::Reflink => reflink(src, dst).await.is_ok(),
+    };
+    if linked {
+        return Ok(mode);
+    }
+    if mode != ImportMode::Copy {
+        tracing::debug!("{:?} not supported for {:?}, copying instead", mode, src);
+        let _ = fs::remove_file(dst).await;
+    }
+    fs::copy(src, dst).await?;
+
+    Ok(ImportMode::Copy)
+}
+
+#[cfg(target_os = "linux")]
+async fn reflink(src: &Path, dst: &Path) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let src_file = fs::File::open(src).await?;
+    let dst_file = fs::File::create(dst).await?;
+    // SAFETY: both file descriptors stay open until the call returns
+    let result = unsafe { libc::ioctl(dst_file.as_raw_fd(), libc::FICLONE, src_file.as_raw_fd()) };
+
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn reflink(_src: &Path, _dst: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "reflinks are not supported on this platform",
+    ))
+}
@@ -1,3 +1,6 @@
+pub mod blob_store;
 pub mod drop_file;
 pub mod file_hash_store;
+pub mod link;
+pub mod range_reader;
 pub mod thumbnail_store;
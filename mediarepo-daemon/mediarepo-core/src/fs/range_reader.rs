@@ -0,0 +1,108 @@
+use std::io::{BufRead, Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use tokio::runtime::Handle;
+
+use crate::error::RepoResult;
+use crate::fs::file_hash_store::FileHashStore;
+
+/// The number of bytes that are fetched from the store with a single range read
+const CHUNK_SIZE: u64 = 256 * 1024;
+
+/// A blocking reader for a stored file that only fetches the parts of the file that
+/// are actually read. Reads block on the runtime so the reader must only be used
+/// outside of async code, e.g. in [tokio::task::spawn_blocking]
+pub struct FileRangeReader {
+    store: FileHashStore,
+    descriptor: Vec<u8>,
+    handle: Handle,
+    size: u64,
+    position: u64,
+    chunk: Vec<u8>,
+    chunk_offset: u64,
+}
+
+impl FileRangeReader {
+    /// Creates a reader for the file and fetches its first chunk.
+    /// Must be called from within the runtime
+    pub async fn open(store: FileHashStore, descriptor: Vec<u8>) -> RepoResult<Self> {
+        let (chunk, size) = store.get_file_range(&descriptor, 0, CHUNK_SIZE).await?;
+
+        Ok(Self {
+            store,
+            descriptor,
+            handle: Handle::current(),
+            size,
+            position: 0,
+            chunk,
+            chunk_offset: 0,
+        })
+    }
+
+    /// Returns the total size of the file
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn chunk_contains(&self, position: u64) -> bool {
+        position >= self.chunk_offset && position < self.chunk_offset + self.chunk.len() as u64
+    }
+}
+
+impl BufRead for FileRangeReader {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.position >= self.size {
+            return Ok(&[]);
+        }
+        if !self.chunk_contains(self.position) {
+            let (chunk, _) = self
+                .handle
+                .block_on(
+                    self.store
+                        .get_file_range(&self.descriptor, self.position, CHUNK_SIZE),
+                )
+                .map_err(|e| Error::other(e.to_string()))?;
+            if chunk.is_empty() {
+                return Err(Error::from(ErrorKind::UnexpectedEof));
+            }
+            self.chunk = chunk;
+            self.chunk_offset = self.position;
+        }
+        let start = (self.position - self.chunk_offset) as usize;
+
+        Ok(&self.chunk[start..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount as u64).min(self.size);
+    }
+}
+
+impl Read for FileRangeReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let available = self.fill_buf()?;
+        let length = available.len().min(buf.len());
+        buf[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+
+        Ok(length)
+    }
+}
+
+impl Seek for FileRangeReader {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        }
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        self.position = position;
+
+        Ok(position)
+    }
+}
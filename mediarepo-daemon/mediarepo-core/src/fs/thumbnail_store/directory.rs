@@ -0,0 +1,193 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::fs;
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+use crate::error::{RepoError, RepoResult};
+use crate::fs::thumbnail_store::{Dimensions, ThumbnailBackend, PACKS_FOLDER_NAME};
+use crate::settings::ThumbnailFormat;
+use crate::utils::get_folder_size;
+
+const THUMBNAIL_FORMATS: [ThumbnailFormat; 3] = [
+    ThumbnailFormat::Png,
+    ThumbnailFormat::Jpeg,
+    ThumbnailFormat::Webp,
+];
+
+/// Stores thumbnails in one directory per parent with one file per thumbnail
+#[derive(Clone, Debug)]
+pub struct DirectoryThumbnailStore {
+    path: PathBuf,
+}
+
+impl DirectoryThumbnailStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Returns all paths a thumbnail of the given size might be stored at
+    fn thumbnail_paths(&self, parent: &str, size: &Dimensions) -> Vec<PathBuf> {
+        let parent_dir = self.path.join(parent);
+        let file_name = format!("{}-{}", size.height, size.width);
+        let mut paths = vec![parent_dir.join(&file_name)];
+        paths.extend(
+            THUMBNAIL_FORMATS
+                .iter()
+                .map(|f| parent_dir.join(format!("{}.{}", file_name, f.extension()))),
+        );
+
+        paths
+    }
+}
+
+#[async_trait]
+impl ThumbnailBackend for DirectoryThumbnailStore {
+    async fn add(
+        &self,
+        parent: &str,
+        size: &Dimensions,
+        format: ThumbnailFormat,
+        data: &[u8],
+    ) -> RepoResult<()> {
+        let parent_dir = self.path.join(parent);
+        let entry_path = parent_dir.join(format!(
+            "{}-{}.{}",
+            size.height,
+            size.width,
+            format.extension()
+        ));
+
+        if parent_dir.exists() {
+            remove_files(
+                self.thumbnail_paths(parent, size)
+                    .into_iter()
+                    .filter(|p| *p != entry_path),
+            )
+            .await?;
+        } else {
+            fs::create_dir_all(parent_dir).await?;
+        }
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&entry_path)
+            .await?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(data).await?;
+        writer.flush().await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, parent: &str, size: &Dimensions) -> RepoResult<Vec<u8>> {
+        let path = self
+            .thumbnail_paths(parent, size)
+            .into_iter()
+            .find(|p| p.exists())
+            .ok_or_else(|| {
+                RepoError::ThumbnailNotFound(format!("{}/{}-{}", parent, size.height, size.width))
+            })?;
+
+        Ok(fs::read(path).await?)
+    }
+
+    async fn list(&self, parent: &str) -> RepoResult<Vec<(Dimensions, ThumbnailFormat)>> {
+        let mut entries = Vec::new();
+        let parent_dir = self.path.join(parent);
+        if !parent_dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut dir = fs::read_dir(parent_dir).await?;
+
+        while let Ok(Some(entry)) = dir.next_entry().await {
+            let path = entry.path();
+            let format =
+                match ThumbnailFormat::from_extension(path.extension().and_then(|e| e.to_str())) {
+                    Some(format) => format,
+                    None => continue,
+                };
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let (height, width) = name
+                .split_once('-')
+                .and_then(|(height, width)| {
+                    Some((height.parse::<u32>().ok()?, width.parse::<u32>().ok()?))
+                })
+                .unwrap_or((255, 255));
+            entries.push((Dimensions { height, width }, format))
+        }
+
+        Ok(entries)
+    }
+
+    async fn delete(&self, parent: &str, size: &Dimensions) -> RepoResult<()> {
+        remove_files(self.thumbnail_paths(parent, size)).await
+    }
+
+    async fn delete_parent(&self, parent: &str) -> RepoResult<()> {
+        let path = self.path.join(parent);
+
+        if !path.exists() {
+            tracing::warn!("directory {:?} doesn't exist", path);
+            return Ok(());
+        }
+        fs::remove_dir_all(&path).await?;
+
+        Ok(())
+    }
+
+    async fn rename_parent(&self, src: &str, dst: &str) -> RepoResult<()> {
+        let src_dir = self.path.join(src);
+        if !src_dir.exists() {
+            tracing::warn!("directory {:?} doesn't exist", src_dir);
+            return Ok(());
+        }
+        let dst_dir = self.path.join(dst);
+        fs::rename(src_dir, dst_dir).await?;
+
+        Ok(())
+    }
+
+    async fn list_parents(&self) -> RepoResult<Vec<String>> {
+        let mut parents = Vec::new();
+        if !self.path.exists() {
+            return Ok(parents);
+        }
+        let mut dir = fs::read_dir(&self.path).await?;
+
+        while let Some(entry) = dir.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if entry.file_type().await?.is_dir() && name != PACKS_FOLDER_NAME {
+                parents.push(name);
+            }
+        }
+
+        Ok(parents)
+    }
+
+    async fn parent_size(&self, parent: &str) -> RepoResult<u64> {
+        get_folder_size(self.path.join(parent)).await
+    }
+
+    async fn size(&self) -> RepoResult<u64> {
+        get_folder_size(self.path.to_owned()).await
+    }
+}
+
+async fn remove_files<I: IntoIterator<Item = PathBuf>>(paths: I) -> RepoResult<()> {
+    for path in paths {
+        if path.exists() {
+            fs::remove_file(path).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,299 @@
+use std::fmt::Debug;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use image::{DynamicImage, ImageOutputFormat};
+use thumbnailer::Thumbnail;
+
+use crate::error::RepoResult;
+use crate::settings::{ThumbnailFormat, ThumbnailSettings, ThumbnailStorage};
+
+pub use directory::DirectoryThumbnailStore;
+pub use packed::PackedThumbnailStore;
+
+mod directory;
+mod packed;
+
+/// The folder inside the thumbnail directory that contains the pack files
+pub(crate) const PACKS_FOLDER_NAME: &str = "packs";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dimensions {
+    pub height: u32,
+    pub width: u32,
+}
+
+/// A storage for encoded thumbnails. Thumbnails are grouped by a parent id
+/// and identified by their dimensions within the parent
+#[async_trait]
+pub trait ThumbnailBackend: Debug + Send + Sync {
+    /// Stores a thumbnail. An existing thumbnail with the same dimensions is replaced
+    async fn add(
+        &self,
+        parent: &str,
+        size: &Dimensions,
+        format: ThumbnailFormat,
+        data: &[u8],
+    ) -> RepoResult<()>;
+
+    /// Returns the contents of a thumbnail
+    async fn get(&self, parent: &str, size: &Dimensions) -> RepoResult<Vec<u8>>;
+
+    /// Returns the dimensions and formats of all thumbnails of a parent
+    async fn list(&self, parent: &str) -> RepoResult<Vec<(Dimensions, ThumbnailFormat)>>;
+
+    /// Deletes a thumbnail. Deleting a thumbnail that doesn't exist is not an error
+    async fn delete(&self, parent: &str, size: &Dimensions) -> RepoResult<()>;
+
+    /// Deletes all thumbnails of a parent
+    async fn delete_parent(&self, parent: &str) -> RepoResult<()>;
+
+    /// Moves all thumbnails of a parent to a different parent
+    async fn rename_parent(&self, src: &str, dst: &str) -> RepoResult<()>;
+
+    /// Returns the ids of all parents with stored thumbnails
+    async fn list_parents(&self) -> RepoResult<Vec<String>>;
+
+    /// Returns the size of all thumbnails of a parent
+    async fn parent_size(&self, parent: &str) -> RepoResult<u64>;
+
+    /// Returns the space used by the storage
+    async fn size(&self) -> RepoResult<u64>;
+
+    /// Frees the space of replaced and deleted thumbnails if the storage can't do it directly.
+    /// Returns the number of freed bytes
+    async fn compact(&self) -> RepoResult<u64> {
+        Ok(0)
+    }
+
+    /// Returns the space that can be freed by compacting the storage
+    async fn wasted_size(&self) -> RepoResult<u64> {
+        Ok(0)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ThumbnailStore {
+    path: PathBuf,
+    storage: ThumbnailStorage,
+    backend: Arc<dyn ThumbnailBackend>,
+    settings: ThumbnailSettings,
+}
+
+impl ThumbnailStore {
+    /// Opens the thumbnail store in the given directory with the configured storage layout
+    pub async fn open(
+        path: PathBuf,
+        storage: ThumbnailStorage,
+        settings: ThumbnailSettings,
+    ) -> RepoResult<Self> {
+        let backend: Arc<dyn ThumbnailBackend> = match storage {
+            ThumbnailStorage::Directory => Arc::new(DirectoryThumbnailStore::new(path.clone())),
+            ThumbnailStorage::Packed => {
+                Arc::new(PackedThumbnailStore::open(path.join(PACKS_FOLDER_NAME)).await?)
+            }
+        };
+
+        Ok(Self {
+            path,
+            storage,
+            backend,
+            settings,
+        })
+    }
+
+    /// Returns the settings thumbnails are encoded with
+    pub fn settings(&self) -> &ThumbnailSettings {
+        &self.settings
+    }
+
+    /// Encodes a thumbnail in the configured format
+    pub fn encode_thumbnail(&self, thumbnail: Thumbnail) -> RepoResult<Vec<u8>> {
+        let mut buf = Cursor::new(Vec::new());
+
+        match self.settings.format {
+            ThumbnailFormat::Png => thumbnail.write_png(&mut buf)?,
+            ThumbnailFormat::Jpeg => thumbnail.write_jpeg(&mut buf, self.settings.quality)?,
+            ThumbnailFormat::Webp => {
+                thumbnail.write_png(&mut buf)?;
+                let image = image::load_from_memory(buf.get_ref())?;
+
+                return encode_image(image, &self.settings);
+            }
+        }
+
+        Ok(buf.into_inner())
+    }
+
+    /// Adds a thumbnail encoded in the configured format to be stored for a parent id
+    /// if the thumbnail already exists it will be recreated without warning
+    #[tracing::instrument(level = "debug", skip(self, data))]
+    pub async fn add_thumbnail<S: ToString + Debug>(
+        &self,
+        parent_id: S,
+        size: Dimensions,
+        data: &[u8],
+    ) -> RepoResult<()> {
+        self.backend
+            .add(&parent_id.to_string(), &size, self.settings.format, data)
+            .await
+    }
+
+    /// Returns the contents of a thumbnail
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_thumbnail<S: ToString + Debug>(
+        &self,
+        parent_id: S,
+        size: &Dimensions,
+    ) -> RepoResult<Vec<u8>> {
+        self.backend.get(&parent_id.to_string(), size).await
+    }
+
+    /// Deletes a single thumbnail
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete_thumbnail<S: ToString + Debug>(
+        &self,
+        parent_id: S,
+        size: &Dimensions,
+    ) -> RepoResult<()> {
+        self.backend.delete(&parent_id.to_string(), size).await
+    }
+
+    /// Re-encodes all thumbnails of a parent that aren't stored in the configured format.
+    /// If `all` is set thumbnails that are already stored in this format are re-encoded as well.
+    /// Returns the number of re-encoded thumbnails
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn reencode_thumbnails<S: ToString + Debug>(
+        &self,
+        parent_id: S,
+        all: bool,
+    ) -> RepoResult<usize> {
+        let parent_id = parent_id.to_string();
+        let mut count = 0;
+
+        for (size, format) in self.backend.list(&parent_id).await? {
+            if format == self.settings.format && !all {
+                continue;
+            }
+            let image = image::load_from_memory(&self.backend.get(&parent_id, &size).await?)?;
+            let data = encode_image(image, &self.settings)?;
+            self.add_thumbnail(&parent_id, size, &data).await?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Returns all thumbnails for a parent id with the format they are stored in
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_thumbnails<S: ToString + Debug>(
+        &self,
+        parent_id: S,
+    ) -> RepoResult<Vec<(Dimensions, ThumbnailFormat)>> {
+        self.backend.list(&parent_id.to_string()).await
+    }
+
+    /// Renames a thumbnail parent
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn rename_parent<S1: AsRef<str> + Debug, S2: AsRef<str> + Debug>(
+        &self,
+        src: S1,
+        dst: S2,
+    ) -> RepoResult<()> {
+        self.backend.rename_parent(src.as_ref(), dst.as_ref()).await
+    }
+
+    /// Deletes all thumbnails of a parent
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete_parent<S: AsRef<str> + Debug>(&self, parent: S) -> RepoResult<()> {
+        self.backend.delete_parent(parent.as_ref()).await
+    }
+
+    /// Returns the names of all thumbnail parents
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_parents(&self) -> RepoResult<Vec<String>> {
+        self.backend.list_parents().await
+    }
+
+    /// Returns the size of all thumbnails of a parent
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_parent_size<S: AsRef<str> + Debug>(&self, parent: S) -> RepoResult<u64> {
+        self.backend.parent_size(parent.as_ref()).await
+    }
+
+    /// Returns the size of the storage
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_size(&self) -> RepoResult<u64> {
+        self.backend.size().await
+    }
+
+    /// Returns the space that can be freed by compacting the storage
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_wasted_size(&self) -> RepoResult<u64> {
+        self.backend.wasted_size().await
+    }
+
+    /// Compacts the storage and returns the number of freed bytes
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn compact(&self) -> RepoResult<u64> {
+        self.backend.compact().await
+    }
+
+    /// Returns the parents that are still stored in the directory layout
+    /// when the thumbnails are configured to be stored in pack files
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_unmigrated_parents(&self) -> RepoResult<Vec<String>> {
+        if self.storage != ThumbnailStorage::Packed {
+            return Ok(vec![]);
+        }
+        DirectoryThumbnailStore::new(self.path.clone())
+            .list_parents()
+            .await
+    }
+
+    /// Moves the thumbnails of a parent from the directory layout into the pack files.
+    /// Thumbnails that aren't stored in the configured format are re-encoded on the way
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn migrate_parent<S: AsRef<str> + Debug>(&self, parent: S) -> RepoResult<()> {
+        if self.storage != ThumbnailStorage::Packed {
+            return Ok(());
+        }
+        let parent = parent.as_ref();
+        let source = DirectoryThumbnailStore::new(self.path.clone());
+
+        for (size, format) in source.list(parent).await? {
+            let mut data = source.get(parent, &size).await?;
+
+            if format != self.settings.format {
+                data = encode_image(image::load_from_memory(&data)?, &self.settings)?;
+            }
+            self.backend
+                .add(parent, &size, self.settings.format, &data)
+                .await?;
+        }
+        source.delete_parent(parent).await
+    }
+}
+
+/// Encodes an image with the given thumbnail settings
+fn encode_image(image: DynamicImage, settings: &ThumbnailSettings) -> RepoResult<Vec<u8>> {
+    let mut buf = Cursor::new(Vec::new());
+
+    match settings.format {
+        ThumbnailFormat::Png => DynamicImage::ImageRgba8(image.into_rgba8())
+            .write_to(&mut buf, ImageOutputFormat::Png)?,
+        ThumbnailFormat::Jpeg => DynamicImage::ImageRgb8(image.into_rgb8())
+            .write_to(&mut buf, ImageOutputFormat::Jpeg(settings.quality))?,
+        ThumbnailFormat::Webp => {
+            let image = image.into_rgba8();
+            let data = webp::Encoder::from_rgba(&image, image.width(), image.height())
+                .encode(settings.quality as f32);
+
+            return Ok(data.to_vec());
+        }
+    }
+
+    Ok(buf.into_inner())
+}
@@ -0,0 +1,638 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::io::{ErrorKind, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufWriter};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::error::{RepoError, RepoResult};
+use crate::fs::thumbnail_store::{Dimensions, ThumbnailBackend};
+use crate::settings::ThumbnailFormat;
+
+const INDEX_FILE_NAME: &str = "index";
+const PACK_FILE_EXTENSION: &str = "pack";
+const TMP_FILE_EXTENSION: &str = "tmp";
+/// New thumbnails are written to the next pack once a pack reaches this size
+const MAX_PACK_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Stores thumbnails in a few append-only pack files. The location of every
+/// thumbnail is recorded in an append-only index that is replayed when the store is opened.
+/// Replaced and deleted thumbnails keep using space until the store is compacted
+#[derive(Debug)]
+pub struct PackedThumbnailStore {
+    path: PathBuf,
+    state: RwLock<PackState>,
+    /// Serializes all writes to the packs and the index
+    writer: Mutex<()>,
+    compaction: Mutex<()>,
+}
+
+#[derive(Debug, Default)]
+struct PackState {
+    entries: HashMap<String, Vec<PackEntry>>,
+    current_pack: u32,
+    current_pack_size: u64,
+    packs_size: u64,
+    index_size: u64,
+    /// The size of all thumbnails that are still referenced by the index
+    live_size: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PackEntry {
+    height: u32,
+    width: u32,
+    format: ThumbnailFormat,
+    pack: u32,
+    offset: u64,
+    length: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum IndexRecord {
+    Add {
+        parent: String,
+        entry: PackEntry,
+    },
+    Delete {
+        parent: String,
+        height: u32,
+        width: u32,
+    },
+    DeleteParent {
+        parent: String,
+    },
+    RenameParent {
+        src: String,
+        dst: String,
+    },
+}
+
+/// The thumbnails of a compaction that have been copied into temporary packs
+struct CompactedPacks {
+    entries: Vec<(String, PackEntry)>,
+    pack_sizes: Vec<u64>,
+}
+
+impl PackedThumbnailStore {
+    /// Opens the pack store in the given directory and loads its index
+    pub async fn open(path: PathBuf) -> RepoResult<Self> {
+        if !path.exists() {
+            fs::create_dir_all(&path).await?;
+        }
+        let mut state = PackState::default();
+        let mut dir = fs::read_dir(&path).await?;
+
+        while let Some(entry) = dir.next_entry().await? {
+            let entry_path = entry.path();
+
+            if let Some(number) = pack_number(&entry_path) {
+                let size = entry.metadata().await?.len();
+                state.packs_size += size;
+
+                if number >= state.current_pack {
+                    state.current_pack = number;
+                    state.current_pack_size = size;
+                }
+            }
+        }
+        let index_path = path.join(INDEX_FILE_NAME);
+
+        if index_path.exists() {
+            let data = fs::read(&index_path).await?;
+            let (records, valid_length) = decode_records(&data);
+
+            if valid_length < data.len() {
+                tracing::warn!(
+                    "discarding {} bytes of incomplete records at the end of the thumbnail index",
+                    data.len() - valid_length
+                );
+                let file = OpenOptions::new().write(true).open(&index_path).await?;
+                file.set_len(valid_length as u64).await?;
+            }
+            records.into_iter().for_each(|r| state.apply(r));
+            state.index_size = valid_length as u64;
+        }
+
+        let store = Self {
+            path,
+            state: RwLock::new(state),
+            writer: Mutex::new(()),
+            compaction: Mutex::new(()),
+        };
+        store.remove_tmp_files().await?;
+
+        Ok(store)
+    }
+
+    fn pack_path(&self, number: u32) -> PathBuf {
+        self.path
+            .join(format!("{:08}.{}", number, PACK_FILE_EXTENSION))
+    }
+
+    fn compaction_pack_path(&self, number: usize) -> PathBuf {
+        self.path.join(format!(
+            "{:08}.{}.{}",
+            number, PACK_FILE_EXTENSION, TMP_FILE_EXTENSION
+        ))
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.path.join(INDEX_FILE_NAME)
+    }
+
+    /// Persists a record in the index and applies it to the loaded state.
+    /// The writer lock has to be held by the caller
+    async fn append_record(&self, record: IndexRecord) -> RepoResult<()> {
+        let data = encode_record(&record)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.index_path())
+            .await?;
+        file.write_all(&data).await?;
+        file.flush().await?;
+        let mut state = self.state.write().await;
+        state.index_size += data.len() as u64;
+        state.apply(record);
+
+        Ok(())
+    }
+
+    async fn read_entry(&self, parent: &str, size: &Dimensions) -> RepoResult<Vec<u8>> {
+        let entry = self
+            .state
+            .read()
+            .await
+            .find_entry(parent, size.height, size.width)
+            .cloned()
+            .ok_or_else(|| {
+                RepoError::ThumbnailNotFound(format!("{}/{}-{}", parent, size.height, size.width))
+            })?;
+        let mut file = File::open(self.pack_path(entry.pack)).await?;
+        file.seek(SeekFrom::Start(entry.offset)).await?;
+        let mut data = vec![0u8; entry.length as usize];
+        file.read_exact(&mut data).await?;
+
+        Ok(data)
+    }
+
+    async fn pack_numbers(&self) -> RepoResult<Vec<u32>> {
+        let mut numbers = Vec::new();
+        let mut dir = fs::read_dir(&self.path).await?;
+
+        while let Some(entry) = dir.next_entry().await? {
+            if let Some(number) = pack_number(&entry.path()) {
+                numbers.push(number);
+            }
+        }
+
+        Ok(numbers)
+    }
+
+    /// Removes temporary files left behind by an interrupted compaction
+    async fn remove_tmp_files(&self) -> RepoResult<()> {
+        let mut dir = fs::read_dir(&self.path).await?;
+
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+
+            if path.extension().and_then(|e| e.to_str()) == Some(TMP_FILE_EXTENSION) {
+                tracing::debug!("removing temporary file {:?}", path);
+                fs::remove_file(path).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copies the given thumbnails into temporary packs
+    async fn copy_into_compaction_packs(
+        &self,
+        entries: &HashMap<String, Vec<PackEntry>>,
+    ) -> RepoResult<CompactedPacks> {
+        let mut compacted = CompactedPacks {
+            entries: Vec::new(),
+            pack_sizes: Vec::new(),
+        };
+        let mut readers: HashMap<u32, File> = HashMap::new();
+        let mut writer: Option<BufWriter<File>> = None;
+
+        for (parent, entries) in entries {
+            for entry in entries {
+                let reader = match readers.entry(entry.pack) {
+                    Entry::Occupied(occupied) => occupied.into_mut(),
+                    Entry::Vacant(vacant) => {
+                        vacant.insert(File::open(self.pack_path(entry.pack)).await?)
+                    }
+                };
+                reader.seek(SeekFrom::Start(entry.offset)).await?;
+                let mut data = vec![0u8; entry.length as usize];
+                reader.read_exact(&mut data).await?;
+
+                let needs_new_pack = match compacted.pack_sizes.last() {
+                    Some(size) => *size > 0 && size + entry.length > MAX_PACK_SIZE,
+                    None => true,
+                };
+                if needs_new_pack {
+                    if let Some(mut writer) = writer.take() {
+                        finish_pack(&mut writer).await?;
+                    }
+                    let path = self.compaction_pack_path(compacted.pack_sizes.len());
+                    writer = Some(BufWriter::new(File::create(path).await?));
+                    compacted.pack_sizes.push(0);
+                }
+                writer.as_mut().unwrap().write_all(&data).await?;
+
+                let pack = compacted.pack_sizes.len() - 1;
+                let pack_size = &mut compacted.pack_sizes[pack];
+                compacted.entries.push((
+                    parent.clone(),
+                    PackEntry {
+                        pack: pack as u32,
+                        offset: *pack_size,
+                        ..entry.clone()
+                    },
+                ));
+                *pack_size += entry.length;
+            }
+        }
+        if let Some(mut writer) = writer {
+            finish_pack(&mut writer).await?;
+        }
+
+        Ok(compacted)
+    }
+
+    /// Reads all index records that have been written since the index had the given size
+    async fn read_index_tail(&self, offset: u64) -> RepoResult<Vec<u8>> {
+        let mut file = File::open(self.index_path()).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).await?;
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl ThumbnailBackend for PackedThumbnailStore {
+    async fn add(
+        &self,
+        parent: &str,
+        size: &Dimensions,
+        format: ThumbnailFormat,
+        data: &[u8],
+    ) -> RepoResult<()> {
+        let _writer = self.writer.lock().await;
+        let length = data.len() as u64;
+        let (pack, pack_size) = {
+            let mut state = self.state.write().await;
+
+            if state.current_pack_size > 0 && state.current_pack_size + length > MAX_PACK_SIZE {
+                state.current_pack += 1;
+                state.current_pack_size = 0;
+            }
+            (state.current_pack, state.current_pack_size)
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.pack_path(pack))
+            .await?;
+        // the actual length is used in case a previous write has been interrupted
+        let offset = file.metadata().await?.len();
+        file.write_all(data).await?;
+        file.flush().await?;
+        {
+            let mut state = self.state.write().await;
+            state.packs_size += offset + length - pack_size;
+            state.current_pack_size = offset + length;
+        }
+
+        let entry = PackEntry {
+            height: size.height,
+            width: size.width,
+            format,
+            pack,
+            offset,
+            length,
+        };
+        self.append_record(IndexRecord::Add {
+            parent: parent.to_string(),
+            entry,
+        })
+        .await
+    }
+
+    async fn get(&self, parent: &str, size: &Dimensions) -> RepoResult<Vec<u8>> {
+        match self.read_entry(parent, size).await {
+            // the pack has been replaced by a compaction after the entry has been looked up
+            Err(RepoError::Io(e)) if e.kind() == ErrorKind::NotFound => {
+                self.read_entry(parent, size).await
+            }
+            result => result,
+        }
+    }
+
+    async fn list(&self, parent: &str) -> RepoResult<Vec<(Dimensions, ThumbnailFormat)>> {
+        let state = self.state.read().await;
+        let thumbnails = state
+            .entries
+            .get(parent)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .map(|e| {
+                        let size = Dimensions {
+                            height: e.height,
+                            width: e.width,
+                        };
+                        (size, e.format)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(thumbnails)
+    }
+
+    async fn delete(&self, parent: &str, size: &Dimensions) -> RepoResult<()> {
+        let _writer = self.writer.lock().await;
+
+        if self
+            .state
+            .read()
+            .await
+            .find_entry(parent, size.height, size.width)
+            .is_none()
+        {
+            return Ok(());
+        }
+        let record = IndexRecord::Delete {
+            parent: parent.to_string(),
+            height: size.height,
+            width: size.width,
+        };
+
+        self.append_record(record).await
+    }
+
+    async fn delete_parent(&self, parent: &str) -> RepoResult<()> {
+        let _writer = self.writer.lock().await;
+
+        if !self.state.read().await.entries.contains_key(parent) {
+            return Ok(());
+        }
+        let record = IndexRecord::DeleteParent {
+            parent: parent.to_string(),
+        };
+
+        self.append_record(record).await
+    }
+
+    async fn rename_parent(&self, src: &str, dst: &str) -> RepoResult<()> {
+        let _writer = self.writer.lock().await;
+
+        if !self.state.read().await.entries.contains_key(src) {
+            tracing::warn!("thumbnail parent {} doesn't exist", src);
+            return Ok(());
+        }
+        let record = IndexRecord::RenameParent {
+            src: src.to_string(),
+            dst: dst.to_string(),
+        };
+
+        self.append_record(record).await
+    }
+
+    async fn list_parents(&self) -> RepoResult<Vec<String>> {
+        let state = self.state.read().await;
+
+        Ok(state.entries.keys().cloned().collect())
+    }
+
+    async fn parent_size(&self, parent: &str) -> RepoResult<u64> {
+        let state = self.state.read().await;
+        let size = state
+            .entries
+            .get(parent)
+            .map(|entries| entries.iter().map(|e| e.length).sum())
+            .unwrap_or(0);
+
+        Ok(size)
+    }
+
+    async fn size(&self) -> RepoResult<u64> {
+        let state = self.state.read().await;
+
+        Ok(state.packs_size + state.index_size)
+    }
+
+    /// Copies all referenced thumbnails into new packs, replaces the index
+    /// with one that only contains the copied thumbnails and removes the old packs.
+    /// Thumbnails can be read and written while the packs are copied as the
+    /// store is only locked to take a snapshot and to swap in the new packs
+    async fn compact(&self) -> RepoResult<u64> {
+        let _compaction = self.compaction.lock().await;
+        let (snapshot, snapshot_index_size, old_packs_size, first_kept_pack) = {
+            let _writer = self.writer.lock().await;
+            let mut state = self.state.write().await;
+
+            if state.wasted_size() == 0 {
+                return Ok(0);
+            }
+            // thumbnails added during the compaction are written to a new pack that is kept
+            state.current_pack += 1;
+            state.current_pack_size = 0;
+
+            (
+                state.entries.clone(),
+                state.index_size,
+                state.packs_size,
+                state.current_pack,
+            )
+        };
+        let compacted = match self.copy_into_compaction_packs(&snapshot).await {
+            Ok(compacted) => compacted,
+            Err(e) => {
+                if let Err(e) = self.remove_tmp_files().await {
+                    tracing::warn!("failed to remove temporary compaction packs: {}", e);
+                }
+                return Err(e);
+            }
+        };
+        let writer = self.writer.lock().await;
+        let (current_pack, current_pack_size, packs_size) = {
+            let state = self.state.read().await;
+            (state.current_pack, state.current_pack_size, state.packs_size)
+        };
+        let mut new_state = PackState {
+            current_pack,
+            current_pack_size,
+            ..Default::default()
+        };
+
+        for (number, size) in compacted.pack_sizes.iter().enumerate() {
+            let pack = current_pack + 1 + number as u32;
+            fs::rename(self.compaction_pack_path(number), self.pack_path(pack)).await?;
+            new_state.current_pack = pack;
+            new_state.current_pack_size = *size;
+        }
+        let compacted_packs_size: u64 = compacted.pack_sizes.iter().sum();
+        let mut index = Vec::new();
+
+        for (parent, entry) in compacted.entries {
+            let record = IndexRecord::Add {
+                parent,
+                entry: PackEntry {
+                    pack: current_pack + 1 + entry.pack,
+                    ..entry
+                },
+            };
+            index.append(&mut encode_record(&record)?);
+            new_state.apply(record);
+        }
+        let compacted_index_size = index.len() as u64;
+        // records written during the compaction are replayed on top of the compacted ones
+        let tail = self.read_index_tail(snapshot_index_size).await?;
+        let (records, tail_length) = decode_records(&tail);
+        records.into_iter().for_each(|r| new_state.apply(r));
+        index.extend_from_slice(&tail[..tail_length]);
+
+        let tmp_index_path = self
+            .path
+            .join(format!("{}.{}", INDEX_FILE_NAME, TMP_FILE_EXTENSION));
+        let mut index_file = File::create(&tmp_index_path).await?;
+        index_file.write_all(&index).await?;
+        index_file.sync_all().await?;
+        fs::rename(&tmp_index_path, self.index_path()).await?;
+        new_state.index_size = index.len() as u64;
+        new_state.packs_size = packs_size - old_packs_size + compacted_packs_size;
+        *self.state.write().await = new_state;
+        drop(writer);
+
+        for number in self.pack_numbers().await? {
+            if number < first_kept_pack {
+                fs::remove_file(self.pack_path(number)).await?;
+            }
+        }
+        let freed = (old_packs_size + snapshot_index_size)
+            .saturating_sub(compacted_packs_size + compacted_index_size);
+
+        Ok(freed)
+    }
+
+    async fn wasted_size(&self) -> RepoResult<u64> {
+        let state = self.state.read().await;
+
+        Ok(state.wasted_size())
+    }
+}
+
+impl PackState {
+    fn apply(&mut self, record: IndexRecord) {
+        match record {
+            IndexRecord::Add { parent, entry } => {
+                self.remove_entry(&parent, entry.height, entry.width);
+                self.live_size += entry.length;
+                self.entries.entry(parent).or_default().push(entry);
+            }
+            IndexRecord::Delete {
+                parent,
+                height,
+                width,
+            } => self.remove_entry(&parent, height, width),
+            IndexRecord::DeleteParent { parent } => self.remove_parent(&parent),
+            IndexRecord::RenameParent { src, dst } => {
+                if let Some(entries) = self.entries.remove(&src) {
+                    self.remove_parent(&dst);
+                    self.entries.insert(dst, entries);
+                }
+            }
+        }
+    }
+
+    fn find_entry(&self, parent: &str, height: u32, width: u32) -> Option<&PackEntry> {
+        self.entries
+            .get(parent)?
+            .iter()
+            .find(|e| e.height == height && e.width == width)
+    }
+
+    fn remove_entry(&mut self, parent: &str, height: u32, width: u32) {
+        if let Some(entries) = self.entries.get_mut(parent) {
+            if let Some(index) = entries
+                .iter()
+                .position(|e| e.height == height && e.width == width)
+            {
+                self.live_size -= entries.remove(index).length;
+            }
+            if entries.is_empty() {
+                self.entries.remove(parent);
+            }
+        }
+    }
+
+    fn remove_parent(&mut self, parent: &str) {
+        if let Some(entries) = self.entries.remove(parent) {
+            self.live_size -= entries.iter().map(|e| e.length).sum::<u64>();
+        }
+    }
+
+    fn wasted_size(&self) -> u64 {
+        self.packs_size.saturating_sub(self.live_size)
+    }
+}
+
+/// Returns the number of a pack file
+fn pack_number(path: &Path) -> Option<u32> {
+    if path.extension()?.to_str()? != PACK_FILE_EXTENSION {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Encodes an index record prefixed with its length
+fn encode_record(record: &IndexRecord) -> RepoResult<Vec<u8>> {
+    let record = bincode::serialize(record)?;
+    let mut data = (record.len() as u32).to_le_bytes().to_vec();
+    data.extend(record);
+
+    Ok(data)
+}
+
+/// Decodes all complete records and returns them with the length of the valid data
+fn decode_records(data: &[u8]) -> (Vec<IndexRecord>, usize) {
+    let mut records = Vec::new();
+    let mut offset = 0;
+
+    while offset + 4 <= data.len() {
+        let length = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
+        let end = offset + 4 + length;
+
+        if end > data.len() {
+            break;
+        }
+        match bincode::deserialize(&data[offset + 4..end]) {
+            Ok(record) => records.push(record),
+            Err(_) => break,
+        }
+        offset = end;
+    }
+
+    (records, offset)
+}
+
+async fn finish_pack(writer: &mut BufWriter<File>) -> RepoResult<()> {
+    writer.flush().await?;
+    writer.get_ref().sync_all().await?;
+
+    Ok(())
+}
@@ -11,6 +11,8 @@ pub mod content_descriptor;
 pub mod context;
 pub mod error;
 pub mod fs;
+pub mod media_metadata;
+pub mod perceptual_hash;
 pub mod settings;
 pub mod tracing_layer_list;
 pub mod type_keys;
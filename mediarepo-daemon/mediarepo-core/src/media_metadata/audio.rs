@@ -0,0 +1,81 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use symphonia::core::units::TimeBase;
+
+use crate::error::{RepoError, RepoResult};
+use crate::media_metadata::MediaMetadata;
+
+/// A seekable media source of a known size so that formats
+/// can skip the parts of the file that aren't needed
+struct SeekableSource<R> {
+    reader: R,
+    size: u64,
+}
+
+impl<R: Read> Read for SeekableSource<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+impl<R: Seek> Seek for SeekableSource<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.reader.seek(pos)
+    }
+}
+
+impl<R: Read + Seek + Send + Sync> MediaSource for SeekableSource<R> {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        Some(self.size)
+    }
+}
+
+/// Reads the duration and codec of the default track of an audio file
+pub(crate) fn read_audio_metadata<R: Read + Seek + Send + Sync + 'static>(
+    reader: R,
+    size: u64,
+    mime_type: &str,
+) -> RepoResult<MediaMetadata> {
+    let source = MediaSourceStream::new(
+        Box::new(SeekableSource { reader, size }),
+        Default::default(),
+    );
+    let mut hint = Hint::new();
+    hint.mime_type(mime_type);
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        source,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let track = probed
+        .format
+        .default_track()
+        .ok_or_else(|| RepoError::from("the file contains no audio track"))?;
+    let params = &track.codec_params;
+
+    let time_base = params
+        .time_base
+        .or_else(|| params.sample_rate.map(|rate| TimeBase::new(1, rate)));
+    let duration = time_base.zip(params.n_frames).map(|(time_base, frames)| {
+        let time = time_base.calc_time(frames);
+        time.seconds * 1000 + (time.frac * 1000.0) as u64
+    });
+    let audio_codec = symphonia::default::get_codecs()
+        .get_codec(params.codec)
+        .map(|codec| codec.short_name.to_string());
+
+    Ok(MediaMetadata {
+        duration,
+        audio_codec,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,206 @@
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use exif::{In, Tag, Value};
+use id3::TagLike;
+
+use crate::error::RepoResult;
+use crate::settings::TagMapping;
+
+/// The number of bytes at the start of a file that are searched for exif and xmp data
+const HEAD_WINDOW_SIZE: u64 = 4 * 1024 * 1024;
+/// The number of bytes at the end of a file that are searched for xmp data
+/// as some containers store their metadata after the media data
+const TAIL_WINDOW_SIZE: u64 = 1024 * 1024;
+const XMP_FIELD_PREFIX: &str = "xmp:";
+const XMP_START: &[u8] = b"<x:xmpmeta";
+const XMP_END: &[u8] = b"</x:xmpmeta>";
+const RDF_NAMESPACE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const ID3_MIME_TYPES: [&str; 7] = [
+    "audio/mpeg",
+    "audio/mp3",
+    "audio/wav",
+    "audio/x-wav",
+    "audio/wave",
+    "audio/aiff",
+    "audio/x-aiff",
+];
+const EXIF_DATE_TAGS: [Tag; 3] = [Tag::DateTime, Tag::DateTimeOriginal, Tag::DateTimeDigitized];
+
+/// Returns if files of the given mime type can contain embedded fields that are mapped.
+/// Videos can only contain xmp fields so they are skipped unless an xmp field is mapped
+pub fn supports_embedded_fields(mime_type: &str, mappings: &[TagMapping]) -> bool {
+    let mime_type = mime_type.to_lowercase();
+
+    if mime_type.starts_with("video/") {
+        mappings
+            .iter()
+            .any(|m| m.field.starts_with(XMP_FIELD_PREFIX))
+    } else {
+        mime_type.starts_with("image/") || is_id3_mime(&mime_type)
+    }
+}
+
+/// Extracts the embedded metadata fields of a file as pairs of field and value.
+/// Fields are prefixed with their source, e.g. `exif:Model`, `xmp:subject` or `id3:TPE1`.
+/// Dates are shortened to the day in the format `YYYY-MM-DD`.
+/// Only the regions of the file that contain metadata are read. This blocks on the
+/// reader so it should be called outside of async code
+pub fn extract_embedded_fields<R: Read + Seek>(
+    mut reader: R,
+    mime_type: &str,
+) -> RepoResult<Vec<(String, String)>> {
+    let mime_type = mime_type.to_lowercase();
+    let file_size = reader.seek(SeekFrom::End(0))?;
+    reader.rewind()?;
+    let mut head = Vec::new();
+    reader
+        .by_ref()
+        .take(HEAD_WINDOW_SIZE)
+        .read_to_end(&mut head)?;
+    let mut fields = Vec::new();
+
+    if mime_type.starts_with("image/") {
+        read_exif_fields(&head, &mut fields);
+    }
+    if is_id3_mime(&mime_type) {
+        reader.rewind()?;
+        read_id3_fields(&mut reader, &mut fields);
+    }
+    let mut packet = find_xmp_packet(&head);
+
+    if packet.is_none() && file_size > head.len() as u64 {
+        let tail_start = file_size
+            .saturating_sub(TAIL_WINDOW_SIZE)
+            .max(head.len() as u64);
+        let mut tail = Vec::new();
+        reader.seek(SeekFrom::Start(tail_start))?;
+        reader.take(TAIL_WINDOW_SIZE).read_to_end(&mut tail)?;
+        packet = find_xmp_packet(&tail);
+    }
+    if let Some(packet) = packet {
+        read_xmp_fields(&packet, &mut fields);
+    }
+    fields.retain(|(_, value)| !value.is_empty());
+
+    Ok(fields)
+}
+
+fn is_id3_mime(mime_type: &str) -> bool {
+    ID3_MIME_TYPES.contains(&mime_type)
+}
+
+fn read_exif_fields(bytes: &[u8], fields: &mut Vec<(String, String)>) {
+    let exif = match exif::Reader::new().read_from_container(&mut Cursor::new(bytes)) {
+        Ok(exif) => exif,
+        Err(_) => return,
+    };
+
+    for field in exif.fields().filter(|f| f.ifd_num == In::PRIMARY) {
+        let value = match &field.value {
+            Value::Ascii(lines) if EXIF_DATE_TAGS.contains(&field.tag) => lines
+                .first()
+                .and_then(|line| exif::DateTime::from_ascii(line).ok())
+                .map(|d| format!("{:04}-{:02}-{:02}", d.year, d.month, d.day)),
+            Value::Ascii(lines) => Some(
+                lines
+                    .iter()
+                    .map(|line| String::from_utf8_lossy(line).trim().to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            ),
+            _ => None,
+        };
+        if let Some(value) = value {
+            fields.push((format!("exif:{}", field.tag), value));
+        }
+    }
+}
+
+fn read_id3_fields<R: Read + Seek>(reader: R, fields: &mut Vec<(String, String)>) {
+    let tag = match id3::Tag::read_from2(reader) {
+        Ok(tag) => tag,
+        Err(_) => return,
+    };
+
+    for frame in tag.frames() {
+        if frame.id() == "TCON" {
+            continue;
+        }
+        if let Some(values) = frame.content().text_values() {
+            fields.extend(values.map(|v| (format!("id3:{}", frame.id()), v.trim().to_string())));
+        } else if let Some(text) = frame.content().extended_text() {
+            fields.push((
+                format!("id3:TXXX:{}", text.description),
+                text.value.trim().to_string(),
+            ));
+        }
+    }
+    // genres can reference the id3v1 genre list by index
+    if let Some(genres) = tag.genre_parsed() {
+        fields.extend(
+            genres
+                .split('\0')
+                .map(|genre| (String::from("id3:TCON"), genre.trim().to_string())),
+        );
+    }
+}
+
+fn read_xmp_fields(packet: &str, fields: &mut Vec<(String, String)>) {
+    let document = match roxmltree::Document::parse(packet) {
+        Ok(document) => document,
+        Err(_) => return,
+    };
+    let descriptions = document
+        .descendants()
+        .filter(|n| n.has_tag_name((RDF_NAMESPACE, "Description")));
+
+    for description in descriptions {
+        for attribute in description.attributes() {
+            if attribute.namespace().is_some() && attribute.namespace() != Some(RDF_NAMESPACE) {
+                fields.push(xmp_field(attribute.name(), attribute.value()));
+            }
+        }
+        for property in description.children().filter(|n| n.is_element()) {
+            let items: Vec<&str> = property
+                .descendants()
+                .filter(|n| n.has_tag_name((RDF_NAMESPACE, "li")))
+                .filter_map(|n| n.text())
+                .collect();
+
+            if !items.is_empty() {
+                fields.extend(
+                    items
+                        .into_iter()
+                        .map(|item| xmp_field(property.tag_name().name(), item)),
+                );
+            } else if let Some(text) = property.text() {
+                fields.push(xmp_field(property.tag_name().name(), text));
+            }
+        }
+    }
+}
+
+fn xmp_field(name: &str, value: &str) -> (String, String) {
+    let value = value.trim();
+    let value = if name.ends_with("Date") {
+        value.split('T').next().unwrap_or(value)
+    } else {
+        value
+    };
+
+    (format!("xmp:{}", name), value.to_string())
+}
+
+/// Searches for the serialized xmp packet that most containers embed as plain text
+fn find_xmp_packet(bytes: &[u8]) -> Option<String> {
+    let start = find_subslice(bytes, XMP_START)?;
+    let end = start + find_subslice(&bytes[start..], XMP_END)? + XMP_END.len();
+
+    String::from_utf8(bytes[start..end].to_vec()).ok()
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
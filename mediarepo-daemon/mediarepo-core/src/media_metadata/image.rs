@@ -0,0 +1,128 @@
+use std::io::{BufRead, Cursor, Read, Seek};
+
+use exif::{Exif, Field, In, Tag, Value};
+use image::io::Reader;
+
+use crate::error::RepoResult;
+use crate::media_metadata::MediaMetadata;
+
+/// The number of bytes at the start of an image that are searched for exif data.
+/// Some containers would otherwise be read completely when they don't contain any
+const EXIF_WINDOW_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Reads the dimensions and exif data of an image
+pub(crate) fn read_image_metadata<R: BufRead + Seek>(reader: &mut R) -> RepoResult<MediaMetadata> {
+    let (width, height) = Reader::new(&mut *reader)
+        .with_guessed_format()?
+        .into_dimensions()?;
+    let mut metadata = MediaMetadata {
+        width: Some(width),
+        height: Some(height),
+        ..Default::default()
+    };
+    reader.rewind()?;
+    let mut head = Vec::new();
+    reader
+        .by_ref()
+        .take(EXIF_WINDOW_SIZE)
+        .read_to_end(&mut head)?;
+
+    // most images don't contain any exif data so failing to read it is expected
+    if let Ok(exif) = exif::Reader::new().read_from_container(&mut Cursor::new(head)) {
+        metadata.orientation = exif
+            .get_field(Tag::Orientation, In::PRIMARY)
+            .and_then(|field| field.value.get_uint(0))
+            .filter(|orientation| (1..=8).contains(orientation))
+            .map(|orientation| orientation as u8);
+        metadata.exif = exif
+            .fields()
+            .filter(|field| field.ifd_num == In::PRIMARY && field.tag != Tag::MakerNote)
+            .map(|field| (field.tag.to_string(), field_value(field, &exif)))
+            .collect();
+    }
+
+    Ok(metadata)
+}
+
+/// Returns the text of ascii fields and the display value with its unit for all others
+fn field_value(field: &Field, exif: &Exif) -> String {
+    match &field.value {
+        Value::Ascii(lines) => lines
+            .iter()
+            .map(|line| String::from_utf8_lossy(line).trim().to_string())
+            .collect::<Vec<String>>()
+            .join(", "),
+        _ => field.display_value().with_unit(exif).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, ImageOutputFormat};
+
+    use super::*;
+
+    fn encode(format: ImageOutputFormat) -> Vec<u8> {
+        let mut bytes = Cursor::new(Vec::new());
+        DynamicImage::new_rgb8(12, 8)
+            .write_to(&mut bytes, format)
+            .unwrap();
+        bytes.into_inner()
+    }
+
+    /// Inserts an exif segment with the given orientation after the start of a jpeg
+    fn with_exif_orientation(jpeg: Vec<u8>, orientation: u16) -> Vec<u8> {
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend_from_slice(&8u32.to_le_bytes());
+        tiff.extend_from_slice(&1u16.to_le_bytes());
+        tiff.extend_from_slice(&0x0112u16.to_le_bytes());
+        tiff.extend_from_slice(&3u16.to_le_bytes());
+        tiff.extend_from_slice(&1u32.to_le_bytes());
+        tiff.extend_from_slice(&orientation.to_le_bytes());
+        tiff.extend_from_slice(&[0, 0]);
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        let payload = [&b"Exif\0\0"[..], &tiff].concat();
+
+        let mut image = jpeg[..2].to_vec();
+        image.extend_from_slice(&[0xFF, 0xE1]);
+        image.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+        image.extend_from_slice(&payload);
+        image.extend_from_slice(&jpeg[2..]);
+        image
+    }
+
+    #[test]
+    fn it_reads_the_dimensions_of_an_image_without_exif() {
+        let metadata =
+            read_image_metadata(&mut Cursor::new(encode(ImageOutputFormat::Png))).unwrap();
+
+        assert_eq!(metadata.width, Some(12));
+        assert_eq!(metadata.height, Some(8));
+        assert_eq!(metadata.orientation, None);
+        assert!(metadata.exif.is_empty());
+    }
+
+    #[test]
+    fn it_reads_the_exif_orientation_of_a_jpeg() {
+        let jpeg = with_exif_orientation(encode(ImageOutputFormat::Jpeg(90)), 6);
+        let metadata = read_image_metadata(&mut Cursor::new(jpeg)).unwrap();
+
+        assert_eq!(metadata.width, Some(12));
+        assert_eq!(metadata.height, Some(8));
+        assert_eq!(metadata.orientation, Some(6));
+        assert!(metadata.exif.iter().any(|(tag, _)| tag == "Orientation"));
+    }
+
+    #[test]
+    fn it_ignores_invalid_orientations() {
+        let jpeg = with_exif_orientation(encode(ImageOutputFormat::Jpeg(90)), 9);
+        let metadata = read_image_metadata(&mut Cursor::new(jpeg)).unwrap();
+
+        assert_eq!(metadata.orientation, None);
+    }
+
+    #[test]
+    fn it_fails_for_data_that_isnt_an_image() {
+        assert!(read_image_metadata(&mut Cursor::new(b"not an image".to_vec())).is_err());
+    }
+}
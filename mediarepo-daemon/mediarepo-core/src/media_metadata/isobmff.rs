@@ -0,0 +1,298 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::error::{RepoError, RepoResult};
+use crate::media_metadata::MediaMetadata;
+
+/// The largest movie box that is read into memory
+const MAX_MOVIE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// A box of an ISO base media file (mp4, mov) with its type and content
+struct Atom<'a> {
+    kind: &'a [u8],
+    data: &'a [u8],
+}
+
+/// Iterates over the boxes in the content of a parent box
+struct Atoms<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Atoms<'a> {
+    type Item = Atom<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let size = read_u32(self.data, 0)? as u64;
+        let kind = self.data.get(4..8)?;
+        let (header_size, size) = match size {
+            0 => (8, self.data.len() as u64),
+            1 => (16, read_u64(self.data, 8)?),
+            size => (8, size),
+        };
+        if size < header_size || size > self.data.len() as u64 {
+            return None;
+        }
+        let data = &self.data[header_size as usize..size as usize];
+        self.data = &self.data[size as usize..];
+
+        Some(Atom { kind, data })
+    }
+}
+
+/// Reads the dimensions, duration and codecs of an ISO base media file.
+/// Only the movie box is read as it may be located at the end of the file
+pub(crate) fn read_isobmff_metadata<R: Read + Seek>(reader: &mut R) -> RepoResult<MediaMetadata> {
+    let movie =
+        read_movie(reader)?.ok_or_else(|| RepoError::from("the file contains no movie box"))?;
+    let mut metadata = MediaMetadata {
+        duration: find_atom(&movie, b"mvhd").and_then(read_header_duration),
+        ..Default::default()
+    };
+
+    for track in atoms(&movie).filter(|a| a.kind == b"trak") {
+        read_track(track.data, &mut metadata);
+    }
+
+    Ok(metadata)
+}
+
+/// Seeks over the top level boxes and returns the content of the movie box
+fn read_movie<R: Read + Seek>(reader: &mut R) -> RepoResult<Option<Vec<u8>>> {
+    let file_size = reader.seek(SeekFrom::End(0))?;
+    let mut offset = 0;
+
+    while file_size.saturating_sub(offset) >= 8 {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut header = [0u8; 16];
+        reader.read_exact(&mut header[..8])?;
+        let (header_size, size) = match read_u32(&header, 0) {
+            Some(0) => (8, file_size - offset),
+            Some(1) => {
+                reader.read_exact(&mut header[8..])?;
+                (16, read_u64(&header, 8).unwrap_or(0))
+            }
+            size => (8, size.unwrap_or(0) as u64),
+        };
+        if size < header_size || size > file_size - offset {
+            return Ok(None);
+        }
+        if &header[4..8] == b"moov" {
+            let content_size = size - header_size;
+            if content_size > MAX_MOVIE_SIZE {
+                return Err(RepoError::from("the movie box is too large"));
+            }
+            let mut movie = vec![0u8; content_size as usize];
+            reader.read_exact(&mut movie)?;
+
+            return Ok(Some(movie));
+        }
+        offset += size;
+    }
+
+    Ok(None)
+}
+
+/// Reads the codec of a track and the dimensions of the first video track
+fn read_track(track: &[u8], metadata: &mut MediaMetadata) -> Option<()> {
+    let media = find_atom(track, b"mdia")?;
+    let handler = find_atom(media, b"hdlr")?.get(8..12)?;
+    let sample_table = find_atom(find_atom(media, b"minf")?, b"stbl")?;
+    let codec = find_atom(sample_table, b"stsd")
+        .and_then(|description| description.get(12..16))
+        .map(codec_name);
+
+    match handler {
+        b"vide" if metadata.video_codec.is_none() => {
+            metadata.video_codec = codec;
+            metadata.frame_count = find_atom(sample_table, b"stsz")
+                .and_then(|sizes| read_u32(sizes, 8))
+                .map(u64::from);
+            read_track_header(find_atom(track, b"tkhd")?, metadata);
+        }
+        b"soun" if metadata.audio_codec.is_none() => metadata.audio_codec = codec,
+        _ => {}
+    }
+
+    Some(())
+}
+
+/// Reads the duration in milliseconds from a movie header
+fn read_header_duration(header: &[u8]) -> Option<u64> {
+    let (timescale, duration) = if header.first()? == &1 {
+        (read_u32(header, 20)?, read_u64(header, 24)?)
+    } else {
+        (read_u32(header, 12)?, read_u32(header, 16)? as u64)
+    };
+
+    if timescale == 0 {
+        None
+    } else {
+        Some((duration as u128 * 1000 / timescale as u128) as u64)
+    }
+}
+
+/// Reads the dimensions and the rotation of a video track
+fn read_track_header(header: &[u8], metadata: &mut MediaMetadata) -> Option<()> {
+    let matrix_offset = if header.first()? == &1 { 52 } else { 40 };
+    let matrix_entry = |index: usize| read_u32(header, matrix_offset + index * 4).map(|v| v as i32);
+
+    metadata.width = Some(read_u32(header, matrix_offset + 36)? >> 16);
+    metadata.height = Some(read_u32(header, matrix_offset + 40)? >> 16);
+    // the rotation is converted into the matching exif orientation
+    metadata.orientation = match (
+        matrix_entry(0)?.signum(),
+        matrix_entry(1)?.signum(),
+        matrix_entry(3)?.signum(),
+        matrix_entry(4)?.signum(),
+    ) {
+        (1, 0, 0, 1) => Some(1),
+        (0, 1, -1, 0) => Some(6),
+        (-1, 0, 0, -1) => Some(3),
+        (0, -1, 1, 0) => Some(8),
+        _ => None,
+    };
+
+    Some(())
+}
+
+fn codec_name(format: &[u8]) -> String {
+    let name = match format {
+        b"avc1" | b"avc3" => "h264",
+        b"hvc1" | b"hev1" => "hevc",
+        b"av01" => "av1",
+        b"vp08" => "vp8",
+        b"vp09" => "vp9",
+        b"mp4v" => "mpeg4",
+        b"mp4a" => "aac",
+        b"Opus" => "opus",
+        b"fLaC" => "flac",
+        b"ac-3" => "ac3",
+        b"ec-3" => "eac3",
+        b".mp3" => "mp3",
+        other => return String::from_utf8_lossy(other).trim().to_lowercase(),
+    };
+
+    name.to_string()
+}
+
+fn atoms(data: &[u8]) -> Atoms<'_> {
+    Atoms { data }
+}
+
+fn find_atom<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    atoms(data).find(|a| a.kind == kind).map(|a| a.data)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(((read_u32(data, offset)? as u64) << 32) | read_u32(data, offset + 4)? as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn atom(kind: &[u8; 4], content: &[u8]) -> Vec<u8> {
+        let mut atom = ((content.len() + 8) as u32).to_be_bytes().to_vec();
+        atom.extend_from_slice(kind);
+        atom.extend_from_slice(content);
+        atom
+    }
+
+    fn track(handler: &[u8; 4], codec: &[u8; 4], header: Option<Vec<u8>>) -> Vec<u8> {
+        let mut description = vec![0u8; 8];
+        description.extend_from_slice(&[0, 0, 0, 16]);
+        description.extend_from_slice(codec);
+        let mut sizes = vec![0u8; 8];
+        sizes.extend_from_slice(&120u32.to_be_bytes());
+        let sample_table = [atom(b"stsd", &description), atom(b"stsz", &sizes)].concat();
+        let mut handler_content = vec![0u8; 8];
+        handler_content.extend_from_slice(handler);
+        let media = [
+            atom(b"hdlr", &handler_content),
+            atom(b"minf", &atom(b"stbl", &sample_table)),
+        ]
+        .concat();
+
+        let mut track = header.map(|h| atom(b"tkhd", &h)).unwrap_or_default();
+        track.extend(atom(b"mdia", &media));
+        atom(b"trak", &track)
+    }
+
+    fn track_header(matrix: [i32; 9], width: u32, height: u32) -> Vec<u8> {
+        let mut header = vec![0u8; 40];
+        for entry in matrix {
+            header.extend_from_slice(&entry.to_be_bytes());
+        }
+        header.extend_from_slice(&(width << 16).to_be_bytes());
+        header.extend_from_slice(&(height << 16).to_be_bytes());
+        header
+    }
+
+    fn movie(tracks: &[Vec<u8>]) -> Vec<u8> {
+        let mut header = vec![0u8; 12];
+        header.extend_from_slice(&1000u32.to_be_bytes());
+        header.extend_from_slice(&4500u32.to_be_bytes());
+        let mut movie = atom(b"mvhd", &header);
+        movie.extend(tracks.concat());
+        atom(b"moov", &movie)
+    }
+
+    #[test]
+    fn it_reads_the_movie_box_at_the_end_of_the_file() {
+        let matrix = [0x10000, 0, 0, 0, 0x10000, 0, 0, 0, 0x40000000];
+        let file = [
+            atom(b"ftyp", b"isom"),
+            atom(b"mdat", &[0u8; 4096]),
+            movie(&[
+                track(b"soun", b"mp4a", None),
+                track(b"vide", b"avc1", Some(track_header(matrix, 1920, 1080))),
+            ]),
+        ]
+        .concat();
+        let metadata = read_isobmff_metadata(&mut Cursor::new(file)).unwrap();
+
+        assert_eq!(metadata.duration, Some(4500));
+        assert_eq!(metadata.width, Some(1920));
+        assert_eq!(metadata.height, Some(1080));
+        assert_eq!(metadata.frame_count, Some(120));
+        assert_eq!(metadata.video_codec.as_deref(), Some("h264"));
+        assert_eq!(metadata.audio_codec.as_deref(), Some("aac"));
+        assert_eq!(metadata.orientation, Some(1));
+    }
+
+    #[test]
+    fn it_converts_the_rotation_into_an_orientation() {
+        let matrix = [0, 0x10000, 0, -0x10000, 0, 0, 0, 0, 0x40000000];
+        let file = movie(&[track(
+            b"vide",
+            b"hvc1",
+            Some(track_header(matrix, 1280, 720)),
+        )]);
+        let metadata = read_isobmff_metadata(&mut Cursor::new(file)).unwrap();
+
+        assert_eq!(metadata.video_codec.as_deref(), Some("hevc"));
+        assert_eq!(metadata.orientation, Some(6));
+    }
+
+    #[test]
+    fn it_fails_without_a_movie_box() {
+        let file = [atom(b"ftyp", b"isom"), atom(b"mdat", &[0u8; 64])].concat();
+
+        assert!(read_isobmff_metadata(&mut Cursor::new(file)).is_err());
+    }
+
+    #[test]
+    fn it_stops_at_boxes_that_exceed_the_file() {
+        let mut file = atom(b"ftyp", b"isom");
+        file.extend_from_slice(&u32::MAX.to_be_bytes());
+        file.extend_from_slice(b"mdat");
+
+        assert!(read_isobmff_metadata(&mut Cursor::new(file)).is_err());
+    }
+}
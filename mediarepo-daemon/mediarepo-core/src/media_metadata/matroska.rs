@@ -0,0 +1,363 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::error::{RepoError, RepoResult};
+use crate::media_metadata::MediaMetadata;
+
+const SEGMENT_ID: u64 = 0x18538067;
+const INFO_ID: u64 = 0x1549A966;
+const TIMESTAMP_SCALE_ID: u64 = 0x2AD7B1;
+const DURATION_ID: u64 = 0x4489;
+const TRACKS_ID: u64 = 0x1654AE6B;
+const TRACK_ENTRY_ID: u64 = 0xAE;
+const TRACK_TYPE_ID: u64 = 0x83;
+const CODEC_ID_ID: u64 = 0x86;
+const DEFAULT_DURATION_ID: u64 = 0x23E383;
+const VIDEO_ID: u64 = 0xE0;
+const PIXEL_WIDTH_ID: u64 = 0xB0;
+const PIXEL_HEIGHT_ID: u64 = 0xBA;
+const CLUSTER_ID: u64 = 0x1F43B675;
+
+const VIDEO_TRACK_TYPE: u64 = 1;
+const AUDIO_TRACK_TYPE: u64 = 2;
+const DEFAULT_TIMESTAMP_SCALE: u64 = 1_000_000;
+/// The largest segment info or tracks element that is read into memory
+const MAX_ELEMENT_SIZE: u64 = 16 * 1024 * 1024;
+/// The length of the longest element id together with the longest element size
+const MAX_HEADER_SIZE: u64 = 12;
+
+/// Iterates over the EBML elements in the content of a parent element
+struct Elements<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Elements<'a> {
+    type Item = (u64, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, id_length) = read_vint(self.data, true)?;
+        let (size, size_length) = read_vint(self.data.get(id_length..)?, false)?;
+        let start = id_length + size_length;
+        let unknown_size = size == (1 << (7 * size_length)) - 1;
+        // elements with an unknown size extend to the end of their parent
+        let end = if unknown_size || start as u64 + size > self.data.len() as u64 {
+            self.data.len()
+        } else {
+            start + size as usize
+        };
+        let content = &self.data[start..end];
+        self.data = &self.data[end..];
+
+        Some((id, content))
+    }
+}
+
+/// Reads the dimensions, duration and codecs of a matroska or webm file.
+/// Only the segment info and tracks are read which are stored before the first cluster
+pub(crate) fn read_matroska_metadata<R: Read + Seek>(reader: &mut R) -> RepoResult<MediaMetadata> {
+    let mut offset = 0;
+    let mut segment_offset = None;
+
+    while let Some((id, header_size, size)) = read_element_header(reader, offset)? {
+        if id == SEGMENT_ID {
+            segment_offset = Some(offset + header_size);
+            break;
+        }
+        match size.and_then(|size| offset.checked_add(header_size + size)) {
+            Some(next_offset) => offset = next_offset,
+            None => break,
+        }
+    }
+    let mut offset =
+        segment_offset.ok_or_else(|| RepoError::from("the file contains no matroska segment"))?;
+    let mut metadata = MediaMetadata::default();
+    let mut default_frame_duration = None;
+
+    while let Some((id, header_size, size)) = read_element_header(reader, offset)? {
+        match id {
+            INFO_ID => {
+                metadata.duration = read_duration(&read_content(reader, size)?);
+            }
+            TRACKS_ID => {
+                let tracks = read_content(reader, size)?;
+                for track in elements(&tracks).filter(|(id, _)| *id == TRACK_ENTRY_ID) {
+                    if let Some(duration) = read_track(track.1, &mut metadata) {
+                        default_frame_duration = Some(duration);
+                    }
+                }
+            }
+            CLUSTER_ID => break,
+            _ => {}
+        }
+        match size.and_then(|size| offset.checked_add(header_size + size)) {
+            Some(next_offset) => offset = next_offset,
+            None => break,
+        }
+    }
+    if let (Some(duration), Some(frame_duration)) = (metadata.duration, default_frame_duration) {
+        metadata.frame_count = duration
+            .checked_mul(1_000_000)
+            .and_then(|duration| duration.checked_div(frame_duration));
+    }
+
+    Ok(metadata)
+}
+
+/// Reads the header of the element at the offset and returns its id, the length of the
+/// header and the size of its content if it's known. The reader is left at the content
+fn read_element_header<R: Read + Seek>(
+    reader: &mut R,
+    offset: u64,
+) -> RepoResult<Option<(u64, u64, Option<u64>)>> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut header = Vec::new();
+    reader
+        .by_ref()
+        .take(MAX_HEADER_SIZE)
+        .read_to_end(&mut header)?;
+
+    let (id, id_length) = match read_vint(&header, true) {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+    let (size, size_length) = match header.get(id_length..).and_then(|h| read_vint(h, false)) {
+        Some(size) => size,
+        None => return Ok(None),
+    };
+    let header_size = (id_length + size_length) as u64;
+    let unknown_size = size == (1 << (7 * size_length)) - 1;
+    reader.seek(SeekFrom::Start(offset + header_size))?;
+
+    Ok(Some((
+        id,
+        header_size,
+        if unknown_size { None } else { Some(size) },
+    )))
+}
+
+/// Reads the content of an element up to the maximum element size
+fn read_content<R: Read>(reader: &mut R, size: Option<u64>) -> RepoResult<Vec<u8>> {
+    let mut content = Vec::new();
+    reader
+        .take(size.unwrap_or(MAX_ELEMENT_SIZE).min(MAX_ELEMENT_SIZE))
+        .read_to_end(&mut content)?;
+
+    Ok(content)
+}
+
+/// Reads the duration in milliseconds from the segment info
+fn read_duration(info: &[u8]) -> Option<u64> {
+    let scale = find_element(info, TIMESTAMP_SCALE_ID)
+        .and_then(read_uint)
+        .unwrap_or(DEFAULT_TIMESTAMP_SCALE);
+    let duration = read_float(find_element(info, DURATION_ID)?)? * scale as f64 / 1_000_000.0;
+
+    // durations that don't fit into the integer would be silently clamped by the cast
+    if duration.is_finite() && duration >= 0.0 && duration < u64::MAX as f64 {
+        Some(duration as u64)
+    } else {
+        None
+    }
+}
+
+/// Reads the codec of a track and the dimensions of the first video track.
+/// Returns the default frame duration in nanoseconds of the video track
+fn read_track(track: &[u8], metadata: &mut MediaMetadata) -> Option<u64> {
+    let track_type = find_element(track, TRACK_TYPE_ID).and_then(read_uint)?;
+    let codec = find_element(track, CODEC_ID_ID).map(codec_name);
+
+    match track_type {
+        VIDEO_TRACK_TYPE if metadata.video_codec.is_none() => {
+            metadata.video_codec = codec;
+            if let Some(video) = find_element(track, VIDEO_ID) {
+                metadata.width = find_element(video, PIXEL_WIDTH_ID)
+                    .and_then(read_uint)
+                    .map(|w| w as u32);
+                metadata.height = find_element(video, PIXEL_HEIGHT_ID)
+                    .and_then(read_uint)
+                    .map(|h| h as u32);
+            }
+            find_element(track, DEFAULT_DURATION_ID)
+                .and_then(read_uint)
+                .filter(|d| *d > 0)
+        }
+        AUDIO_TRACK_TYPE if metadata.audio_codec.is_none() => {
+            metadata.audio_codec = codec;
+            None
+        }
+        _ => None,
+    }
+}
+
+fn codec_name(codec_id: &[u8]) -> String {
+    let codec_id = String::from_utf8_lossy(codec_id);
+    let codec_id = codec_id.trim_end_matches('\0');
+    let name = match codec_id {
+        "V_MPEG4/ISO/AVC" => "h264",
+        "V_MPEGH/ISO/HEVC" => "hevc",
+        "V_MPEG4/ISO/SP" | "V_MPEG4/ISO/ASP" | "V_MPEG4/ISO/AP" => "mpeg4",
+        "A_MPEG/L3" => "mp3",
+        id if id.starts_with("A_AAC") => "aac",
+        id => {
+            let id = id
+                .strip_prefix("V_")
+                .or_else(|| id.strip_prefix("A_"))
+                .unwrap_or(id);
+            return id.to_lowercase();
+        }
+    };
+
+    name.to_string()
+}
+
+fn elements(data: &[u8]) -> Elements<'_> {
+    Elements { data }
+}
+
+fn find_element(data: &[u8], id: u64) -> Option<&[u8]> {
+    elements(data)
+        .find(|(element_id, _)| *element_id == id)
+        .map(|(_, content)| content)
+}
+
+/// Reads a variable length integer and returns it with its length in bytes.
+/// Element ids keep their length marker while sizes don't
+fn read_vint(data: &[u8], keep_marker: bool) -> Option<(u64, usize)> {
+    let first = *data.first()?;
+    if first == 0 {
+        return None;
+    }
+    let length = first.leading_zeros() as usize + 1;
+    let bytes = data.get(..length)?;
+    let first = if keep_marker {
+        first as u64
+    } else {
+        first as u64 & (0xFF >> length)
+    };
+    let value = bytes[1..]
+        .iter()
+        .fold(first, |value, byte| (value << 8) | *byte as u64);
+
+    Some((value, length))
+}
+
+fn read_uint(data: &[u8]) -> Option<u64> {
+    if data.len() > 8 {
+        return None;
+    }
+    Some(
+        data.iter()
+            .fold(0, |value, byte| (value << 8) | *byte as u64),
+    )
+}
+
+fn read_float(data: &[u8]) -> Option<f64> {
+    match data.len() {
+        4 => Some(f32::from_be_bytes([data[0], data[1], data[2], data[3]]) as f64),
+        8 => {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(data);
+            Some(f64::from_be_bytes(bytes))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const EBML_HEADER_ID: &[u8] = &[0x1A, 0x45, 0xDF, 0xA3];
+    const UNKNOWN_SIZE: [u8; 8] = [0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+
+    fn element(id: &[u8], content: &[u8]) -> Vec<u8> {
+        let mut size = (content.len() as u64).to_be_bytes();
+        size[0] = 0x01;
+        [id, &size[..], content].concat()
+    }
+
+    fn unknown_size_element(id: &[u8], content: &[u8]) -> Vec<u8> {
+        [id, &UNKNOWN_SIZE[..], content].concat()
+    }
+
+    fn uint_element(id: &[u8], value: u64) -> Vec<u8> {
+        element(id, &value.to_be_bytes())
+    }
+
+    fn info(duration: f64) -> Vec<u8> {
+        let content = [
+            uint_element(&[0x2A, 0xD7, 0xB1], 1_000_000),
+            element(&[0x44, 0x89], &duration.to_be_bytes()),
+        ]
+        .concat();
+        element(&[0x15, 0x49, 0xA9, 0x66], &content)
+    }
+
+    fn tracks(frame_duration: u64) -> Vec<u8> {
+        let video = [
+            uint_element(&[0x83], VIDEO_TRACK_TYPE),
+            element(&[0x86], b"V_VP9"),
+            uint_element(&[0x23, 0xE3, 0x83], frame_duration),
+            element(
+                &[0xE0],
+                &[uint_element(&[0xB0], 640), uint_element(&[0xBA], 480)].concat(),
+            ),
+        ]
+        .concat();
+        let audio = [
+            uint_element(&[0x83], AUDIO_TRACK_TYPE),
+            element(&[0x86], b"A_OPUS"),
+        ]
+        .concat();
+        let entries = [element(&[0xAE], &video), element(&[0xAE], &audio)].concat();
+        element(&[0x16, 0x54, 0xAE, 0x6B], &entries)
+    }
+
+    fn file(segment_content: &[u8]) -> Vec<u8> {
+        [
+            element(EBML_HEADER_ID, &uint_element(&[0x42, 0x86], 1)),
+            unknown_size_element(&[0x18, 0x53, 0x80, 0x67], segment_content),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn it_reads_the_info_and_tracks_of_a_segment() {
+        let cluster = unknown_size_element(&[0x1F, 0x43, 0xB6, 0x75], &[0u8; 256]);
+        let segment = [info(2000.0), tracks(40_000_000), cluster].concat();
+        let metadata = read_matroska_metadata(&mut Cursor::new(file(&segment))).unwrap();
+
+        assert_eq!(metadata.duration, Some(2000));
+        assert_eq!(metadata.width, Some(640));
+        assert_eq!(metadata.height, Some(480));
+        assert_eq!(metadata.frame_count, Some(50));
+        assert_eq!(metadata.video_codec.as_deref(), Some("vp9"));
+        assert_eq!(metadata.audio_codec.as_deref(), Some("opus"));
+    }
+
+    #[test]
+    fn it_drops_the_frame_count_when_it_overflows() {
+        let segment = [info(2e13), tracks(1)].concat();
+        let metadata = read_matroska_metadata(&mut Cursor::new(file(&segment))).unwrap();
+
+        assert_eq!(metadata.duration, Some(20_000_000_000_000));
+        assert_eq!(metadata.frame_count, None);
+    }
+
+    #[test]
+    fn it_ignores_durations_that_dont_fit() {
+        let segment = [info(1e30), tracks(0)].concat();
+        let metadata = read_matroska_metadata(&mut Cursor::new(file(&segment))).unwrap();
+
+        assert_eq!(metadata.duration, None);
+        assert_eq!(metadata.frame_count, None);
+    }
+
+    #[test]
+    fn it_fails_without_a_segment() {
+        let file = element(EBML_HEADER_ID, &uint_element(&[0x42, 0x86], 1));
+
+        assert!(read_matroska_metadata(&mut Cursor::new(file)).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use std::io::{BufRead, Seek, SeekFrom};
+
+use crate::error::RepoResult;
+
+pub use embedded_fields::{extract_embedded_fields, supports_embedded_fields};
+
+mod audio;
+mod embedded_fields;
+mod image;
+mod isobmff;
+mod matroska;
+
+const ISOBMFF_MIME_TYPES: [&str; 5] = [
+    "video/mp4",
+    "video/quicktime",
+    "video/x-m4v",
+    "video/3gpp",
+    "video/3gpp2",
+];
+const MATROSKA_MIME_TYPES: [&str; 4] = [
+    "video/webm",
+    "video/x-matroska",
+    "audio/webm",
+    "audio/x-matroska",
+];
+const AUDIO_MIME_TYPES: [&str; 12] = [
+    "audio/mpeg",
+    "audio/mp3",
+    "audio/flac",
+    "audio/x-flac",
+    "audio/wav",
+    "audio/x-wav",
+    "audio/wave",
+    "audio/ogg",
+    "audio/vorbis",
+    "audio/mp4",
+    "audio/x-m4a",
+    "audio/aac",
+];
+
+/// Technical metadata of an image, audio or video file
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaMetadata {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// The duration in milliseconds
+    pub duration: Option<u64>,
+    pub frame_count: Option<u64>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    /// The average bitrate in bits per second
+    pub bitrate: Option<u64>,
+    /// The exif orientation from 1 to 8
+    pub orientation: Option<u8>,
+    /// The exif fields of the primary image as tag name and display value
+    pub exif: Vec<(String, String)>,
+}
+
+/// Returns if media metadata can be extracted from files of the given mime type
+pub fn supports_media_metadata(mime_type: &str) -> bool {
+    let mime_type = mime_type.to_lowercase();
+
+    (mime_type.starts_with("image/") && ::image::ImageFormat::from_mime_type(&mime_type).is_some())
+        || ISOBMFF_MIME_TYPES.contains(&mime_type.as_str())
+        || MATROSKA_MIME_TYPES.contains(&mime_type.as_str())
+        || AUDIO_MIME_TYPES.contains(&mime_type.as_str())
+}
+
+/// Extracts the media metadata of a file with one of the supported mime types.
+/// Only the parts of the file that contain the metadata are read. This blocks on the
+/// reader so it should be called outside of async code
+pub fn extract_media_metadata<R>(mut reader: R, mime_type: &str) -> RepoResult<MediaMetadata>
+where
+    R: BufRead + Seek + Send + Sync + 'static,
+{
+    let mime_type = mime_type.to_lowercase();
+    let file_size = reader.seek(SeekFrom::End(0))?;
+    reader.rewind()?;
+
+    let mut metadata = if mime_type.starts_with("image/") {
+        image::read_image_metadata(&mut reader)?
+    } else if ISOBMFF_MIME_TYPES.contains(&mime_type.as_str()) {
+        isobmff::read_isobmff_metadata(&mut reader)?
+    } else if MATROSKA_MIME_TYPES.contains(&mime_type.as_str()) {
+        matroska::read_matroska_metadata(&mut reader)?
+    } else {
+        audio::read_audio_metadata(reader, file_size, &mime_type)?
+    };
+    if metadata.bitrate.is_none() {
+        metadata.bitrate = metadata
+            .duration
+            .filter(|duration| *duration > 0)
+            .map(|duration| file_size * 8 * 1000 / duration);
+    }
+
+    Ok(metadata)
+}
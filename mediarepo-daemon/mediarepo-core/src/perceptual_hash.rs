@@ -0,0 +1,148 @@
+use image::imageops::FilterType;
+
+use crate::error::RepoResult;
+
+const HASH_WIDTH: u32 = 9;
+const HASH_HEIGHT: u32 = 8;
+
+/// Creates a difference hash (dHash) of an image.
+/// Visually similar images produce hashes with a small hamming distance
+pub fn create_perceptual_hash(bytes: &[u8]) -> RepoResult<u64> {
+    let image = image::load_from_memory(bytes)?
+        .resize_exact(HASH_WIDTH, HASH_HEIGHT, FilterType::Triangle)
+        .into_luma8();
+    let mut hash = 0u64;
+
+    for y in 0..HASH_HEIGHT {
+        for x in 0..HASH_WIDTH - 1 {
+            let left = image.get_pixel(x, y).0[0];
+            let right = image.get_pixel(x + 1, y).0[0];
+            hash = (hash << 1) | (left > right) as u64;
+        }
+    }
+
+    Ok(hash)
+}
+
+/// Returns the number of bits that differ between two perceptual hashes
+#[inline]
+pub fn hamming_distance(hash_a: u64, hash_b: u64) -> u32 {
+    (hash_a ^ hash_b).count_ones()
+}
+
+/// A BK-tree of perceptual hashes that finds all similar hashes
+/// without comparing the searched hash to every stored one
+#[derive(Clone, Debug)]
+pub struct PerceptualHashTree<T> {
+    nodes: Vec<HashTreeNode<T>>,
+}
+
+#[derive(Clone, Debug)]
+struct HashTreeNode<T> {
+    hash: u64,
+    values: Vec<T>,
+    children: Vec<(u32, usize)>,
+}
+
+impl<T> Default for PerceptualHashTree<T> {
+    fn default() -> Self {
+        Self { nodes: Vec::new() }
+    }
+}
+
+impl<T> PerceptualHashTree<T> {
+    /// Adds a value with the given hash to the tree
+    pub fn insert(&mut self, hash: u64, value: T) {
+        let new_index = self.nodes.len();
+        let mut index = 0;
+
+        while index < new_index {
+            let node = &mut self.nodes[index];
+            let distance = hamming_distance(node.hash, hash);
+
+            if distance == 0 {
+                node.values.push(value);
+                return;
+            }
+            match node.children.iter().find(|(d, _)| *d == distance) {
+                Some((_, child)) => index = *child,
+                None => {
+                    node.children.push((distance, new_index));
+                    break;
+                }
+            }
+        }
+        self.nodes.push(HashTreeNode {
+            hash,
+            values: vec![value],
+            children: Vec::new(),
+        });
+    }
+
+    /// Returns all values with a hash that has a hamming distance
+    /// of at most `max_distance` to the given hash together with the distance
+    pub fn find(&self, hash: u64, max_distance: u32) -> Vec<(&T, u32)> {
+        let mut results = Vec::new();
+        let mut stack = Vec::new();
+
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let distance = hamming_distance(node.hash, hash);
+
+            if distance <= max_distance {
+                results.extend(node.values.iter().map(|v| (v, distance)));
+            }
+            // by the triangle inequality matches can only be found in
+            // children whose distance differs by at most max_distance
+            let min_distance = distance.saturating_sub(max_distance);
+            let max_distance = distance + max_distance;
+            stack.extend(
+                node.children
+                    .iter()
+                    .filter(|(d, _)| *d >= min_distance && *d <= max_distance)
+                    .map(|(_, child)| *child),
+            );
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_same_hashes_as_a_pairwise_comparison() {
+        let hashes: Vec<u64> = (0..500u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> (i % 7))
+            .chain([0, 1, 3, u64::MAX, u64::MAX - 1])
+            .collect();
+        let mut tree = PerceptualHashTree::default();
+
+        for (i, hash) in hashes.iter().enumerate() {
+            tree.insert(*hash, i);
+        }
+        for max_distance in [0, 2, 10, 32] {
+            for hash in &hashes {
+                let mut found: Vec<usize> = tree
+                    .find(*hash, max_distance)
+                    .into_iter()
+                    .map(|(i, _)| *i)
+                    .collect();
+                found.sort_unstable();
+                let expected: Vec<usize> = hashes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, h)| hamming_distance(**h, *hash) <= max_distance)
+                    .map(|(i, _)| i)
+                    .collect();
+
+                assert_eq!(found, expected);
+            }
+        }
+    }
+}
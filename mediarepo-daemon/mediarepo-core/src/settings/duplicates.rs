@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DuplicateSettings {
+    /// The maximum number of differing bits in the perceptual hashes
+    /// of two images to consider them duplicates
+    pub max_distance: u32,
+}
+
+impl Default for DuplicateSettings {
+    fn default() -> Self {
+        Self { max_distance: 10 }
+    }
+}
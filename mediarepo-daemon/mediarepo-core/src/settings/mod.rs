@@ -4,16 +4,24 @@ use std::path::{Path, PathBuf};
 use config::{Config, FileFormat};
 use serde::{Deserialize, Serialize};
 
+pub use duplicates::*;
 pub use logging::*;
 pub use paths::*;
 pub use server::*;
+pub use storage::*;
+pub use tag_extraction::*;
+pub use thumbnails::*;
 
 use crate::error::RepoResult;
 use crate::settings::v1::SettingsV1;
 
+mod duplicates;
 mod logging;
 mod paths;
 mod server;
+mod storage;
+mod tag_extraction;
+mod thumbnails;
 pub mod v1;
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -21,13 +29,17 @@ pub struct Settings {
     pub server: ServerSettings,
     pub paths: PathSettings,
     pub logging: LoggingSettings,
+    pub storage: StorageSettings,
+    pub duplicates: DuplicateSettings,
+    pub thumbnails: ThumbnailSettings,
+    pub tag_extraction: TagExtractionSettings,
 }
 
 impl Settings {
     pub fn read(root: &Path) -> RepoResult<Self> {
         let settings = Config::builder()
             .add_source(config::File::from_str(
-                &*Settings::default().to_toml_string()?,
+                &Settings::default().to_toml_string()?,
                 FileFormat::Toml,
             ))
             .add_source(config::File::from(root.join("repo")))
@@ -52,7 +64,7 @@ impl Settings {
 
         let settings = Config::builder()
             .add_source(config::File::from_str(
-                &*settings_main.to_toml_string()?,
+                &settings_main.to_toml_string()?,
                 FileFormat::Toml,
             ))
             .add_source(config::Environment::with_prefix("MEDIAREPO"))
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+pub struct StorageSettings {
+    pub backend: StorageBackend,
+    pub thumbnails: ThumbnailStorage,
+    pub s3: S3StorageSettings,
+}
+
+/// The backend used to store file contents
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+pub enum StorageBackend {
+    /// Files are stored in the files directory of the repository
+    #[default]
+    Local,
+    /// Files are stored in an S3 compatible object storage
+    S3,
+}
+
+/// The layout used to store thumbnails
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub enum ThumbnailStorage {
+    /// Every thumbnail is stored in its own file
+    #[default]
+    Directory,
+    /// Thumbnails are appended to a few large pack files
+    Packed,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct S3StorageSettings {
+    pub bucket: String,
+    pub region: String,
+    /// Custom endpoint for S3 compatible storage providers like MinIO
+    pub endpoint: Option<String>,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    /// Addresses the bucket as part of the path instead of the domain
+    pub path_style: bool,
+    /// Prefix for all objects stored by the repository
+    pub prefix: String,
+}
+
+impl Default for S3StorageSettings {
+    fn default() -> Self {
+        Self {
+            bucket: String::from("mediarepo"),
+            region: String::from("us-east-1"),
+            endpoint: None,
+            access_key: None,
+            secret_key: None,
+            path_style: false,
+            prefix: String::from("files"),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TagExtractionSettings {
+    /// If tags are extracted from the embedded metadata of imported files
+    pub enabled: bool,
+    pub mappings: Vec<TagMapping>,
+}
+
+/// Maps the values of an embedded metadata field to tags
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TagMapping {
+    /// The field prefixed with its source, e.g. `exif:Model`, `xmp:subject` or `id3:TPE1`
+    pub field: String,
+    pub namespace: Option<String>,
+}
+
+impl TagMapping {
+    fn new(field: &str, namespace: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            namespace: Some(namespace.to_string()),
+        }
+    }
+}
+
+impl Default for TagExtractionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            mappings: vec![
+                TagMapping::new("exif:Model", "camera"),
+                TagMapping::new("exif:DateTimeOriginal", "date"),
+                TagMapping::new("xmp:subject", "keyword"),
+                TagMapping::new("id3:TPE1", "artist"),
+                TagMapping::new("id3:TALB", "album"),
+                TagMapping::new("id3:TCON", "genre"),
+            ],
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ThumbnailSettings {
+    pub format: ThumbnailFormat,
+    /// The quality of lossy formats from 0 to 100
+    pub quality: u8,
+}
+
+impl Default for ThumbnailSettings {
+    fn default() -> Self {
+        Self {
+            format: ThumbnailFormat::Png,
+            quality: 85,
+        }
+    }
+}
+
+/// The format thumbnails are encoded in
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+pub enum ThumbnailFormat {
+    #[default]
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl ThumbnailFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ThumbnailFormat::Png => "image/png",
+            ThumbnailFormat::Jpeg => "image/jpeg",
+            ThumbnailFormat::Webp => "image/webp",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ThumbnailFormat::Png => "png",
+            ThumbnailFormat::Jpeg => "jpg",
+            ThumbnailFormat::Webp => "webp",
+        }
+    }
+
+    /// Returns the format for a file extension. Files without an extension are png thumbnails
+    pub fn from_extension(extension: Option<&str>) -> Option<Self> {
+        match extension {
+            None | Some("png") => Some(ThumbnailFormat::Png),
+            Some("jpg") => Some(ThumbnailFormat::Jpeg),
+            Some("webp") => Some(ThumbnailFormat::Webp),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+CREATE TABLE integrity_findings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind INTEGER NOT NULL,
+    storage_key VARCHAR(255) NOT NULL,
+    cd_id INTEGER REFERENCES content_descriptors (id) ON DELETE CASCADE,
+    detected_at DATETIME NOT NULL,
+    UNIQUE (kind, storage_key)
+);
+
+CREATE INDEX integrity_finding_cd_index ON integrity_findings (cd_id);
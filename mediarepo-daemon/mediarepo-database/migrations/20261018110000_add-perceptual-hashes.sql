@@ -0,0 +1,16 @@
+CREATE TABLE perceptual_hashes (
+    cd_id INTEGER PRIMARY KEY REFERENCES content_descriptors (id) ON DELETE CASCADE,
+    hash INTEGER NOT NULL
+);
+
+CREATE TABLE duplicate_candidates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cd_id_a INTEGER NOT NULL REFERENCES content_descriptors (id) ON DELETE CASCADE,
+    cd_id_b INTEGER NOT NULL REFERENCES content_descriptors (id) ON DELETE CASCADE,
+    distance INTEGER NOT NULL,
+    status INTEGER NOT NULL,
+    UNIQUE (cd_id_a, cd_id_b)
+);
+
+CREATE INDEX duplicate_candidate_cd_b_index ON duplicate_candidates (cd_id_b);
+CREATE INDEX duplicate_candidate_status_index ON duplicate_candidates (status);
@@ -0,0 +1,2 @@
+ALTER TABLE files
+    ADD COLUMN import_mode INTEGER NOT NULL DEFAULT 10;
@@ -0,0 +1,6 @@
+CREATE TABLE saved_searches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    query BLOB NOT NULL,
+    sorting_preset_id INTEGER REFERENCES sorting_presets (id) ON DELETE SET NULL
+);
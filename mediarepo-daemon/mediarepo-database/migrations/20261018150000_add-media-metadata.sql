@@ -0,0 +1,12 @@
+CREATE TABLE media_metadata (
+    cd_id INTEGER PRIMARY KEY REFERENCES content_descriptors (id) ON DELETE CASCADE,
+    width INTEGER,
+    height INTEGER,
+    duration INTEGER,
+    frame_count INTEGER,
+    video_codec VARCHAR(64),
+    audio_codec VARCHAR(64),
+    bitrate INTEGER,
+    orientation INTEGER,
+    exif BLOB NOT NULL
+);
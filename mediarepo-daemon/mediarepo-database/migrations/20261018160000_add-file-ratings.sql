@@ -0,0 +1,7 @@
+CREATE TABLE file_ratings (
+    file_id INTEGER PRIMARY KEY REFERENCES files (id) ON DELETE CASCADE,
+    rating INTEGER,
+    favorite INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX file_ratings_rating ON file_ratings (rating);
+CREATE INDEX file_ratings_favorite ON file_ratings (favorite);
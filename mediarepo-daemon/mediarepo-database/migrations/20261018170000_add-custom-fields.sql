@@ -0,0 +1,15 @@
+CREATE TABLE custom_fields (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name VARCHAR(128) NOT NULL UNIQUE,
+    field_type INTEGER NOT NULL
+);
+CREATE TABLE custom_field_values (
+    file_id INTEGER NOT NULL REFERENCES files (id) ON DELETE CASCADE,
+    field_id INTEGER NOT NULL REFERENCES custom_fields (id) ON DELETE CASCADE,
+    int_value INTEGER,
+    float_value REAL,
+    text_value TEXT,
+    date_value DATETIME,
+    PRIMARY KEY (file_id, field_id)
+);
+CREATE INDEX custom_field_values_field_id ON custom_field_values (field_id);
@@ -0,0 +1,8 @@
+CREATE TABLE file_relations (
+    file_id INTEGER NOT NULL REFERENCES files (id) ON DELETE CASCADE,
+    related_file_id INTEGER NOT NULL REFERENCES files (id) ON DELETE CASCADE,
+    relation_type INTEGER NOT NULL,
+    PRIMARY KEY (file_id, related_file_id, relation_type),
+    CHECK (file_id != related_file_id)
+);
+CREATE INDEX file_relations_related_file_id ON file_relations (related_file_id);
@@ -0,0 +1,14 @@
+CREATE TABLE collections (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    cover_file_id INTEGER REFERENCES files (id) ON DELETE SET NULL
+);
+CREATE TABLE collection_files (
+    collection_id INTEGER NOT NULL REFERENCES collections (id) ON DELETE CASCADE,
+    file_id INTEGER NOT NULL REFERENCES files (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (collection_id, file_id)
+);
+CREATE INDEX collection_files_position ON collection_files (collection_id, position);
+CREATE INDEX collection_files_file_id ON collection_files (file_id);
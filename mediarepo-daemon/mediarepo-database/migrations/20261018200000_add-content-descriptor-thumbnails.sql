@@ -0,0 +1,3 @@
+CREATE TABLE content_descriptor_thumbnails (
+    cd_id INTEGER PRIMARY KEY REFERENCES content_descriptors (id) ON DELETE CASCADE
+);
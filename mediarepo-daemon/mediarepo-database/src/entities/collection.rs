@@ -0,0 +1,26 @@
+use sea_orm::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "collections")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub cover_file_id: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl Related<super::file::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::collection_file::Relation::File.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::collection_file::Relation::Collection.def().rev())
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
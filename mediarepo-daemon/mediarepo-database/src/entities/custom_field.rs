@@ -0,0 +1,25 @@
+use sea_orm::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "custom_fields")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub name: String,
+    pub field_type: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl Related<super::file::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::custom_field_value::Relation::File.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::custom_field_value::Relation::CustomField.def().rev())
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
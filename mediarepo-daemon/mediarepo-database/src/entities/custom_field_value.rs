@@ -0,0 +1,47 @@
+use chrono::NaiveDateTime;
+use sea_orm::prelude::*;
+
+/// The value of a custom field for a file. Only the column matching
+/// the type of the field is set where booleans are stored as integers
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "custom_field_values")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub file_id: i64,
+    #[sea_orm(primary_key)]
+    pub field_id: i64,
+    pub int_value: Option<i64>,
+    pub float_value: Option<f64>,
+    pub text_value: Option<String>,
+    pub date_value: Option<NaiveDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::file::Entity",
+        from = "Column::FileId",
+        to = "super::file::Column::Id"
+    )]
+    File,
+    #[sea_orm(
+        belongs_to = "super::custom_field::Entity",
+        from = "Column::FieldId",
+        to = "super::custom_field::Column::Id"
+    )]
+    CustomField,
+}
+
+impl Related<super::file::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::File.def()
+    }
+}
+
+impl Related<super::custom_field::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CustomField.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
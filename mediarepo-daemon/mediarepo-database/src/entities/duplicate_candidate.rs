@@ -0,0 +1,44 @@
+use sea_orm::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "duplicate_candidates")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub cd_id_a: i64,
+    pub cd_id_b: i64,
+    pub distance: i32,
+    pub status: DuplicateStatus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u32", db_type = "Integer")]
+pub enum DuplicateStatus {
+    /// The pair still needs to be reviewed
+    #[sea_orm(num_value = 10)]
+    Pending,
+    /// The files were reviewed and are not duplicates
+    #[sea_orm(num_value = 20)]
+    NotDuplicates,
+    /// One of the files was kept and the other one discarded
+    #[sea_orm(num_value = 30)]
+    Resolved,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::content_descriptor::Entity",
+        from = "Column::CdIdA",
+        to = "super::content_descriptor::Column::Id"
+    )]
+    ContentDescriptorA,
+    #[sea_orm(
+        belongs_to = "super::content_descriptor::Entity",
+        from = "Column::CdIdB",
+        to = "super::content_descriptor::Column::Id"
+    )]
+    ContentDescriptorB,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
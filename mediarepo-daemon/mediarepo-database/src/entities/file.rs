@@ -8,6 +8,7 @@ pub struct Model {
     pub status: i32,
     pub mime_type: String,
     pub cd_id: i64,
+    pub import_mode: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
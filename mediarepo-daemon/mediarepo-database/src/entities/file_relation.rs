@@ -0,0 +1,31 @@
+use sea_orm::prelude::*;
+
+/// A directed relation that reads as "the file is the `relation_type` of the related file"
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "file_relations")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub file_id: i64,
+    #[sea_orm(primary_key)]
+    pub related_file_id: i64,
+    #[sea_orm(primary_key)]
+    pub relation_type: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::file::Entity",
+        from = "Column::FileId",
+        to = "super::file::Column::Id"
+    )]
+    File,
+    #[sea_orm(
+        belongs_to = "super::file::Entity",
+        from = "Column::RelatedFileId",
+        to = "super::file::Column::Id"
+    )]
+    RelatedFile,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+use sea_orm::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "integrity_findings")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub kind: FindingKind,
+    pub storage_key: String,
+    pub cd_id: Option<i64>,
+    pub detected_at: NaiveDateTime,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u32", db_type = "Integer")]
+pub enum FindingKind {
+    /// The file of a content descriptor doesn't exist in the storage
+    #[sea_orm(num_value = 10)]
+    Missing,
+    /// The contents of the stored file don't match the content descriptor
+    #[sea_orm(num_value = 20)]
+    Mismatch,
+    /// A file exists in the storage without a matching content descriptor
+    #[sea_orm(num_value = 30)]
+    Stray,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::content_descriptor::Entity",
+        from = "Column::CdId",
+        to = "super::content_descriptor::Column::Id"
+    )]
+    ContentDescriptorId,
+}
+
+impl Related<super::content_descriptor::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ContentDescriptorId.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
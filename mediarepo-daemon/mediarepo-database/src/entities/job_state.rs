@@ -22,6 +22,12 @@ pub enum JobType {
     CheckIntegrity,
     #[sea_orm(num_value = 50)]
     Vacuum,
+    #[sea_orm(num_value = 60)]
+    VerifyFiles,
+    #[sea_orm(num_value = 70)]
+    ReencodeThumbs,
+    #[sea_orm(num_value = 80)]
+    SyncThumbPresence,
 }
 
 impl TryFromU64 for JobType {
@@ -32,6 +38,9 @@ impl TryFromU64 for JobType {
             30 => Self::GenerateThumbs,
             40 => Self::CheckIntegrity,
             50 => Self::Vacuum,
+            60 => Self::VerifyFiles,
+            70 => Self::ReencodeThumbs,
+            80 => Self::SyncThumbPresence,
             _ => return Err(DbErr::Custom(String::from("Invalid job type"))),
         };
 
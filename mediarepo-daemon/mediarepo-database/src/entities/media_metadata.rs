@@ -0,0 +1,35 @@
+use sea_orm::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "media_metadata")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub cd_id: i64,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub duration: Option<i64>,
+    pub frame_count: Option<i64>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub bitrate: Option<i64>,
+    pub orientation: Option<i32>,
+    pub exif: Vec<u8>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::content_descriptor::Entity",
+        from = "Column::CdId",
+        to = "super::content_descriptor::Column::Id"
+    )]
+    ContentDescriptorId,
+}
+
+impl Related<super::content_descriptor::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ContentDescriptorId.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,10 +1,22 @@
+pub mod collection;
+pub mod collection_file;
 pub mod content_descriptor;
 pub mod content_descriptor_source;
 pub mod content_descriptor_tag;
+pub mod content_descriptor_thumbnail;
+pub mod custom_field;
+pub mod custom_field_value;
+pub mod duplicate_candidate;
 pub mod file;
 pub mod file_metadata;
+pub mod file_rating;
+pub mod file_relation;
+pub mod integrity_finding;
 pub mod job_state;
+pub mod media_metadata;
 pub mod namespace;
+pub mod perceptual_hash;
+pub mod saved_search;
 pub mod sort_key;
 pub mod sorting_preset;
 pub mod sorting_preset_key;
@@ -0,0 +1,27 @@
+use sea_orm::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "perceptual_hashes")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub cd_id: i64,
+    pub hash: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::content_descriptor::Entity",
+        from = "Column::CdId",
+        to = "super::content_descriptor::Column::Id"
+    )]
+    ContentDescriptorId,
+}
+
+impl Related<super::content_descriptor::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ContentDescriptorId.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,29 @@
+use sea_orm::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "saved_searches")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub name: String,
+    pub query: Vec<u8>,
+    pub sorting_preset_id: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::sorting_preset::Entity",
+        from = "Column::SortingPresetId",
+        to = "super::sorting_preset::Column::Id"
+    )]
+    SortingPreset,
+}
+
+impl Related<super::sorting_preset::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SortingPreset.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
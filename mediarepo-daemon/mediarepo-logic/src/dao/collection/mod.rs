@@ -0,0 +1,477 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use sea_orm::prelude::*;
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveValue::{Set, Unchanged};
+use sea_orm::{DatabaseTransaction, QueryOrder, TransactionTrait};
+
+use mediarepo_core::error::{RepoError, RepoResult};
+use mediarepo_core::itertools::Itertools;
+use mediarepo_database::entities::{collection, collection_file, content_descriptor, file};
+
+use crate::dao::file::map_file_and_cd;
+use crate::dao::opt_to_active_val;
+use crate::dao_provider;
+use crate::dto::{AddCollectionDto, CollectionDto, FileDto, UpdateCollectionDto};
+
+dao_provider!(CollectionDao);
+
+impl CollectionDao {
+    /// Returns all collections ordered by name
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all(&self) -> RepoResult<Vec<CollectionDto>> {
+        let collections = collection::Entity::find()
+            .order_by_asc(collection::Column::Name)
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(CollectionDto::new)
+            .collect();
+
+        Ok(collections)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn by_id(&self, id: i64) -> RepoResult<Option<CollectionDto>> {
+        let collection = collection::Entity::find_by_id(id)
+            .one(&self.ctx.db)
+            .await?
+            .map(CollectionDto::new);
+
+        Ok(collection)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add(&self, collection: AddCollectionDto) -> RepoResult<CollectionDto> {
+        let model = collection::ActiveModel {
+            name: Set(validate_name(collection.name)?),
+            description: Set(collection.description),
+            cover_file_id: Set(collection.cover_file_id),
+            ..Default::default()
+        };
+        let model = model.insert(&self.ctx.db).await?;
+
+        Ok(CollectionDto::new(model))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn update(&self, update_dto: UpdateCollectionDto) -> RepoResult<CollectionDto> {
+        if update_dto.name.is_none()
+            && update_dto.description.is_none()
+            && update_dto.cover_file_id.is_none()
+        {
+            return self
+                .by_id(update_dto.id)
+                .await?
+                .ok_or_else(|| collection_not_found(update_dto.id));
+        }
+        let model = collection::ActiveModel {
+            id: Unchanged(update_dto.id),
+            name: opt_to_active_val(update_dto.name.map(validate_name).transpose()?),
+            description: opt_to_active_val(update_dto.description),
+            cover_file_id: opt_to_active_val(update_dto.cover_file_id),
+        };
+        let model = model.update(&self.ctx.db).await?;
+
+        Ok(CollectionDto::new(model))
+    }
+
+    /// Deletes a collection. The files of the collection are kept
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete(&self, id: i64) -> RepoResult<()> {
+        let trx = self.ctx.db.begin().await?;
+        collection_file::Entity::delete_many()
+            .filter(collection_file::Column::CollectionId.eq(id))
+            .exec(&trx)
+            .await?;
+        collection::Entity::delete_many()
+            .filter(collection::Column::Id.eq(id))
+            .exec(&trx)
+            .await?;
+        trx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns the files of a collection in their order
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn files(&self, collection_id: i64) -> RepoResult<Vec<FileDto>> {
+        let positions: HashMap<i64, i32> = collection_file::Entity::find()
+            .filter(collection_file::Column::CollectionId.eq(collection_id))
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(|m| (m.file_id, m.position))
+            .collect();
+
+        if positions.is_empty() {
+            return Ok(vec![]);
+        }
+        let files = file::Entity::find()
+            .find_also_related(content_descriptor::Entity)
+            .filter(file::Column::Id.is_in(positions.keys().copied().collect::<Vec<i64>>()))
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .filter_map(map_file_and_cd)
+            .sorted_by_key(|f| positions.get(&f.id()).copied())
+            .collect();
+
+        Ok(files)
+    }
+
+    /// Inserts files into a collection at the given position or at the end if no
+    /// position is given. Files that are already part of the collection are skipped
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn insert_files(
+        &self,
+        collection_id: i64,
+        file_ids: Vec<i64>,
+        position: Option<u32>,
+    ) -> RepoResult<()> {
+        let trx = self.ctx.db.begin().await?;
+        collection_by_id(&trx, collection_id).await?;
+        let members = members(&trx, collection_id).await?;
+        let member_ids: Vec<i64> = members.iter().map(|m| m.file_id).collect();
+        let new_ids: Vec<i64> = file_ids
+            .into_iter()
+            .unique()
+            .filter(|id| !member_ids.contains(id))
+            .collect();
+
+        if new_ids.is_empty() {
+            return Ok(());
+        }
+        let position = insert_position(position, members.len());
+        collection_file::Entity::update_many()
+            .col_expr(
+                collection_file::Column::Position,
+                Expr::col(collection_file::Column::Position).add(new_ids.len() as i32),
+            )
+            .filter(collection_file::Column::CollectionId.eq(collection_id))
+            .filter(collection_file::Column::Position.gte(position))
+            .exec(&trx)
+            .await?;
+        let models: Vec<collection_file::ActiveModel> = new_ids
+            .into_iter()
+            .enumerate()
+            .map(|(i, file_id)| collection_file::ActiveModel {
+                collection_id: Set(collection_id),
+                file_id: Set(file_id),
+                position: Set(position + i as i32),
+            })
+            .collect();
+        collection_file::Entity::insert_many(models)
+            .exec(&trx)
+            .await?;
+        trx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Moves a file of a collection to a new position and shifts the files in between.
+    /// Positions after the end of the collection move the file to the end
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn move_file(
+        &self,
+        collection_id: i64,
+        file_id: i64,
+        position: u32,
+    ) -> RepoResult<()> {
+        let trx = self.ctx.db.begin().await?;
+        let members = members(&trx, collection_id).await?;
+        let old_position = members
+            .iter()
+            .find(|m| m.file_id == file_id)
+            .map(|m| m.position)
+            .ok_or_else(|| {
+                RepoError::from(
+                    format!(
+                        "file {} is not part of collection {}",
+                        file_id, collection_id
+                    )
+                    .as_str(),
+                )
+            })?;
+        let new_position = move_position(position, members.len());
+        let (shift, start, end) = match shift_for_move(old_position, new_position) {
+            Some(shift) => shift,
+            None => return Ok(()),
+        };
+        collection_file::Entity::update_many()
+            .col_expr(
+                collection_file::Column::Position,
+                Expr::col(collection_file::Column::Position).add(shift),
+            )
+            .filter(collection_file::Column::CollectionId.eq(collection_id))
+            .filter(collection_file::Column::Position.between(start, end))
+            .exec(&trx)
+            .await?;
+        collection_file::Entity::update_many()
+            .col_expr(collection_file::Column::Position, Expr::value(new_position))
+            .filter(collection_file::Column::CollectionId.eq(collection_id))
+            .filter(collection_file::Column::FileId.eq(file_id))
+            .exec(&trx)
+            .await?;
+        trx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Removes files from a collection and closes the gaps they leave in the order
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn remove_files(&self, collection_id: i64, file_ids: Vec<i64>) -> RepoResult<()> {
+        let trx = self.ctx.db.begin().await?;
+        collection_file::Entity::delete_many()
+            .filter(collection_file::Column::CollectionId.eq(collection_id))
+            .filter(collection_file::Column::FileId.is_in(file_ids))
+            .exec(&trx)
+            .await?;
+        compact_positions(&trx, collection_id).await?;
+        trx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Renumbers the files of a collection so that their positions don't have any gaps
+pub(crate) async fn compact_positions(
+    trx: &DatabaseTransaction,
+    collection_id: i64,
+) -> RepoResult<()> {
+    let members = members(trx, collection_id).await?;
+
+    for (file_id, position) in compacted_positions(&members) {
+        collection_file::Entity::update_many()
+            .col_expr(collection_file::Column::Position, Expr::value(position))
+            .filter(collection_file::Column::CollectionId.eq(collection_id))
+            .filter(collection_file::Column::FileId.eq(file_id))
+            .exec(trx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Returns the position files are inserted at in a collection with the given number of files.
+/// Files are appended if no position is given or the position is after the end
+fn insert_position(position: Option<u32>, member_count: usize) -> i32 {
+    let count = member_count as u32;
+
+    position.map(|p| p.min(count)).unwrap_or(count) as i32
+}
+
+/// Returns the position a file is moved to in a collection with the given number of files.
+/// Positions after the end move the file to the end
+fn move_position(position: u32, member_count: usize) -> i32 {
+    position.min((member_count as u32).saturating_sub(1)) as i32
+}
+
+/// Returns the shift for the files between the old and new position of a moved file
+/// and the inclusive range of positions it applies to. Returns None if the file doesn't move
+fn shift_for_move(old_position: i32, new_position: i32) -> Option<(i32, i32, i32)> {
+    match new_position.cmp(&old_position) {
+        Ordering::Greater => Some((-1, old_position + 1, new_position)),
+        Ordering::Less => Some((1, new_position, old_position - 1)),
+        Ordering::Equal => None,
+    }
+}
+
+/// Returns the ids and new positions of all files whose position has to change
+/// to close the gaps in the order. The files must be sorted by their position
+fn compacted_positions(members: &[collection_file::Model]) -> Vec<(i64, i32)> {
+    members
+        .iter()
+        .enumerate()
+        .filter(|(position, member)| member.position != *position as i32)
+        .map(|(position, member)| (member.file_id, position as i32))
+        .collect()
+}
+
+async fn members(
+    trx: &DatabaseTransaction,
+    collection_id: i64,
+) -> RepoResult<Vec<collection_file::Model>> {
+    let members = collection_file::Entity::find()
+        .filter(collection_file::Column::CollectionId.eq(collection_id))
+        .order_by_asc(collection_file::Column::Position)
+        .all(trx)
+        .await?;
+
+    Ok(members)
+}
+
+async fn collection_by_id(
+    trx: &DatabaseTransaction,
+    collection_id: i64,
+) -> RepoResult<collection::Model> {
+    collection::Entity::find_by_id(collection_id)
+        .one(trx)
+        .await?
+        .ok_or_else(|| collection_not_found(collection_id))
+}
+
+fn collection_not_found(collection_id: i64) -> RepoError {
+    RepoError::from(format!("collection {} does not exist", collection_id).as_str())
+}
+
+/// Trims the name and fails if it is empty
+fn validate_name(name: String) -> RepoResult<String> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        Err(RepoError::from("the name of a collection can't be empty"))
+    } else {
+        Ok(name.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(file_id: i64, position: i32) -> collection_file::Model {
+        collection_file::Model {
+            collection_id: 1,
+            file_id,
+            position,
+        }
+    }
+
+    /// Applies the position updates of an insertion to files ordered by their position
+    /// and returns the resulting order
+    fn insert(order: &[i64], new_ids: &[i64], position: Option<u32>) -> Vec<i64> {
+        let position = insert_position(position, order.len());
+        let mut members: Vec<(i64, i32)> = order
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let i = i as i32;
+                let shift = if i >= position {
+                    new_ids.len() as i32
+                } else {
+                    0
+                };
+                (*id, i + shift)
+            })
+            .collect();
+        members.extend(
+            new_ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| (*id, position + i as i32)),
+        );
+
+        into_order(members)
+    }
+
+    /// Applies the position updates of a move to files ordered by their position
+    /// and returns the resulting order
+    fn move_to(order: &[i64], file_id: i64, position: u32) -> Vec<i64> {
+        let old_position = order.iter().position(|id| *id == file_id).unwrap() as i32;
+        let new_position = move_position(position, order.len());
+        let mut members: Vec<(i64, i32)> = order
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i as i32))
+            .collect();
+
+        if let Some((shift, start, end)) = shift_for_move(old_position, new_position) {
+            for (id, position) in &mut members {
+                if *id == file_id {
+                    *position = new_position;
+                } else if *position >= start && *position <= end {
+                    *position += shift;
+                }
+            }
+        }
+
+        into_order(members)
+    }
+
+    /// Sorts the files by position and checks that the positions don't have gaps or duplicates
+    fn into_order(mut members: Vec<(i64, i32)>) -> Vec<i64> {
+        members.sort_by_key(|(_, position)| *position);
+        let positions: Vec<i32> = members.iter().map(|(_, position)| *position).collect();
+        assert_eq!(positions, (0..members.len() as i32).collect::<Vec<i32>>());
+
+        members.into_iter().map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn it_appends_files_without_a_position() {
+        assert_eq!(insert(&[1, 2, 3], &[4, 5], None), vec![1, 2, 3, 4, 5]);
+        assert_eq!(insert(&[], &[4], None), vec![4]);
+    }
+
+    #[test]
+    fn it_inserts_files_at_the_position() {
+        assert_eq!(insert(&[1, 2, 3], &[4, 5], Some(0)), vec![4, 5, 1, 2, 3]);
+        assert_eq!(insert(&[1, 2, 3], &[4, 5], Some(1)), vec![1, 4, 5, 2, 3]);
+        assert_eq!(insert(&[1, 2, 3], &[4], Some(3)), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn it_appends_files_inserted_past_the_end() {
+        assert_eq!(insert(&[1, 2, 3], &[4], Some(10)), vec![1, 2, 3, 4]);
+        assert_eq!(insert(&[1, 2, 3], &[4], Some(u32::MAX)), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn it_shifts_files_back_when_moving_forward() {
+        assert_eq!(move_to(&[1, 2, 3, 4, 5], 2, 3), vec![1, 3, 4, 2, 5]);
+        assert_eq!(move_to(&[1, 2, 3, 4, 5], 1, 4), vec![2, 3, 4, 5, 1]);
+    }
+
+    #[test]
+    fn it_shifts_files_forward_when_moving_back() {
+        assert_eq!(move_to(&[1, 2, 3, 4, 5], 4, 1), vec![1, 4, 2, 3, 5]);
+        assert_eq!(move_to(&[1, 2, 3, 4, 5], 5, 0), vec![5, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn it_swaps_adjacent_files() {
+        assert_eq!(shift_for_move(2, 3), Some((-1, 3, 3)));
+        assert_eq!(shift_for_move(3, 2), Some((1, 2, 2)));
+        assert_eq!(move_to(&[1, 2, 3, 4], 3, 3), vec![1, 2, 4, 3]);
+        assert_eq!(move_to(&[1, 2, 3, 4], 4, 2), vec![1, 2, 4, 3]);
+    }
+
+    #[test]
+    fn it_doesnt_shift_files_when_the_position_is_unchanged() {
+        assert_eq!(shift_for_move(2, 2), None);
+        assert_eq!(move_to(&[1, 2, 3], 2, 1), vec![1, 2, 3]);
+        assert_eq!(move_to(&[1], 1, 5), vec![1]);
+    }
+
+    #[test]
+    fn it_moves_files_past_the_end_to_the_end() {
+        assert_eq!(move_position(10, 3), 2);
+        assert_eq!(move_position(u32::MAX, 3), 2);
+        assert_eq!(move_to(&[1, 2, 3], 1, 10), vec![2, 3, 1]);
+        assert_eq!(move_to(&[1, 2, 3], 3, 10), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn it_closes_the_gaps_of_removed_files() {
+        let members = vec![member(1, 0), member(3, 2), member(4, 3), member(7, 7)];
+
+        assert_eq!(compacted_positions(&members), vec![(3, 1), (4, 2), (7, 3)]);
+    }
+
+    #[test]
+    fn it_closes_a_gap_at_the_start() {
+        let members = vec![member(2, 1), member(3, 2)];
+
+        assert_eq!(compacted_positions(&members), vec![(2, 0), (3, 1)]);
+    }
+
+    #[test]
+    fn it_keeps_compact_positions() {
+        let members = vec![member(1, 0), member(2, 1), member(3, 2)];
+
+        assert!(compacted_positions(&members).is_empty());
+        assert!(compacted_positions(&[]).is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use sea_orm::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{QueryOrder, TransactionTrait};
+
+use mediarepo_core::error::{RepoError, RepoResult};
+use mediarepo_database::entities::{custom_field, custom_field_value};
+
+use crate::dao_provider;
+use crate::dto::{AddCustomFieldDto, CustomFieldDto, CustomFieldValue, CustomFieldValueDto};
+
+dao_provider!(CustomFieldDao);
+
+impl CustomFieldDao {
+    /// Returns all custom fields ordered by name
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all(&self) -> RepoResult<Vec<CustomFieldDto>> {
+        let fields = custom_field::Entity::find()
+            .order_by_asc(custom_field::Column::Name)
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .filter_map(CustomFieldDto::new)
+            .collect();
+
+        Ok(fields)
+    }
+
+    /// Adds a new custom field. The name is stored in lowercase
+    /// and may only contain letters, digits, `-` and `_`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add(&self, field: AddCustomFieldDto) -> RepoResult<CustomFieldDto> {
+        let name = field.name.trim().to_lowercase();
+
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(RepoError::from(
+                format!(
+                    "invalid custom field name '{}', names may only contain letters, digits, '-' and '_'",
+                    field.name
+                )
+                .as_str(),
+            ));
+        }
+        let existing = custom_field::Entity::find()
+            .filter(custom_field::Column::Name.eq(name.clone()))
+            .one(&self.ctx.db)
+            .await?;
+
+        if existing.is_some() {
+            return Err(RepoError::from(
+                format!("a custom field named '{}' already exists", name).as_str(),
+            ));
+        }
+        let model = custom_field::ActiveModel {
+            name: Set(name),
+            field_type: Set(field.field_type.to_number()),
+            ..Default::default()
+        }
+        .insert(&self.ctx.db)
+        .await?;
+
+        CustomFieldDto::new(model).ok_or_else(|| RepoError::from("invalid custom field type"))
+    }
+
+    /// Deletes a custom field with all of its values
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete(&self, id: i64) -> RepoResult<()> {
+        let trx = self.ctx.db.begin().await?;
+        custom_field_value::Entity::delete_many()
+            .filter(custom_field_value::Column::FieldId.eq(id))
+            .exec(&trx)
+            .await?;
+        custom_field::Entity::delete_many()
+            .filter(custom_field::Column::Id.eq(id))
+            .exec(&trx)
+            .await?;
+        trx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns the custom field values of a file ordered by the field name
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn values_for_file(&self, file_id: i64) -> RepoResult<Vec<CustomFieldValueDto>> {
+        let mut values = self.values_for_files(vec![file_id]).await?;
+        values.sort_by(|a, b| a.field().name().cmp(b.field().name()));
+
+        Ok(values)
+    }
+
+    /// Returns the custom field values of all given files
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn values_for_files(
+        &self,
+        file_ids: Vec<i64>,
+    ) -> RepoResult<Vec<CustomFieldValueDto>> {
+        if file_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let values = custom_field_value::Entity::find()
+            .find_also_related(custom_field::Entity)
+            .filter(custom_field_value::Column::FileId.is_in(file_ids))
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .filter_map(|(value, field)| {
+                CustomFieldValueDto::new(CustomFieldDto::new(field?)?, value)
+            })
+            .collect();
+
+        Ok(values)
+    }
+
+    /// Sets the custom field values of a file where fields with a value of
+    /// `None` are removed. Values are converted to the type of their field
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn set_values(
+        &self,
+        file_id: i64,
+        values: Vec<(i64, Option<CustomFieldValue>)>,
+    ) -> RepoResult<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        let trx = self.ctx.db.begin().await?;
+        let field_ids: Vec<i64> = values.iter().map(|(id, _)| *id).collect();
+        let fields: HashMap<i64, CustomFieldDto> = custom_field::Entity::find()
+            .filter(custom_field::Column::Id.is_in(field_ids.clone()))
+            .all(&trx)
+            .await?
+            .into_iter()
+            .filter_map(CustomFieldDto::new)
+            .map(|f| (f.id(), f))
+            .collect();
+        custom_field_value::Entity::delete_many()
+            .filter(custom_field_value::Column::FileId.eq(file_id))
+            .filter(custom_field_value::Column::FieldId.is_in(field_ids))
+            .exec(&trx)
+            .await?;
+        let mut models = Vec::new();
+
+        for (field_id, value) in values {
+            let field = fields.get(&field_id).ok_or_else(|| {
+                RepoError::from(format!("custom field {} does not exist", field_id).as_str())
+            })?;
+            if let Some(value) = value {
+                models.push(value_to_active_model(file_id, field, value)?);
+            }
+        }
+        if !models.is_empty() {
+            custom_field_value::Entity::insert_many(models)
+                .exec(&trx)
+                .await?;
+        }
+        trx.commit().await?;
+
+        Ok(())
+    }
+}
+
+fn value_to_active_model(
+    file_id: i64,
+    field: &CustomFieldDto,
+    value: CustomFieldValue,
+) -> RepoResult<custom_field_value::ActiveModel> {
+    let value_type = value.field_type();
+    let value = value.cast(field.field_type()).ok_or_else(|| {
+        RepoError::from(
+            format!(
+                "a value of type {:?} can't be stored in the custom field '{}'",
+                value_type,
+                field.name()
+            )
+            .as_str(),
+        )
+    })?;
+    let mut model = custom_field_value::ActiveModel {
+        file_id: Set(file_id),
+        field_id: Set(field.id()),
+        int_value: Set(None),
+        float_value: Set(None),
+        text_value: Set(None),
+        date_value: Set(None),
+    };
+    match value {
+        CustomFieldValue::Int(value) => model.int_value = Set(Some(value)),
+        CustomFieldValue::Float(value) => model.float_value = Set(Some(value)),
+        CustomFieldValue::Text(value) => model.text_value = Set(Some(value)),
+        CustomFieldValue::Date(value) => model.date_value = Set(Some(value)),
+        CustomFieldValue::Bool(value) => model.int_value = Set(Some(value as i64)),
+    }
+
+    Ok(model)
+}
@@ -0,0 +1,105 @@
+use std::collections::{HashMap, HashSet};
+
+use sea_orm::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::QueryOrder;
+
+use mediarepo_core::error::RepoResult;
+use mediarepo_database::entities::duplicate_candidate;
+use mediarepo_database::entities::duplicate_candidate::DuplicateStatus;
+
+use crate::dao_provider;
+use crate::dto::{AddDuplicateCandidateDto, DuplicateCandidateDto, FileDto};
+
+pub mod resolve;
+
+dao_provider!(DuplicateDao);
+
+impl DuplicateDao {
+    /// Returns all duplicate candidates that haven't been reviewed yet
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn pending(&self) -> RepoResult<Vec<DuplicateCandidateDto>> {
+        let candidates = duplicate_candidate::Entity::find()
+            .filter(duplicate_candidate::Column::Status.eq(DuplicateStatus::Pending))
+            .order_by_asc(duplicate_candidate::Column::Distance)
+            .order_by_asc(duplicate_candidate::Column::Id)
+            .all(&self.ctx.db)
+            .await?;
+        let cd_ids: HashSet<i64> = candidates
+            .iter()
+            .flat_map(|c| [c.cd_id_a, c.cd_id_b])
+            .collect();
+        let files: HashMap<i64, FileDto> = self
+            .file()
+            .all_by_cd_id(cd_ids.into_iter().collect())
+            .await?
+            .into_iter()
+            .map(|f| (f.cd_id(), f))
+            .collect();
+
+        let candidates = candidates
+            .into_iter()
+            .filter_map(|c| {
+                let file_a = files.get(&c.cd_id_a)?.clone();
+                let file_b = files.get(&c.cd_id_b)?.clone();
+
+                Some(DuplicateCandidateDto::new(c, file_a, file_b))
+            })
+            .collect();
+
+        Ok(candidates)
+    }
+
+    /// Adds duplicate candidates for pairs that haven't been recorded before
+    /// and returns the number of new candidates
+    #[tracing::instrument(level = "debug", skip(self, candidates))]
+    pub async fn add_candidates(
+        &self,
+        candidates: Vec<AddDuplicateCandidateDto>,
+    ) -> RepoResult<usize> {
+        let pairs: Vec<(i64, i64, u32)> = candidates
+            .into_iter()
+            .map(|c| {
+                // pairs are always stored in the same order to keep them unique
+                let (cd_id_a, cd_id_b) = if c.cd_id_a < c.cd_id_b {
+                    (c.cd_id_a, c.cd_id_b)
+                } else {
+                    (c.cd_id_b, c.cd_id_a)
+                };
+                (cd_id_a, cd_id_b, c.distance)
+            })
+            .collect();
+        let cd_ids_a: HashSet<i64> = pairs.iter().map(|(a, _, _)| *a).collect();
+        let cd_ids_b: HashSet<i64> = pairs.iter().map(|(_, b, _)| *b).collect();
+        let existing_pairs: HashSet<(i64, i64)> = duplicate_candidate::Entity::find()
+            .filter(duplicate_candidate::Column::CdIdA.is_in(cd_ids_a))
+            .filter(duplicate_candidate::Column::CdIdB.is_in(cd_ids_b))
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(|c| (c.cd_id_a, c.cd_id_b))
+            .collect();
+        let models: Vec<duplicate_candidate::ActiveModel> = pairs
+            .into_iter()
+            .filter(|(a, b, _)| !existing_pairs.contains(&(*a, *b)))
+            .map(
+                |(cd_id_a, cd_id_b, distance)| duplicate_candidate::ActiveModel {
+                    cd_id_a: Set(cd_id_a),
+                    cd_id_b: Set(cd_id_b),
+                    distance: Set(distance as i32),
+                    status: Set(DuplicateStatus::Pending),
+                    ..Default::default()
+                },
+            )
+            .collect();
+        let count = models.len();
+
+        if !models.is_empty() {
+            duplicate_candidate::Entity::insert_many(models)
+                .exec(&self.ctx.db)
+                .await?;
+        }
+
+        Ok(count)
+    }
+}
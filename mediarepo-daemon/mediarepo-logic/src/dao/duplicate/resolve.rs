@@ -0,0 +1,110 @@
+use sea_orm::prelude::*;
+use sea_orm::sea_query::Expr;
+use sea_orm::Condition;
+
+use mediarepo_core::error::{RepoError, RepoResult};
+use mediarepo_database::entities::duplicate_candidate;
+use mediarepo_database::entities::duplicate_candidate::DuplicateStatus;
+
+use crate::dao::duplicate::DuplicateDao;
+use crate::dao::DaoProvider;
+use crate::dto::{DuplicateResolutionDto, FileStatus, UpdateFileDto};
+
+impl DuplicateDao {
+    /// Resolves a duplicate candidate after it has been reviewed
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn resolve(&self, id: i64, resolution: DuplicateResolutionDto) -> RepoResult<()> {
+        let candidate = duplicate_candidate::Entity::find_by_id(id)
+            .one(&self.ctx.db)
+            .await?
+            .ok_or_else(|| RepoError::from("duplicate candidate not found"))?;
+
+        match resolution {
+            DuplicateResolutionDto::NotDuplicates => {
+                self.set_status(
+                    Condition::all().add(duplicate_candidate::Column::Id.eq(candidate.id)),
+                    DuplicateStatus::NotDuplicates,
+                )
+                .await
+            }
+            DuplicateResolutionDto::Keep {
+                file_id,
+                merge_tags,
+            } => self.keep_file(candidate, file_id, merge_tags).await,
+        }
+    }
+
+    /// Keeps one file of a duplicate pair and moves the other one to the trash
+    async fn keep_file(
+        &self,
+        candidate: duplicate_candidate::Model,
+        file_id: i64,
+        merge_tags: bool,
+    ) -> RepoResult<()> {
+        let survivor = self
+            .file()
+            .by_id(file_id)
+            .await?
+            .ok_or_else(|| RepoError::from("file not found"))?;
+        let discarded_cd_id = if survivor.cd_id() == candidate.cd_id_a {
+            candidate.cd_id_b
+        } else if survivor.cd_id() == candidate.cd_id_b {
+            candidate.cd_id_a
+        } else {
+            return Err(RepoError::from(
+                "file is not part of the duplicate candidate",
+            ));
+        };
+
+        if merge_tags {
+            let tag_ids = self
+                .tag()
+                .tags_for_cd(discarded_cd_id)
+                .await?
+                .into_iter()
+                .map(|t| t.id())
+                .collect();
+            self.tag()
+                .upsert_mappings(vec![survivor.cd_id()], tag_ids)
+                .await?;
+        }
+        for file in self.file().all_by_cd_id(vec![discarded_cd_id]).await? {
+            self.file()
+                .update(UpdateFileDto {
+                    id: file.id(),
+                    status: Some(FileStatus::Deleted),
+                    ..Default::default()
+                })
+                .await?;
+        }
+        // other pairs with the discarded file don't need to be reviewed anymore
+        self.set_status(
+            Condition::any()
+                .add(duplicate_candidate::Column::Id.eq(candidate.id))
+                .add(
+                    Condition::all()
+                        .add(duplicate_candidate::Column::Status.eq(DuplicateStatus::Pending))
+                        .add(
+                            Condition::any()
+                                .add(duplicate_candidate::Column::CdIdA.eq(discarded_cd_id))
+                                .add(duplicate_candidate::Column::CdIdB.eq(discarded_cd_id)),
+                        ),
+                ),
+            DuplicateStatus::Resolved,
+        )
+        .await
+    }
+
+    async fn set_status(&self, condition: Condition, status: DuplicateStatus) -> RepoResult<()> {
+        duplicate_candidate::Entity::update_many()
+            .col_expr(
+                duplicate_candidate::Column::Status,
+                Expr::value(status.into_value()),
+            )
+            .filter(condition)
+            .exec(&self.ctx.db)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -5,22 +5,40 @@ use sea_orm::ActiveValue::Set;
 use sea_orm::{ActiveModelTrait, DatabaseTransaction, TransactionTrait};
 
 use mediarepo_core::error::RepoResult;
+use mediarepo_core::fs::link::ImportMode;
+use mediarepo_core::media_metadata::supports_media_metadata;
 use mediarepo_core::thumbnailer::ThumbnailSize;
 use mediarepo_database::entities::{content_descriptor, file, file_metadata};
 
+use crate::dao::file::perceptual_hash::supports_perceptual_hash;
 use crate::dao::file::FileDao;
-use crate::dto::{AddFileDto, FileDto};
+use crate::dto::{AddFileDto, FileContent, FileDto};
 
 impl FileDao {
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn add(&self, add_dto: AddFileDto) -> RepoResult<FileDto> {
+        let (cd_bin, file_size, import_mode) = match add_dto.content {
+            FileContent::Bytes(bytes) => {
+                let file_size = bytes.len() as u64;
+                let cd_bin = self.ctx.main_storage.add_file(Cursor::new(bytes)).await?;
+                (cd_bin, file_size, ImportMode::Copy)
+            }
+            FileContent::Upload(upload_id) => {
+                let (cd_bin, file_size) = self.ctx.main_storage.commit_upload(&upload_id).await?;
+                (cd_bin, file_size, ImportMode::Copy)
+            }
+            FileContent::Reference { path, mode } => {
+                self.ctx
+                    .main_storage
+                    .add_file_by_reference(&path, mode)
+                    .await?
+            }
+        };
+        if let Some(file) = self.by_cd(cd_bin.clone()).await? {
+            tracing::debug!("Inserted file already exists");
+            return Ok(file);
+        }
         let trx = self.ctx.db.begin().await?;
-        let file_size = add_dto.content.len();
-        let cd_bin = self
-            .ctx
-            .main_storage
-            .add_file(Cursor::new(add_dto.content), None)
-            .await?;
         let cd_model = content_descriptor::ActiveModel {
             descriptor: Set(cd_bin),
             ..Default::default()
@@ -30,6 +48,7 @@ impl FileDao {
         let model = file::ActiveModel {
             cd_id: Set(cd.id),
             mime_type: Set(add_dto.mime_type),
+            import_mode: Set(import_mode_to_i32(import_mode)),
             ..Default::default()
         };
         let file: file::Model = model.insert(&trx).await?;
@@ -46,6 +65,25 @@ impl FileDao {
 
         trx.commit().await?;
         let dto = FileDto::new(file, cd, Some(metadata));
+
+        if supports_perceptual_hash(dto.mime_type()) {
+            if let Err(e) = self.create_perceptual_hash(&dto).await {
+                tracing::warn!(
+                    "failed to create perceptual hash for {}: {}",
+                    dto.encoded_cd(),
+                    e
+                );
+            }
+        }
+        if supports_media_metadata(dto.mime_type()) {
+            if let Err(e) = self.create_media_metadata(&dto).await {
+                tracing::warn!(
+                    "failed to extract media metadata for {}: {}",
+                    dto.encoded_cd(),
+                    e
+                );
+            }
+        }
         self.create_thumbnails(&dto, vec![ThumbnailSize::Medium])
             .await?;
 
@@ -75,3 +113,11 @@ async fn add_file_metadata(
 
     Ok(metadata)
 }
+
+fn import_mode_to_i32(mode: ImportMode) -> i32 {
+    match mode {
+        ImportMode::Copy => 10,
+        ImportMode::Hardlink => 20,
+        ImportMode::Reflink => 30,
+    }
+}
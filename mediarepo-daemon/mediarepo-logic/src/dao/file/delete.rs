@@ -1,11 +1,14 @@
 use sea_orm::prelude::*;
-use sea_orm::TransactionTrait;
+use sea_orm::sea_query::Expr;
+use sea_orm::{Condition, TransactionTrait};
 
 use mediarepo_core::error::RepoResult;
 use mediarepo_database::entities::{
-    content_descriptor, content_descriptor_tag, file, file_metadata,
+    collection, collection_file, content_descriptor, content_descriptor_source,
+    content_descriptor_tag, custom_field_value, file, file_metadata, file_rating, file_relation,
 };
 
+use crate::dao::collection::compact_positions;
 use crate::dao::file::FileDao;
 use crate::dto::FileDto;
 
@@ -18,6 +21,45 @@ impl FileDao {
             .filter(file_metadata::Column::FileId.eq(file.id()))
             .exec(&trx)
             .await?;
+        custom_field_value::Entity::delete_many()
+            .filter(custom_field_value::Column::FileId.eq(file.id()))
+            .exec(&trx)
+            .await?;
+        file_rating::Entity::delete_many()
+            .filter(file_rating::Column::FileId.eq(file.id()))
+            .exec(&trx)
+            .await?;
+        file_relation::Entity::delete_many()
+            .filter(
+                Condition::any()
+                    .add(file_relation::Column::FileId.eq(file.id()))
+                    .add(file_relation::Column::RelatedFileId.eq(file.id())),
+            )
+            .exec(&trx)
+            .await?;
+        let collection_ids: Vec<i64> = collection_file::Entity::find()
+            .filter(collection_file::Column::FileId.eq(file.id()))
+            .all(&trx)
+            .await?
+            .into_iter()
+            .map(|m| m.collection_id)
+            .collect();
+        collection_file::Entity::delete_many()
+            .filter(collection_file::Column::FileId.eq(file.id()))
+            .exec(&trx)
+            .await?;
+
+        for collection_id in collection_ids {
+            compact_positions(&trx, collection_id).await?;
+        }
+        collection::Entity::update_many()
+            .col_expr(
+                collection::Column::CoverFileId,
+                Expr::value(Option::<i64>::None),
+            )
+            .filter(collection::Column::CoverFileId.eq(file.id()))
+            .exec(&trx)
+            .await?;
         file::Entity::delete_many()
             .filter(file::Column::Id.eq(file.id()))
             .exec(&trx)
@@ -26,6 +68,10 @@ impl FileDao {
             .filter(content_descriptor_tag::Column::CdId.eq(file.cd_id()))
             .exec(&trx)
             .await?;
+        content_descriptor_source::Entity::delete_many()
+            .filter(content_descriptor_source::Column::CdId.eq(file.cd_id()))
+            .exec(&trx)
+            .await?;
         content_descriptor::Entity::delete_many()
             .filter(content_descriptor::Column::Id.eq(file.cd_id()))
             .exec(&trx)
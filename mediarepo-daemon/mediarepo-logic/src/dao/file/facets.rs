@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use sea_orm::sea_query::{Alias, Expr, Query, SelectStatement};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, JoinType, Order, QueryFilter,
+    QuerySelect, QueryTrait, RelationTrait,
+};
+
+use mediarepo_core::error::RepoResult;
+use mediarepo_database::entities::{content_descriptor_tag, file, file_metadata, namespace, tag};
+
+use crate::dao::file::find::{build_find_filter_conditions, FilterCondition};
+use crate::dao::file::FileDao;
+use crate::dto::{FileFacetsDto, MonthBucketDto, NamespaceDto, SizeBucketDto, TagDto};
+
+/// The upper bounds of the file size buckets. The last bucket has no upper bound
+const SIZE_BUCKET_BOUNDS: [u64; 5] = [100_000, 1_000_000, 10_000_000, 100_000_000, 1_000_000_000];
+
+#[derive(Debug, FromQueryResult)]
+struct Count {
+    count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct TagCount {
+    tag_id: i64,
+    count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct NamespaceCount {
+    id: i64,
+    name: String,
+    count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct MimeTypeCount {
+    mime_type: String,
+    count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct BucketCount {
+    bucket: i64,
+    count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct MonthCount {
+    year: i32,
+    month: i32,
+    count: i64,
+}
+
+impl FileDao {
+    /// Returns the tags, namespaces, mime types, sizes and import dates of the files
+    /// matching the filters with their number of occurrences.
+    /// Only the `tag_limit` most used tags are returned
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn facets<F: Into<FilterCondition> + Debug>(
+        &self,
+        filters: F,
+        tag_limit: u64,
+    ) -> RepoResult<FileFacetsDto> {
+        let condition = build_find_filter_conditions(filters.into());
+        let matching_files = file::Entity::find()
+            .join(
+                JoinType::InnerJoin,
+                file::Relation::ContentDescriptorId.def(),
+            )
+            .filter(condition)
+            .select_only();
+        let file_ids = matching_files.clone().column(file::Column::Id).into_query();
+        let cd_ids = matching_files.column(file::Column::CdId).into_query();
+
+        Ok(FileFacetsDto {
+            total: self.count_matching(file_ids.clone()).await?,
+            tags: self.tag_facets(cd_ids.clone(), tag_limit).await?,
+            namespaces: self.namespace_facets(cd_ids).await?,
+            mime_types: self.mime_type_facets(file_ids.clone()).await?,
+            sizes: self.size_facets(file_ids.clone()).await?,
+            import_months: self.import_month_facets(file_ids).await?,
+        })
+    }
+
+    async fn count_matching(&self, file_ids: SelectStatement) -> RepoResult<u64> {
+        let query = Query::select()
+            .expr_as(Expr::cust("COUNT(*)"), Alias::new("count"))
+            .from_subquery(file_ids, Alias::new("matching_files"))
+            .to_owned();
+        let count = self
+            .query_all::<Count>(query)
+            .await?
+            .into_iter()
+            .next()
+            .map(|c| c.count as u64)
+            .unwrap_or(0);
+
+        Ok(count)
+    }
+
+    async fn tag_facets(
+        &self,
+        cd_ids: SelectStatement,
+        limit: u64,
+    ) -> RepoResult<Vec<(TagDto, u64)>> {
+        let count_column = Alias::new("count");
+        let query = Query::select()
+            .expr_as(
+                Expr::col(content_descriptor_tag::Column::TagId),
+                Alias::new("tag_id"),
+            )
+            .expr_as(
+                content_descriptor_tag::Column::CdId.count(),
+                count_column.clone(),
+            )
+            .from(content_descriptor_tag::Entity)
+            .and_where(Expr::col(content_descriptor_tag::Column::CdId).in_subquery(cd_ids))
+            .group_by_col(content_descriptor_tag::Column::TagId)
+            .order_by(count_column, Order::Desc)
+            .order_by(content_descriptor_tag::Column::TagId, Order::Asc)
+            .limit(limit)
+            .to_owned();
+        let counts: Vec<TagCount> = self.query_all(query).await?;
+        let mut tags: HashMap<i64, TagDto> = tag::Entity::find()
+            .find_also_related(namespace::Entity)
+            .filter(tag::Column::Id.is_in(counts.iter().map(|c| c.tag_id).collect::<Vec<_>>()))
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(|(tag, namespace)| (tag.id, TagDto::new(tag, namespace)))
+            .collect();
+
+        let facets = counts
+            .into_iter()
+            .filter_map(|c| Some((tags.remove(&c.tag_id)?, c.count as u64)))
+            .collect();
+
+        Ok(facets)
+    }
+
+    async fn namespace_facets(
+        &self,
+        cd_ids: SelectStatement,
+    ) -> RepoResult<Vec<(NamespaceDto, u64)>> {
+        let count_column = Alias::new("count");
+        let query = Query::select()
+            .column((namespace::Entity, namespace::Column::Id))
+            .column((namespace::Entity, namespace::Column::Name))
+            .expr_as(
+                Expr::cust("COUNT(DISTINCT \"cd_tag_mappings\".\"cd_id\")"),
+                count_column.clone(),
+            )
+            .from(content_descriptor_tag::Entity)
+            .inner_join(
+                tag::Entity,
+                Expr::tbl(tag::Entity, tag::Column::Id).equals(
+                    content_descriptor_tag::Entity,
+                    content_descriptor_tag::Column::TagId,
+                ),
+            )
+            .inner_join(
+                namespace::Entity,
+                Expr::tbl(namespace::Entity, namespace::Column::Id)
+                    .equals(tag::Entity, tag::Column::NamespaceId),
+            )
+            .and_where(
+                Expr::tbl(
+                    content_descriptor_tag::Entity,
+                    content_descriptor_tag::Column::CdId,
+                )
+                .in_subquery(cd_ids),
+            )
+            .group_by_col((namespace::Entity, namespace::Column::Id))
+            .order_by(count_column, Order::Desc)
+            .order_by((namespace::Entity, namespace::Column::Name), Order::Asc)
+            .to_owned();
+        let facets = self
+            .query_all::<NamespaceCount>(query)
+            .await?
+            .into_iter()
+            .map(|c| {
+                let model = namespace::Model {
+                    id: c.id,
+                    name: c.name,
+                };
+                (NamespaceDto::new(model), c.count as u64)
+            })
+            .collect();
+
+        Ok(facets)
+    }
+
+    async fn mime_type_facets(&self, file_ids: SelectStatement) -> RepoResult<Vec<(String, u64)>> {
+        let count_column = Alias::new("count");
+        let query = Query::select()
+            .column(file::Column::MimeType)
+            .expr_as(file::Column::Id.count(), count_column.clone())
+            .from(file::Entity)
+            .and_where(Expr::col(file::Column::Id).in_subquery(file_ids))
+            .group_by_col(file::Column::MimeType)
+            .order_by(count_column, Order::Desc)
+            .order_by(file::Column::MimeType, Order::Asc)
+            .to_owned();
+        let facets = self
+            .query_all::<MimeTypeCount>(query)
+            .await?
+            .into_iter()
+            .map(|c| (c.mime_type, c.count as u64))
+            .collect();
+
+        Ok(facets)
+    }
+
+    async fn size_facets(&self, file_ids: SelectStatement) -> RepoResult<Vec<SizeBucketDto>> {
+        let bucket_column = Alias::new("bucket");
+        let cases = SIZE_BUCKET_BOUNDS
+            .iter()
+            .enumerate()
+            .map(|(i, bound)| format!("WHEN \"size\" < {} THEN {}", bound, i))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let query = Query::select()
+            .expr_as(
+                Expr::cust(&format!(
+                    "CASE {} ELSE {} END",
+                    cases,
+                    SIZE_BUCKET_BOUNDS.len()
+                )),
+                bucket_column.clone(),
+            )
+            .expr_as(file_metadata::Column::FileId.count(), Alias::new("count"))
+            .from(file_metadata::Entity)
+            .and_where(Expr::col(file_metadata::Column::FileId).in_subquery(file_ids))
+            .group_by_col(bucket_column)
+            .to_owned();
+        let counts: HashMap<i64, u64> = self
+            .query_all::<BucketCount>(query)
+            .await?
+            .into_iter()
+            .map(|c| (c.bucket, c.count as u64))
+            .collect();
+
+        let buckets = (0..=SIZE_BUCKET_BOUNDS.len())
+            .map(|i| SizeBucketDto {
+                min: if i == 0 { 0 } else { SIZE_BUCKET_BOUNDS[i - 1] },
+                max: SIZE_BUCKET_BOUNDS.get(i).copied(),
+                count: counts.get(&(i as i64)).copied().unwrap_or(0),
+            })
+            .collect();
+
+        Ok(buckets)
+    }
+
+    async fn import_month_facets(
+        &self,
+        file_ids: SelectStatement,
+    ) -> RepoResult<Vec<MonthBucketDto>> {
+        let year_column = Alias::new("year");
+        let month_column = Alias::new("month");
+        let query = Query::select()
+            .expr_as(
+                Expr::cust("CAST(strftime('%Y', \"import_time\") AS INTEGER)"),
+                year_column.clone(),
+            )
+            .expr_as(
+                Expr::cust("CAST(strftime('%m', \"import_time\") AS INTEGER)"),
+                month_column.clone(),
+            )
+            .expr_as(file_metadata::Column::FileId.count(), Alias::new("count"))
+            .from(file_metadata::Entity)
+            .and_where(Expr::col(file_metadata::Column::FileId).in_subquery(file_ids))
+            .group_by_columns(vec![year_column.clone(), month_column.clone()])
+            .order_by(year_column, Order::Asc)
+            .order_by(month_column, Order::Asc)
+            .to_owned();
+        let buckets = self
+            .query_all::<MonthCount>(query)
+            .await?
+            .into_iter()
+            .map(|c| MonthBucketDto {
+                year: c.year,
+                month: c.month as u32,
+                count: c.count as u64,
+            })
+            .collect();
+
+        Ok(buckets)
+    }
+
+    async fn query_all<T: FromQueryResult>(&self, query: SelectStatement) -> RepoResult<Vec<T>> {
+        let statement = self.ctx.db.get_database_backend().build(&query);
+        let results = T::find_by_statement(statement).all(&self.ctx.db).await?;
+
+        Ok(results)
+    }
+}
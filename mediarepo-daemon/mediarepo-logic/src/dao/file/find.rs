@@ -1,16 +1,29 @@
+use std::fmt::Debug;
+
 use chrono::NaiveDateTime;
+use sea_orm::sea_query::{Alias, Expr, Query, SimpleExpr, SubQueryStatement};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
-use sea_orm::Condition;
-use sea_orm::sea_query::{Alias, Expr, Query, SimpleExpr};
+use sea_orm::{Condition, JoinType, Order, PaginatorTrait, QueryOrder, RelationTrait};
 
 use mediarepo_core::error::RepoResult;
+use mediarepo_database::entities::collection_file;
 use mediarepo_database::entities::content_descriptor;
+use mediarepo_database::entities::content_descriptor_source;
 use mediarepo_database::entities::content_descriptor_tag;
+use mediarepo_database::entities::content_descriptor_thumbnail;
+use mediarepo_database::entities::custom_field;
+use mediarepo_database::entities::custom_field_value;
 use mediarepo_database::entities::file;
 use mediarepo_database::entities::file_metadata;
+use mediarepo_database::entities::file_rating;
+use mediarepo_database::entities::file_relation;
+use mediarepo_database::entities::media_metadata;
+use mediarepo_database::entities::namespace;
+use mediarepo_database::entities::source;
+use mediarepo_database::entities::tag;
 
-use crate::dao::file::{FileDao, map_cd_and_file};
-use crate::dto::FileDto;
+use crate::dao::file::{map_cd_and_file, FileDao};
+use crate::dto::{FileDto, FileRelationType};
 
 macro_rules! apply_ordering_comparator {
     ($column:expr, $filter:expr) => {
@@ -25,13 +38,64 @@ macro_rules! apply_ordering_comparator {
     };
 }
 
+/// A boolean expression of filter properties
+#[derive(Clone, Debug)]
+pub enum FilterCondition {
+    And(Vec<FilterCondition>),
+    Or(Vec<FilterCondition>),
+    Not(Box<FilterCondition>),
+    Leaf(FilterProperty),
+    /// Matches no files. Used for filters that reference something that doesn't exist
+    Never,
+}
+
+impl From<Vec<Vec<FilterProperty>>> for FilterCondition {
+    /// Converts a list of OR-joined properties that are all required to match
+    fn from(filters: Vec<Vec<FilterProperty>>) -> Self {
+        FilterCondition::And(
+            filters
+                .into_iter()
+                .map(|expression| {
+                    FilterCondition::Or(expression.into_iter().map(FilterCondition::Leaf).collect())
+                })
+                .collect(),
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum FilterProperty {
     TagId(NegatableComparator<i64>),
     TagWildcardIds(NegatableComparator<Vec<i64>>),
+    /// Matches the normalized tag names (`namespace:name`) against a pattern
+    /// where `*` matches any text and `?` a single character. Wildcards
+    /// are matched literally when they are escaped with `\`
+    TagPattern(NegatableComparator<String>),
     ContentDescriptor(NegatableComparator<Vec<u8>>),
     TagCount(OrderingComparator<i64>),
     FileProperty(FilterFileProperty),
+    /// Files with any tag of the namespace
+    Namespace(NegatableComparator<String>),
+    /// Files without any tags or only files with tags if false
+    Untagged(bool),
+    /// Files with a source url on the domain or one of its subdomains
+    SourceDomain(NegatableComparator<String>),
+    /// Files with a source url matching the pattern where `*` matches any text
+    SourceUrl(NegatableComparator<String>),
+    /// Files without any thumbnails or only files with thumbnails if false
+    MissingThumbnail(bool),
+    MediaProperty(FilterMediaProperty),
+    /// Files with a rating matching the comparator. Unrated files never match
+    Rating(OrderingComparator<i64>),
+    /// Favorite files or only files that aren't favorites if false
+    Favorite(bool),
+    /// Files with a value for the custom field with the given id that matches
+    CustomField(i64, FilterCustomFieldValue),
+    /// Files that are the given type of relation of the related file
+    /// or of any file if no related file is given
+    Relation(FileRelationType, Option<i64>),
+    /// Files that are part of the collection with the given id
+    InCollection(i64),
 }
 
 #[derive(Clone, Debug)]
@@ -42,6 +106,65 @@ pub enum FilterFileProperty {
     ImportedTime(OrderingComparator<NaiveDateTime>),
     ChangedTime(OrderingComparator<NaiveDateTime>),
     CreatedTime(OrderingComparator<NaiveDateTime>),
+    NameText(String),
+    CommentText(String),
+    /// Matches the mime type against a pattern where `*` matches any text
+    MimeType(NegatableComparator<String>),
+}
+
+/// A property of the extracted media metadata. Files without
+/// media metadata never match
+#[derive(Clone, Debug)]
+pub enum FilterMediaProperty {
+    Width(OrderingComparator<i64>),
+    Height(OrderingComparator<i64>),
+    /// The duration in milliseconds
+    Duration(OrderingComparator<i64>),
+    /// The bitrate in bits per second
+    Bitrate(OrderingComparator<i64>),
+    /// The video codec compared case-insensitively
+    VideoCodec(NegatableComparator<String>),
+    /// The audio codec compared case-insensitively
+    AudioCodec(NegatableComparator<String>),
+}
+
+/// A comparison with the value of a custom field. The type of the
+/// compared value must match the type of the field
+#[derive(Clone, Debug)]
+pub enum FilterCustomFieldValue {
+    Int(OrderingComparator<i64>),
+    Float(OrderingComparator<f64>),
+    /// Texts are compared case-insensitively for equality where `*` matches any text
+    Text(OrderingComparator<String>),
+    Date(OrderingComparator<NaiveDateTime>),
+    Bool(bool),
+}
+
+/// A file property that can be sorted by in the database
+#[derive(Clone, Debug)]
+pub enum FileSortColumn {
+    Name,
+    Size,
+    MimeType,
+    ImportedTime,
+    CreatedTime,
+    ChangedTime,
+    TagCount,
+    Width,
+    Height,
+    Duration,
+    Bitrate,
+    Rating,
+    /// The value of the custom field with the given name
+    CustomField(String),
+    /// The tags of the namespace with the given name
+    Namespace(String),
+}
+
+#[derive(Clone, Debug)]
+pub enum FileOrdering {
+    Ascending(FileSortColumn),
+    Descending(FileSortColumn),
 }
 
 #[derive(Clone, Debug)]
@@ -61,8 +184,11 @@ pub enum NegatableComparator<T> {
 impl FileDao {
     /// Finds files by filters
     #[tracing::instrument(level = "debug", skip(self))]
-    pub async fn find(&self, filters: Vec<Vec<FilterProperty>>) -> RepoResult<Vec<FileDto>> {
-        let main_condition = build_find_filter_conditions(filters);
+    pub async fn find<F: Into<FilterCondition> + Debug>(
+        &self,
+        filters: F,
+    ) -> RepoResult<Vec<FileDto>> {
+        let main_condition = build_find_filter_conditions(filters.into());
 
         let files = content_descriptor::Entity::find()
             .find_also_related(file::Entity)
@@ -76,27 +202,210 @@ impl FileDao {
 
         Ok(files)
     }
+
+    /// Finds files by filters sorted by the given columns. Files with equal values
+    /// are sorted by their id so that pages stay stable
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn find_sorted<F: Into<FilterCondition> + Debug>(
+        &self,
+        filters: F,
+        ordering: Vec<FileOrdering>,
+        offset: u64,
+        limit: Option<u64>,
+    ) -> RepoResult<Vec<FileDto>> {
+        let query = content_descriptor::Entity::find()
+            .find_also_related(file::Entity)
+            .join(
+                JoinType::LeftJoin,
+                file_metadata::Relation::File.def().rev(),
+            )
+            .filter(build_find_filter_conditions(filters.into()))
+            .group_by(file::Column::Id);
+        let query = ordering
+            .into_iter()
+            .fold(query, |query, ordering| match ordering {
+                FileOrdering::Ascending(column) => {
+                    query.order_by(build_sort_column_expr(column), Order::Asc)
+                }
+                FileOrdering::Descending(column) => {
+                    query.order_by(build_sort_column_expr(column), Order::Desc)
+                }
+            })
+            .order_by_asc(file::Column::Id)
+            // sqlite only supports an offset together with a limit
+            .limit(limit.unwrap_or(i64::MAX as u64))
+            .offset(offset);
+
+        let files = query
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .filter_map(map_cd_and_file)
+            .collect();
+
+        Ok(files)
+    }
+
+    /// Returns the number of files matching the filters
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn count<F: Into<FilterCondition> + Debug>(&self, filters: F) -> RepoResult<u64> {
+        let count = content_descriptor::Entity::find()
+            .find_also_related(file::Entity)
+            .filter(build_find_filter_conditions(filters.into()))
+            .group_by(file::Column::Id)
+            .count(&self.ctx.db)
+            .await?;
+
+        Ok(count as u64)
+    }
 }
 
 #[tracing::instrument(level = "debug")]
-fn build_find_filter_conditions(filters: Vec<Vec<FilterProperty>>) -> Condition {
-    filters
-        .into_iter()
-        .fold(Condition::all(), |all_cond, mut expression| {
-            if expression.len() == 1 {
-                let property = expression.pop().unwrap();
-
-                all_cond.add(build_single_filter(property))
-            } else if !expression.is_empty() {
-                let sub_condition = expression.into_iter().fold(Condition::any(), |cond, prop| {
-                    cond.add(build_single_filter(prop))
-                });
-
-                all_cond.add(sub_condition)
-            } else {
-                all_cond
-            }
-        })
+pub(crate) fn build_find_filter_conditions(filter: FilterCondition) -> Condition {
+    match filter {
+        FilterCondition::And(conditions) => {
+            conditions.into_iter().fold(Condition::all(), |cond, c| {
+                cond.add(build_find_filter_conditions(c))
+            })
+        }
+        FilterCondition::Or(conditions) => {
+            conditions.into_iter().fold(Condition::any(), |cond, c| {
+                cond.add(build_find_filter_conditions(c))
+            })
+        }
+        FilterCondition::Not(condition) => build_find_filter_conditions(*condition).not(),
+        FilterCondition::Leaf(property) => Condition::all().add(build_single_filter(property)),
+        FilterCondition::Never => Condition::all().add(Expr::val(1).eq(0)),
+    }
+}
+
+fn build_sort_column_expr(column: FileSortColumn) -> SimpleExpr {
+    match column {
+        FileSortColumn::Name => {
+            Expr::tbl(file_metadata::Entity, file_metadata::Column::Name).into()
+        }
+        FileSortColumn::Size => {
+            Expr::tbl(file_metadata::Entity, file_metadata::Column::Size).into()
+        }
+        FileSortColumn::MimeType => Expr::tbl(file::Entity, file::Column::MimeType).into(),
+        FileSortColumn::ImportedTime => {
+            Expr::tbl(file_metadata::Entity, file_metadata::Column::ImportTime).into()
+        }
+        FileSortColumn::CreatedTime => {
+            Expr::tbl(file_metadata::Entity, file_metadata::Column::CreationTime).into()
+        }
+        FileSortColumn::ChangedTime => {
+            Expr::tbl(file_metadata::Entity, file_metadata::Column::ChangeTime).into()
+        }
+        FileSortColumn::TagCount => {
+            SimpleExpr::SubQuery(Box::new(SubQueryStatement::SelectStatement(
+                Query::select()
+                    .expr(content_descriptor_tag::Column::TagId.count())
+                    .from(content_descriptor_tag::Entity)
+                    .and_where(
+                        Expr::tbl(
+                            content_descriptor_tag::Entity,
+                            content_descriptor_tag::Column::CdId,
+                        )
+                        .equals(content_descriptor::Entity, content_descriptor::Column::Id),
+                    )
+                    .to_owned(),
+            )))
+        }
+        FileSortColumn::Width => build_media_sort_column_expr(media_metadata::Column::Width),
+        FileSortColumn::Height => build_media_sort_column_expr(media_metadata::Column::Height),
+        FileSortColumn::Duration => build_media_sort_column_expr(media_metadata::Column::Duration),
+        FileSortColumn::Bitrate => build_media_sort_column_expr(media_metadata::Column::Bitrate),
+        FileSortColumn::Rating => {
+            SimpleExpr::SubQuery(Box::new(SubQueryStatement::SelectStatement(
+                Query::select()
+                    .column(file_rating::Column::Rating)
+                    .from(file_rating::Entity)
+                    .and_where(
+                        Expr::tbl(file_rating::Entity, file_rating::Column::FileId)
+                            .equals(file::Entity, file::Column::Id),
+                    )
+                    .to_owned(),
+            )))
+        }
+        FileSortColumn::CustomField(name) => build_custom_field_sort_column_expr(name),
+        FileSortColumn::Namespace(name) => build_namespace_sort_column_expr(name),
+    }
+}
+
+/// Selects the smallest tag of a namespace. Numeric tags are compared by their value
+/// and sort before all other tags which are compared by their text. Files without
+/// tags in the namespace have no value and sort first
+fn build_namespace_sort_column_expr(name: String) -> SimpleExpr {
+    SimpleExpr::SubQuery(Box::new(SubQueryStatement::SelectStatement(
+        Query::select()
+            .expr(Expr::cust(
+                "min(case when \"tags\".\"name\" glob '*[0-9]*' and \"tags\".\"name\" not glob '*[^0-9.]*' \
+                 then cast(\"tags\".\"name\" as real) else \"tags\".\"name\" end)",
+            ))
+            .from(content_descriptor_tag::Entity)
+            .inner_join(
+                tag::Entity,
+                Expr::tbl(tag::Entity, tag::Column::Id).equals(
+                    content_descriptor_tag::Entity,
+                    content_descriptor_tag::Column::TagId,
+                ),
+            )
+            .inner_join(
+                namespace::Entity,
+                Expr::tbl(namespace::Entity, namespace::Column::Id)
+                    .equals(tag::Entity, tag::Column::NamespaceId),
+            )
+            .and_where(Expr::tbl(namespace::Entity, namespace::Column::Name).eq(name))
+            .and_where(
+                Expr::tbl(
+                    content_descriptor_tag::Entity,
+                    content_descriptor_tag::Column::CdId,
+                )
+                .equals(content_descriptor::Entity, content_descriptor::Column::Id),
+            )
+            .to_owned(),
+    )))
+}
+
+/// Selects the value of a custom field. Only the column of the field type
+/// is set so the first non-null column contains the value
+fn build_custom_field_sort_column_expr(name: String) -> SimpleExpr {
+    SimpleExpr::SubQuery(Box::new(SubQueryStatement::SelectStatement(
+        Query::select()
+            .expr(Expr::cust(
+                "coalesce(\"custom_field_values\".\"int_value\", \"custom_field_values\".\"float_value\", \
+                 \"custom_field_values\".\"text_value\", \"custom_field_values\".\"date_value\")",
+            ))
+            .from(custom_field_value::Entity)
+            .and_where(
+                custom_field_value::Column::FieldId.in_subquery(
+                    Query::select()
+                        .column(custom_field::Column::Id)
+                        .from(custom_field::Entity)
+                        .and_where(custom_field::Column::Name.eq(name))
+                        .to_owned(),
+                ),
+            )
+            .and_where(
+                Expr::tbl(custom_field_value::Entity, custom_field_value::Column::FileId)
+                    .equals(file::Entity, file::Column::Id),
+            )
+            .to_owned(),
+    )))
+}
+
+fn build_media_sort_column_expr(column: media_metadata::Column) -> SimpleExpr {
+    SimpleExpr::SubQuery(Box::new(SubQueryStatement::SelectStatement(
+        Query::select()
+            .column(column)
+            .from(media_metadata::Entity)
+            .and_where(
+                Expr::tbl(media_metadata::Entity, media_metadata::Column::CdId)
+                    .equals(content_descriptor::Entity, content_descriptor::Column::Id),
+            )
+            .to_owned(),
+    )))
 }
 
 #[inline]
@@ -106,11 +415,29 @@ fn build_single_filter(property: FilterProperty) -> SimpleExpr {
         FilterProperty::TagWildcardIds(wildcard_filter) => {
             build_tag_wildcard_ids_filter(wildcard_filter)
         }
+        FilterProperty::TagPattern(pattern_filter) => build_tag_pattern_filter(pattern_filter),
         FilterProperty::ContentDescriptor(cd_filter) => build_content_descriptor_filter(cd_filter),
         FilterProperty::TagCount(count_filter) => build_tag_count_filter(count_filter),
         FilterProperty::FileProperty(property_filter) => {
             build_file_property_filter(property_filter)
         }
+        FilterProperty::Namespace(namespace_filter) => build_namespace_filter(namespace_filter),
+        FilterProperty::Untagged(untagged) => build_untagged_filter(untagged),
+        FilterProperty::SourceDomain(domain_filter) => build_source_domain_filter(domain_filter),
+        FilterProperty::SourceUrl(url_filter) => build_source_url_filter(url_filter),
+        FilterProperty::MissingThumbnail(missing) => build_missing_thumbnail_filter(missing),
+        FilterProperty::MediaProperty(property_filter) => {
+            build_media_property_filter(property_filter)
+        }
+        FilterProperty::Rating(rating_filter) => build_rating_filter(rating_filter),
+        FilterProperty::Favorite(favorite) => build_favorite_filter(favorite),
+        FilterProperty::CustomField(field_id, value_filter) => {
+            build_custom_field_filter(field_id, value_filter)
+        }
+        FilterProperty::Relation(relation_type, related_file_id) => {
+            build_relation_filter(relation_type, related_file_id)
+        }
+        FilterProperty::InCollection(collection_id) => build_collection_filter(collection_id),
     }
 }
 
@@ -152,6 +479,42 @@ fn build_tag_wildcard_ids_filter(filter: NegatableComparator<Vec<i64>>) -> Simpl
     }
 }
 
+fn build_tag_pattern_filter(filter: NegatableComparator<String>) -> SimpleExpr {
+    let (pattern, negate) = match filter {
+        NegatableComparator::Is(pattern) => (pattern, false),
+        NegatableComparator::IsNot(pattern) => (pattern, true),
+    };
+    let pattern_subquery = Query::select()
+        .expr(Expr::tbl(
+            content_descriptor_tag::Entity,
+            content_descriptor_tag::Column::CdId,
+        ))
+        .from(content_descriptor_tag::Entity)
+        .inner_join(
+            tag::Entity,
+            Expr::tbl(tag::Entity, tag::Column::Id).equals(
+                content_descriptor_tag::Entity,
+                content_descriptor_tag::Column::TagId,
+            ),
+        )
+        .left_join(
+            namespace::Entity,
+            Expr::tbl(namespace::Entity, namespace::Column::Id)
+                .equals(tag::Entity, tag::Column::NamespaceId),
+        )
+        .and_where(Expr::cust_with_values(
+            "coalesce(\"namespaces\".\"name\" || ':', '') || \"tags\".\"name\" GLOB ?",
+            vec![escaped_pattern_to_glob(&pattern)],
+        ))
+        .to_owned();
+
+    if negate {
+        content_descriptor::Column::Id.not_in_subquery(pattern_subquery)
+    } else {
+        content_descriptor::Column::Id.in_subquery(pattern_subquery)
+    }
+}
+
 fn build_content_descriptor_filter(filter: NegatableComparator<Vec<u8>>) -> SimpleExpr {
     match filter {
         NegatableComparator::Is(cd) => content_descriptor::Column::Descriptor.eq(cd),
@@ -159,6 +522,162 @@ fn build_content_descriptor_filter(filter: NegatableComparator<Vec<u8>>) -> Simp
     }
 }
 
+fn build_missing_thumbnail_filter(missing: bool) -> SimpleExpr {
+    let thumbnail_subquery = Query::select()
+        .expr(Expr::col(content_descriptor_thumbnail::Column::CdId))
+        .from(content_descriptor_thumbnail::Entity)
+        .to_owned();
+
+    if missing {
+        content_descriptor::Column::Id.not_in_subquery(thumbnail_subquery)
+    } else {
+        content_descriptor::Column::Id.in_subquery(thumbnail_subquery)
+    }
+}
+
+fn build_namespace_filter(filter: NegatableComparator<String>) -> SimpleExpr {
+    let (name, negate) = match filter {
+        NegatableComparator::Is(name) => (name, false),
+        NegatableComparator::IsNot(name) => (name, true),
+    };
+    let namespace_subquery = Query::select()
+        .expr(Expr::tbl(
+            content_descriptor_tag::Entity,
+            content_descriptor_tag::Column::CdId,
+        ))
+        .from(content_descriptor_tag::Entity)
+        .inner_join(
+            tag::Entity,
+            Expr::tbl(tag::Entity, tag::Column::Id).equals(
+                content_descriptor_tag::Entity,
+                content_descriptor_tag::Column::TagId,
+            ),
+        )
+        .inner_join(
+            namespace::Entity,
+            Expr::tbl(namespace::Entity, namespace::Column::Id)
+                .equals(tag::Entity, tag::Column::NamespaceId),
+        )
+        .and_where(Expr::tbl(namespace::Entity, namespace::Column::Name).eq(name))
+        .to_owned();
+
+    if negate {
+        content_descriptor::Column::Id.not_in_subquery(namespace_subquery)
+    } else {
+        content_descriptor::Column::Id.in_subquery(namespace_subquery)
+    }
+}
+
+fn build_untagged_filter(untagged: bool) -> SimpleExpr {
+    let tagged_subquery = Query::select()
+        .expr(Expr::col(content_descriptor_tag::Column::CdId))
+        .from(content_descriptor_tag::Entity)
+        .to_owned();
+
+    if untagged {
+        content_descriptor::Column::Id.not_in_subquery(tagged_subquery)
+    } else {
+        content_descriptor::Column::Id.in_subquery(tagged_subquery)
+    }
+}
+
+/// Builds a filter for sources on a domain. The host of the url is matched with
+/// patterns as sqlite has no function to parse urls
+fn build_source_domain_filter(filter: NegatableComparator<String>) -> SimpleExpr {
+    let (domain, negate) = match filter {
+        NegatableComparator::Is(domain) => (domain, false),
+        NegatableComparator::IsNot(domain) => (domain, true),
+    };
+    let domain = to_glob_pattern(&domain.trim().trim_matches('.').to_lowercase(), &[]);
+    let patterns = vec![
+        format!("*://{}", domain),
+        format!("*://{}[/:?#]*", domain),
+        format!("*://*.{}", domain),
+        format!("*://*.{}[/:?#]*", domain),
+    ];
+    let url_condition = patterns
+        .into_iter()
+        .fold(Condition::any(), |condition, pattern| {
+            condition.add(Expr::cust_with_values(
+                "lower(\"sources\".\"url\") GLOB ?",
+                vec![pattern],
+            ))
+        });
+
+    build_source_filter(url_condition, negate)
+}
+
+/// Builds a case insensitive filter for source urls matching a pattern
+fn build_source_url_filter(filter: NegatableComparator<String>) -> SimpleExpr {
+    let (pattern, negate) = match filter {
+        NegatableComparator::Is(pattern) => (pattern, false),
+        NegatableComparator::IsNot(pattern) => (pattern, true),
+    };
+    let pattern = to_glob_pattern(&pattern.trim().to_lowercase(), &['*']);
+    let url_condition = Condition::all().add(Expr::cust_with_values(
+        "lower(\"sources\".\"url\") GLOB ?",
+        vec![pattern],
+    ));
+
+    build_source_filter(url_condition, negate)
+}
+
+/// Builds a filter for files with any source matching the condition
+fn build_source_filter(url_condition: Condition, negate: bool) -> SimpleExpr {
+    let source_subquery = Query::select()
+        .expr(Expr::tbl(
+            content_descriptor_source::Entity,
+            content_descriptor_source::Column::CdId,
+        ))
+        .from(content_descriptor_source::Entity)
+        .inner_join(
+            source::Entity,
+            Expr::tbl(source::Entity, source::Column::Id).equals(
+                content_descriptor_source::Entity,
+                content_descriptor_source::Column::SourceId,
+            ),
+        )
+        .cond_where(url_condition)
+        .to_owned();
+
+    if negate {
+        content_descriptor::Column::Id.not_in_subquery(source_subquery)
+    } else {
+        content_descriptor::Column::Id.in_subquery(source_subquery)
+    }
+}
+
+/// Converts a pattern to a glob pattern where only the given wildcards keep their meaning
+fn to_glob_pattern(pattern: &str, wildcards: &[char]) -> String {
+    pattern
+        .chars()
+        .map(|c| match c {
+            '*' | '?' | '[' if !wildcards.contains(&c) => format!("[{}]", c),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Converts a pattern where `*` and `?` are wildcards unless they are escaped
+/// with `\` to a glob pattern
+fn escaped_pattern_to_glob(pattern: &str) -> String {
+    let mut glob = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    glob.push_str(&to_glob_pattern(&escaped.to_string(), &[]));
+                }
+            }
+            c => glob.push_str(&to_glob_pattern(&c.to_string(), &['*', '?'])),
+        }
+    }
+
+    glob
+}
+
 fn build_tag_count_filter(filter: OrderingComparator<i64>) -> SimpleExpr {
     let count_column = Alias::new("count");
     let cd_id_column = Alias::new("cd_id");
@@ -204,7 +723,75 @@ fn build_file_property_filter(property: FilterFileProperty) -> SimpleExpr {
         FilterFileProperty::CreatedTime(time_filter) => {
             build_file_metadata_filter(build_file_created_time_filter(time_filter))
         }
+        FilterFileProperty::NameText(text) => build_file_text_filter("name", &text),
+        FilterFileProperty::CommentText(text) => build_file_text_filter("comment", &text),
+        FilterFileProperty::MimeType(mime_filter) => build_file_mime_type_filter(mime_filter),
+    }
+}
+
+/// Builds a full text search on a column of the file metadata.
+/// Files only match if they contain all terms of the text
+fn build_file_text_filter(column: &str, text: &str) -> SimpleExpr {
+    let match_expression = build_fts_match_expression(column, text);
+
+    if match_expression.is_empty() {
+        return SimpleExpr::Value(false.into());
+    }
+
+    file::Column::Id.in_subquery(
+        Query::select()
+            .expr(Expr::col(Alias::new("rowid")))
+            .from(Alias::new("file_metadata_fts"))
+            .and_where(Expr::cust_with_values(
+                "file_metadata_fts MATCH ?",
+                vec![match_expression],
+            ))
+            .to_owned(),
+    )
+}
+
+/// Converts a search text into an fts5 query on a single column.
+/// Double quoted parts of the text are matched as phrases and
+/// terms ending with `*` are matched as prefixes
+fn build_fts_match_expression(column: &str, text: &str) -> String {
+    let mut terms = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let term = match c {
+            c if c.is_whitespace() => continue,
+            '"' => {
+                let mut phrase = String::new();
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        break;
+                    }
+                    phrase.push(c);
+                }
+                phrase
+            }
+            c => {
+                let mut word = String::from(c);
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '"') {
+                    word.push(c);
+                }
+                word
+            }
+        };
+        let prefix = term.ends_with('*') || chars.next_if_eq(&'*').is_some();
+        let term = term.trim_end_matches('*');
+
+        if !term.trim().is_empty() {
+            terms.push(format!(
+                "{} : \"{}\"{}",
+                column,
+                term.replace('"', "\"\""),
+                if prefix { "*" } else { "" }
+            ));
+        }
     }
+
+    terms.join(" AND ")
 }
 
 fn build_file_id_filter(filter: NegatableComparator<i64>) -> SimpleExpr {
@@ -221,6 +808,21 @@ fn build_file_status_filter(filter: NegatableComparator<i64>) -> SimpleExpr {
     }
 }
 
+fn build_file_mime_type_filter(filter: NegatableComparator<String>) -> SimpleExpr {
+    let (pattern, negate) = match filter {
+        NegatableComparator::Is(pattern) => (pattern, false),
+        NegatableComparator::IsNot(pattern) => (pattern, true),
+    };
+    let pattern = to_glob_pattern(&pattern.trim().to_lowercase(), &['*']);
+    let sql = if negate {
+        "lower(\"files\".\"mime_type\") NOT GLOB ?"
+    } else {
+        "lower(\"files\".\"mime_type\") GLOB ?"
+    };
+
+    Expr::cust_with_values(sql, vec![pattern])
+}
+
 fn build_file_metadata_filter(property_condition: SimpleExpr) -> SimpleExpr {
     file::Column::Id.in_subquery(
         Query::select()
@@ -246,3 +848,172 @@ fn build_file_changed_time_filter(filter: OrderingComparator<NaiveDateTime>) ->
 fn build_file_created_time_filter(filter: OrderingComparator<NaiveDateTime>) -> SimpleExpr {
     apply_ordering_comparator!(file_metadata::Column::CreationTime, filter)
 }
+
+fn build_media_property_filter(property: FilterMediaProperty) -> SimpleExpr {
+    let condition = match property {
+        FilterMediaProperty::Width(filter) => {
+            apply_ordering_comparator!(media_metadata::Column::Width, filter)
+        }
+        FilterMediaProperty::Height(filter) => {
+            apply_ordering_comparator!(media_metadata::Column::Height, filter)
+        }
+        FilterMediaProperty::Duration(filter) => {
+            apply_ordering_comparator!(media_metadata::Column::Duration, filter)
+        }
+        FilterMediaProperty::Bitrate(filter) => {
+            apply_ordering_comparator!(media_metadata::Column::Bitrate, filter)
+        }
+        FilterMediaProperty::VideoCodec(filter) => build_codec_condition("video_codec", filter),
+        FilterMediaProperty::AudioCodec(filter) => build_codec_condition("audio_codec", filter),
+    };
+
+    content_descriptor::Column::Id.in_subquery(
+        Query::select()
+            .expr(Expr::col(media_metadata::Column::CdId))
+            .from(media_metadata::Entity)
+            .cond_where(condition)
+            .to_owned(),
+    )
+}
+
+/// Builds the condition for a codec. Negated conditions also match
+/// media metadata without a codec of that kind
+fn build_codec_condition(column: &str, filter: NegatableComparator<String>) -> SimpleExpr {
+    match filter {
+        NegatableComparator::Is(codec) => Expr::cust_with_values(
+            &format!("lower(\"media_metadata\".\"{}\") = ?", column),
+            vec![codec.trim().to_lowercase()],
+        ),
+        NegatableComparator::IsNot(codec) => Expr::cust_with_values(
+            &format!(
+                "(\"media_metadata\".\"{0}\" IS NULL OR lower(\"media_metadata\".\"{0}\") != ?)",
+                column
+            ),
+            vec![codec.trim().to_lowercase()],
+        ),
+    }
+}
+
+fn build_rating_filter(filter: OrderingComparator<i64>) -> SimpleExpr {
+    file::Column::Id.in_subquery(
+        Query::select()
+            .expr(Expr::col(file_rating::Column::FileId))
+            .from(file_rating::Entity)
+            .cond_where(apply_ordering_comparator!(
+                file_rating::Column::Rating,
+                filter
+            ))
+            .to_owned(),
+    )
+}
+
+fn build_favorite_filter(favorite: bool) -> SimpleExpr {
+    let favorites_subquery = Query::select()
+        .expr(Expr::col(file_rating::Column::FileId))
+        .from(file_rating::Entity)
+        .cond_where(file_rating::Column::Favorite.eq(true))
+        .to_owned();
+
+    if favorite {
+        file::Column::Id.in_subquery(favorites_subquery)
+    } else {
+        file::Column::Id.not_in_subquery(favorites_subquery)
+    }
+}
+
+fn build_custom_field_filter(field_id: i64, filter: FilterCustomFieldValue) -> SimpleExpr {
+    let condition = match filter {
+        FilterCustomFieldValue::Int(filter) => {
+            apply_ordering_comparator!(custom_field_value::Column::IntValue, filter)
+        }
+        FilterCustomFieldValue::Float(filter) => {
+            apply_ordering_comparator!(custom_field_value::Column::FloatValue, filter)
+        }
+        FilterCustomFieldValue::Text(OrderingComparator::Equal(pattern)) => Expr::cust_with_values(
+            "lower(\"custom_field_values\".\"text_value\") GLOB ?",
+            vec![to_glob_pattern(&pattern.trim().to_lowercase(), &['*'])],
+        ),
+        FilterCustomFieldValue::Text(filter) => {
+            apply_ordering_comparator!(custom_field_value::Column::TextValue, filter)
+        }
+        FilterCustomFieldValue::Date(filter) => {
+            apply_ordering_comparator!(custom_field_value::Column::DateValue, filter)
+        }
+        FilterCustomFieldValue::Bool(value) => {
+            custom_field_value::Column::IntValue.eq(value as i64)
+        }
+    };
+
+    file::Column::Id.in_subquery(
+        Query::select()
+            .expr(Expr::col(custom_field_value::Column::FileId))
+            .from(custom_field_value::Entity)
+            .and_where(custom_field_value::Column::FieldId.eq(field_id))
+            .and_where(condition)
+            .to_owned(),
+    )
+}
+
+fn build_collection_filter(collection_id: i64) -> SimpleExpr {
+    file::Column::Id.in_subquery(
+        Query::select()
+            .expr(Expr::col(collection_file::Column::FileId))
+            .from(collection_file::Entity)
+            .cond_where(collection_file::Column::CollectionId.eq(collection_id))
+            .to_owned(),
+    )
+}
+
+/// Builds the relation filter for the stored form of relations
+/// where child relations are stored as parent relations and
+/// alternates can be stored in either direction
+fn build_relation_filter(
+    relation_type: FileRelationType,
+    related_file_id: Option<i64>,
+) -> SimpleExpr {
+    match relation_type {
+        FileRelationType::Child => build_relation_subquery_filter(
+            FileRelationType::Parent,
+            file_relation::Column::RelatedFileId,
+            file_relation::Column::FileId,
+            related_file_id,
+        ),
+        FileRelationType::Alternate => build_relation_subquery_filter(
+            relation_type,
+            file_relation::Column::FileId,
+            file_relation::Column::RelatedFileId,
+            related_file_id,
+        )
+        .or(build_relation_subquery_filter(
+            relation_type,
+            file_relation::Column::RelatedFileId,
+            file_relation::Column::FileId,
+            related_file_id,
+        )),
+        _ => build_relation_subquery_filter(
+            relation_type,
+            file_relation::Column::FileId,
+            file_relation::Column::RelatedFileId,
+            related_file_id,
+        ),
+    }
+}
+
+fn build_relation_subquery_filter(
+    relation_type: FileRelationType,
+    file_column: file_relation::Column,
+    related_column: file_relation::Column,
+    related_file_id: Option<i64>,
+) -> SimpleExpr {
+    let mut subquery = Query::select();
+    subquery
+        .expr(Expr::col(file_column))
+        .from(file_relation::Entity)
+        .and_where(file_relation::Column::RelationType.eq(relation_type.to_number()));
+
+    if let Some(related_file_id) = related_file_id {
+        subquery.and_where(related_column.eq(related_file_id));
+    }
+
+    file::Column::Id.in_subquery(subquery.to_owned())
+}
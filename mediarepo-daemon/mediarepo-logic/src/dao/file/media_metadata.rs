@@ -0,0 +1,100 @@
+use sea_orm::prelude::*;
+use sea_orm::sea_query::Query;
+use sea_orm::ActiveValue::Set;
+use sea_orm::TransactionTrait;
+
+use mediarepo_core::bincode;
+use mediarepo_core::error::{RepoError, RepoResult};
+use mediarepo_core::fs::range_reader::FileRangeReader;
+use mediarepo_core::media_metadata::{extract_media_metadata, supports_media_metadata};
+use mediarepo_database::entities::{content_descriptor, file, media_metadata};
+
+use crate::dao::file::{map_file_and_cd, FileDao};
+use crate::dto::{FileDto, MediaMetadataDto};
+
+impl FileDao {
+    /// Returns the media metadata of a content descriptor
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn media_metadata(&self, cd_id: i64) -> RepoResult<Option<MediaMetadataDto>> {
+        media_metadata::Entity::find_by_id(cd_id)
+            .one(&self.ctx.db)
+            .await?
+            .map(MediaMetadataDto::new)
+            .transpose()
+    }
+
+    /// Returns the media metadata of all given content descriptors that have any
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all_media_metadata(&self, cd_ids: Vec<i64>) -> RepoResult<Vec<MediaMetadataDto>> {
+        if cd_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        media_metadata::Entity::find()
+            .filter(media_metadata::Column::CdId.is_in(cd_ids))
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(MediaMetadataDto::new)
+            .collect()
+    }
+
+    /// Returns all supported files that don't have media metadata yet
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all_without_media_metadata(&self) -> RepoResult<Vec<FileDto>> {
+        let files = file::Entity::find()
+            .find_also_related(content_descriptor::Entity)
+            .filter(
+                file::Column::CdId.not_in_subquery(
+                    Query::select()
+                        .column(media_metadata::Column::CdId)
+                        .from(media_metadata::Entity)
+                        .to_owned(),
+                ),
+            )
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .filter_map(map_file_and_cd)
+            .filter(|f| supports_media_metadata(f.mime_type()))
+            .collect();
+
+        Ok(files)
+    }
+
+    /// Extracts and stores the media metadata of a file.
+    /// The parsing runs on a blocking thread and only reads the required parts of the file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn create_media_metadata(&self, file: &FileDto) -> RepoResult<MediaMetadataDto> {
+        let reader =
+            FileRangeReader::open(self.ctx.main_storage.clone(), file.cd().to_vec()).await?;
+        let mime_type = file.mime_type().to_owned();
+        let metadata =
+            tokio::task::spawn_blocking(move || extract_media_metadata(reader, &mime_type))
+                .await
+                .map_err(|e| RepoError::from(e.to_string().as_str()))??;
+        let trx = self.ctx.db.begin().await?;
+
+        media_metadata::Entity::delete_many()
+            .filter(media_metadata::Column::CdId.eq(file.cd_id()))
+            .exec(&trx)
+            .await?;
+        let model = media_metadata::ActiveModel {
+            cd_id: Set(file.cd_id()),
+            width: Set(metadata.width.map(i64::from)),
+            height: Set(metadata.height.map(i64::from)),
+            duration: Set(metadata.duration.map(|d| d as i64)),
+            frame_count: Set(metadata.frame_count.map(|c| c as i64)),
+            video_codec: Set(metadata.video_codec),
+            audio_codec: Set(metadata.audio_codec),
+            bitrate: Set(metadata.bitrate.map(|b| b as i64)),
+            orientation: Set(metadata.orientation.map(i32::from)),
+            exif: Set(bincode::serialize(&metadata.exif)?),
+        }
+        .insert(&trx)
+        .await?;
+        trx.commit().await?;
+
+        MediaMetadataDto::new(model)
+    }
+}
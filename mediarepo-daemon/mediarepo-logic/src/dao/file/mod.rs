@@ -9,8 +9,13 @@ use crate::dto::{FileDto, FileMetadataDto, ThumbnailDto};
 
 pub mod add;
 pub mod delete;
+pub mod facets;
 pub mod find;
+pub mod media_metadata;
+pub mod perceptual_hash;
+pub mod rating;
 pub mod update;
+pub mod upload;
 
 dao_provider!(FileDao);
 
@@ -76,6 +81,24 @@ impl FileDao {
         Ok(files)
     }
 
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all_by_cd_id(&self, cd_ids: Vec<i64>) -> RepoResult<Vec<FileDto>> {
+        if cd_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let files = file::Entity::find()
+            .find_also_related(content_descriptor::Entity)
+            .filter(file::Column::CdId.is_in(cd_ids))
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .filter_map(map_file_and_cd)
+            .collect();
+
+        Ok(files)
+    }
+
     pub async fn metadata(&self, file_id: i64) -> RepoResult<Option<FileMetadataDto>> {
         self.all_metadata(vec![file_id])
             .await
@@ -108,8 +131,13 @@ impl FileDao {
             .get_thumbnails(&encoded_cd)
             .await?
             .into_iter()
-            .map(|(size, path)| {
-                ThumbnailDto::new(path, encoded_cd.clone(), size, String::from("image/png"))
+            .map(|(size, format)| {
+                ThumbnailDto::new(
+                    self.ctx.thumbnail_storage.clone(),
+                    encoded_cd.clone(),
+                    size,
+                    format.mime_type().to_string(),
+                )
             })
             .collect();
 
@@ -119,14 +147,28 @@ impl FileDao {
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn get_bytes(&self, cd: &[u8]) -> RepoResult<Vec<u8>> {
         let mut buf = Vec::new();
-        let mut reader = self.ctx.main_storage.get_file(cd).await?.1;
+        let mut reader = self.ctx.main_storage.get_file(cd).await?;
         reader.read_to_end(&mut buf).await?;
 
         Ok(buf)
     }
+
+    /// Reads a range of the files contents and returns it together with the total size
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_range(
+        &self,
+        cd: &[u8],
+        offset: u64,
+        length: u64,
+    ) -> RepoResult<(Vec<u8>, u64)> {
+        self.ctx
+            .main_storage
+            .get_file_range(cd, offset, length)
+            .await
+    }
 }
 
-fn map_file_and_cd(
+pub(crate) fn map_file_and_cd(
     (file, cd): (file::Model, Option<content_descriptor::Model>),
 ) -> Option<FileDto> {
     cd.map(|c| FileDto::new(file, c, None))
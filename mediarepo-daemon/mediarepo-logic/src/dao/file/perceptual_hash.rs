@@ -0,0 +1,63 @@
+use sea_orm::prelude::*;
+use sea_orm::sea_query::Query;
+use sea_orm::ActiveValue::Set;
+use sea_orm::TransactionTrait;
+
+use mediarepo_core::error::RepoResult;
+use mediarepo_core::perceptual_hash::create_perceptual_hash;
+use mediarepo_database::entities::{content_descriptor, file, perceptual_hash};
+
+use crate::dao::file::{map_file_and_cd, FileDao};
+use crate::dto::FileDto;
+
+impl FileDao {
+    /// Returns all image files that don't have a perceptual hash yet
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all_without_perceptual_hash(&self) -> RepoResult<Vec<FileDto>> {
+        let files = file::Entity::find()
+            .find_also_related(content_descriptor::Entity)
+            .filter(file::Column::MimeType.starts_with("image/"))
+            .filter(
+                file::Column::CdId.not_in_subquery(
+                    Query::select()
+                        .column(perceptual_hash::Column::CdId)
+                        .from(perceptual_hash::Entity)
+                        .to_owned(),
+                ),
+            )
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .filter_map(map_file_and_cd)
+            .collect();
+
+        Ok(files)
+    }
+
+    /// Computes and stores the perceptual hash of an image file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn create_perceptual_hash(&self, file: &FileDto) -> RepoResult<u64> {
+        let bytes = self.get_bytes(file.cd()).await?;
+        let hash = create_perceptual_hash(&bytes)?;
+        let trx = self.ctx.db.begin().await?;
+
+        perceptual_hash::Entity::delete_many()
+            .filter(perceptual_hash::Column::CdId.eq(file.cd_id()))
+            .exec(&trx)
+            .await?;
+        perceptual_hash::ActiveModel {
+            cd_id: Set(file.cd_id()),
+            hash: Set(hash as i64),
+        }
+        .insert(&trx)
+        .await?;
+        trx.commit().await?;
+
+        Ok(hash)
+    }
+}
+
+/// Returns if a perceptual hash can be created for files of the given mime type
+pub(crate) fn supports_perceptual_hash(mime_type: &str) -> bool {
+    mime_type.starts_with("image/")
+}
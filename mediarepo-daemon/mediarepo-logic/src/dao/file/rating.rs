@@ -0,0 +1,90 @@
+use sea_orm::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::TransactionTrait;
+
+use mediarepo_core::error::{RepoError, RepoResult};
+use mediarepo_database::entities::file_rating;
+
+use crate::dao::file::FileDao;
+use crate::dto::{FileRatingDto, UpdateFileRatingDto};
+
+/// The highest rating a file can have
+pub const MAX_RATING: u32 = 10;
+
+impl FileDao {
+    /// Returns the rating of a file. Files that haven't been rated
+    /// have no rating and aren't favorites
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn rating(&self, file_id: i64) -> RepoResult<FileRatingDto> {
+        let rating = file_rating::Entity::find_by_id(file_id)
+            .one(&self.ctx.db)
+            .await?
+            .map(FileRatingDto::new)
+            .unwrap_or_else(|| FileRatingDto::unrated(file_id));
+
+        Ok(rating)
+    }
+
+    /// Returns the ratings of all given files that have been rated
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all_ratings(&self, file_ids: Vec<i64>) -> RepoResult<Vec<FileRatingDto>> {
+        if file_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let ratings = file_rating::Entity::find()
+            .filter(file_rating::Column::FileId.is_in(file_ids))
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(FileRatingDto::new)
+            .collect();
+
+        Ok(ratings)
+    }
+
+    /// Updates the rating and favorite flag of a file if they are set in the dto
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn update_rating(
+        &self,
+        update_dto: UpdateFileRatingDto,
+    ) -> RepoResult<FileRatingDto> {
+        if let Some(Some(rating)) = update_dto.rating {
+            if rating > MAX_RATING {
+                return Err(RepoError::from(
+                    format!("rating must be between 0 and {}", MAX_RATING).as_str(),
+                ));
+            }
+        }
+        let trx = self.ctx.db.begin().await?;
+        let existing = file_rating::Entity::find_by_id(update_dto.file_id)
+            .one(&trx)
+            .await?;
+        let rating = update_dto.rating.map(|r| r.map(|r| r as i32));
+
+        let model = match existing {
+            Some(existing) if rating.is_none() && update_dto.favorite.is_none() => existing,
+            Some(existing) => {
+                let mut active_model: file_rating::ActiveModel = existing.into();
+                if let Some(rating) = rating {
+                    active_model.rating = Set(rating);
+                }
+                if let Some(favorite) = update_dto.favorite {
+                    active_model.favorite = Set(favorite);
+                }
+                active_model.update(&trx).await?
+            }
+            None => {
+                file_rating::ActiveModel {
+                    file_id: Set(update_dto.file_id),
+                    rating: Set(rating.flatten()),
+                    favorite: Set(update_dto.favorite.unwrap_or(false)),
+                }
+                .insert(&trx)
+                .await?
+            }
+        };
+        trx.commit().await?;
+
+        Ok(FileRatingDto::new(model))
+    }
+}
@@ -4,13 +4,15 @@ use std::str::FromStr;
 
 use sea_orm::prelude::*;
 use sea_orm::ActiveValue::{Set, Unchanged};
-use sea_orm::{NotSet, TransactionTrait};
+use sea_orm::{ConnectionTrait, DbBackend, NotSet, Statement, TransactionTrait};
 
 use mediarepo_core::error::{RepoError, RepoResult};
 use mediarepo_core::fs::thumbnail_store::Dimensions;
 use mediarepo_core::thumbnailer;
 use mediarepo_core::thumbnailer::ThumbnailSize;
-use mediarepo_database::entities::{content_descriptor, file, file_metadata};
+use mediarepo_database::entities::{
+    content_descriptor, content_descriptor_thumbnail, file, file_metadata,
+};
 
 use crate::dao::file::FileDao;
 use crate::dao::opt_to_active_val;
@@ -25,6 +27,7 @@ impl FileDao {
             cd_id: update_dto.cd_id.map(Set).unwrap_or(NotSet),
             mime_type: update_dto.mime_type.map(Set).unwrap_or(NotSet),
             status: update_dto.status.map(|v| Set(v as i32)).unwrap_or(NotSet),
+            import_mode: NotSet,
         };
         let file_model = model.update(&trx).await?;
         let cd = file_model
@@ -42,19 +45,29 @@ impl FileDao {
         &self,
         update_dto: UpdateFileMetadataDto,
     ) -> RepoResult<FileMetadataDto> {
-        let model = file_metadata::ActiveModel {
-            file_id: Unchanged(update_dto.file_id),
-            name: opt_to_active_val(update_dto.name),
-            comment: opt_to_active_val(update_dto.comment),
-            size: opt_to_active_val(update_dto.size),
-            change_time: opt_to_active_val(update_dto.change_time),
-            ..Default::default()
-        };
-        let metadata = model.update(&self.ctx.db).await?;
+        let metadata = update_metadata_model(&self.ctx.db, update_dto).await?;
 
         Ok(FileMetadataDto::new(metadata))
     }
 
+    /// Updates the metadata of multiple files in a single transaction
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn update_all_metadata(
+        &self,
+        update_dtos: Vec<UpdateFileMetadataDto>,
+    ) -> RepoResult<Vec<FileMetadataDto>> {
+        let trx = self.ctx.db.begin().await?;
+        let mut metadata = Vec::with_capacity(update_dtos.len());
+
+        for update_dto in update_dtos {
+            let model = update_metadata_model(&trx, update_dto).await?;
+            metadata.push(FileMetadataDto::new(model));
+        }
+        trx.commit().await?;
+
+        Ok(metadata)
+    }
+
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn create_thumbnails<I: IntoIterator<Item = ThumbnailSize> + Debug>(
         &self,
@@ -64,32 +77,84 @@ impl FileDao {
         let bytes = self.get_bytes(file.cd()).await?;
         let mime_type =
             mime::Mime::from_str(file.mime_type()).unwrap_or(mime::APPLICATION_OCTET_STREAM);
-        let thumbnails =
-            thumbnailer::create_thumbnails(Cursor::new(bytes), mime_type.clone(), sizes)?;
+        let thumbnails = thumbnailer::create_thumbnails(Cursor::new(bytes), mime_type, sizes)?;
         let mut dtos = Vec::new();
 
+        let thumbnail_mime_type = self.ctx.thumbnail_storage.settings().format.mime_type();
+
         for thumbnail in thumbnails {
-            let mut buf = Cursor::new(Vec::new());
             let size = thumbnail.size();
             let size = Dimensions {
                 height: size.1,
                 width: size.0,
             };
-            thumbnail.write_png(&mut buf)?;
+            let data = self.ctx.thumbnail_storage.encode_thumbnail(thumbnail)?;
 
-            let path = self
-                .ctx
+            self.ctx
                 .thumbnail_storage
-                .add_thumbnail(file.encoded_cd(), size.clone(), &buf.into_inner())
+                .add_thumbnail(file.encoded_cd(), size.clone(), &data)
                 .await?;
             dtos.push(ThumbnailDto::new(
-                path,
+                self.ctx.thumbnail_storage.clone(),
                 file.encoded_cd(),
                 size,
-                mime_type.to_string(),
+                thumbnail_mime_type.to_string(),
             ))
         }
+        if !dtos.is_empty() {
+            self.ctx
+                .db
+                .execute(Statement::from_sql_and_values(
+                    DbBackend::Sqlite,
+                    "INSERT OR IGNORE INTO content_descriptor_thumbnails (cd_id) VALUES (?)",
+                    vec![file.cd_id().into()],
+                ))
+                .await?;
+        }
 
         Ok(dtos)
     }
+
+    /// Deletes all thumbnails of a file
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete_thumbnails(&self, file: &FileDto) -> RepoResult<()> {
+        let thumbnails = self.thumbnails(file.encoded_cd()).await?;
+
+        for thumb in thumbnails {
+            thumb.delete().await?;
+        }
+        content_descriptor_thumbnail::Entity::delete_many()
+            .filter(content_descriptor_thumbnail::Column::CdId.eq(file.cd_id()))
+            .exec(&self.ctx.db)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Updates the metadata of a file or returns it unchanged if the update is empty
+async fn update_metadata_model<C: ConnectionTrait>(
+    db: &C,
+    update_dto: UpdateFileMetadataDto,
+) -> RepoResult<file_metadata::Model> {
+    let file_id = update_dto.file_id;
+
+    if update_dto.is_empty() {
+        file_metadata::Entity::find_by_id(file_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| RepoError::from("file metadata not found"))
+    } else {
+        let model = file_metadata::ActiveModel {
+            file_id: Unchanged(file_id),
+            name: opt_to_active_val(update_dto.name),
+            comment: opt_to_active_val(update_dto.comment),
+            size: opt_to_active_val(update_dto.size),
+            creation_time: opt_to_active_val(update_dto.creation_time),
+            change_time: opt_to_active_val(update_dto.change_time),
+            ..Default::default()
+        };
+
+        Ok(model.update(db).await?)
+    }
 }
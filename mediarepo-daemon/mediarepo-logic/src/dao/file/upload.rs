@@ -0,0 +1,37 @@
+use mediarepo_core::error::RepoResult;
+
+use crate::dao::file::FileDao;
+
+impl FileDao {
+    /// Starts a new chunked upload and returns its id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn begin_upload(&self) -> RepoResult<String> {
+        self.ctx.main_storage.begin_upload().await
+    }
+
+    /// Returns the number of bytes that have been received for an upload
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn upload_offset(&self, upload_id: &str) -> RepoResult<u64> {
+        self.ctx.main_storage.upload_offset(upload_id).await
+    }
+
+    /// Appends a chunk to an upload and returns the new offset
+    #[tracing::instrument(level = "debug", skip(self, bytes))]
+    pub async fn append_upload_chunk(
+        &self,
+        upload_id: &str,
+        offset: u64,
+        bytes: &[u8],
+    ) -> RepoResult<u64> {
+        self.ctx
+            .main_storage
+            .append_upload_chunk(upload_id, offset, bytes)
+            .await
+    }
+
+    /// Discards an upload that hasn't been committed
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn abort_upload(&self, upload_id: &str) -> RepoResult<()> {
+        self.ctx.main_storage.abort_upload(upload_id).await
+    }
+}
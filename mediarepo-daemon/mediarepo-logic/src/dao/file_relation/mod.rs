@@ -0,0 +1,111 @@
+use sea_orm::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{Condition, QueryOrder};
+
+use mediarepo_core::error::{RepoError, RepoResult};
+use mediarepo_database::entities::file_relation;
+
+use crate::dao_provider;
+use crate::dto::{FileRelationDto, FileRelationType};
+
+dao_provider!(FileRelationDao);
+
+impl FileRelationDao {
+    /// Returns all relations the file is part of in either direction
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn for_file(&self, file_id: i64) -> RepoResult<Vec<FileRelationDto>> {
+        let relations = file_relation::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(file_relation::Column::FileId.eq(file_id))
+                    .add(file_relation::Column::RelatedFileId.eq(file_id)),
+            )
+            .order_by_asc(file_relation::Column::RelationType)
+            .order_by_asc(file_relation::Column::FileId)
+            .order_by_asc(file_relation::Column::RelatedFileId)
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .filter_map(FileRelationDto::new)
+            .collect();
+
+        Ok(relations)
+    }
+
+    /// Adds a relation that reads as "the file is the `relation_type` of the related file".
+    /// Adding a relation that already exists does nothing
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add(
+        &self,
+        file_id: i64,
+        related_file_id: i64,
+        relation_type: FileRelationType,
+    ) -> RepoResult<()> {
+        if file_id == related_file_id {
+            return Err(RepoError::from("a file can't be related to itself"));
+        }
+        let (file_id, related_file_id, relation_type) =
+            normalize_relation(file_id, related_file_id, relation_type);
+        let existing = file_relation::Entity::find()
+            .filter(relation_condition(file_id, related_file_id, relation_type))
+            .one(&self.ctx.db)
+            .await?;
+
+        if existing.is_none() {
+            file_relation::Entity::insert_many(vec![file_relation::ActiveModel {
+                file_id: Set(file_id),
+                related_file_id: Set(related_file_id),
+                relation_type: Set(relation_type.to_number()),
+            }])
+            .exec(&self.ctx.db)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes a relation between two files
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn remove(
+        &self,
+        file_id: i64,
+        related_file_id: i64,
+        relation_type: FileRelationType,
+    ) -> RepoResult<()> {
+        let (file_id, related_file_id, relation_type) =
+            normalize_relation(file_id, related_file_id, relation_type);
+        file_relation::Entity::delete_many()
+            .filter(relation_condition(file_id, related_file_id, relation_type))
+            .exec(&self.ctx.db)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Brings a relation into the form it is stored in. Child relations are stored as
+/// parent relations in the opposite direction and alternates with the lower file id first
+fn normalize_relation(
+    file_id: i64,
+    related_file_id: i64,
+    relation_type: FileRelationType,
+) -> (i64, i64, FileRelationType) {
+    match relation_type {
+        FileRelationType::Child => (related_file_id, file_id, FileRelationType::Parent),
+        FileRelationType::Alternate if file_id > related_file_id => {
+            (related_file_id, file_id, relation_type)
+        }
+        _ => (file_id, related_file_id, relation_type),
+    }
+}
+
+fn relation_condition(
+    file_id: i64,
+    related_file_id: i64,
+    relation_type: FileRelationType,
+) -> Condition {
+    Condition::all()
+        .add(file_relation::Column::FileId.eq(file_id))
+        .add(file_relation::Column::RelatedFileId.eq(related_file_id))
+        .add(file_relation::Column::RelationType.eq(relation_type.to_number()))
+}
@@ -0,0 +1,126 @@
+use sea_orm::prelude::*;
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{Condition, QueryOrder, TransactionTrait};
+
+use mediarepo_core::error::RepoResult;
+use mediarepo_database::entities::{content_descriptor, file, integrity_finding};
+
+use crate::dao::file::map_file_and_cd;
+use crate::dao_provider;
+use crate::dto::{AddIntegrityFindingDto, FileDto, FileStatus, IntegrityFindingDto};
+
+dao_provider!(IntegrityDao);
+
+impl IntegrityDao {
+    /// Returns all findings of the file verification
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all(&self) -> RepoResult<Vec<IntegrityFindingDto>> {
+        let findings = integrity_finding::Entity::find()
+            .find_also_related(content_descriptor::Entity)
+            .order_by_asc(integrity_finding::Column::Id)
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(|(finding, cd)| IntegrityFindingDto::new(finding, cd))
+            .collect();
+
+        Ok(findings)
+    }
+
+    /// Adds findings replacing existing findings of the same kind for the same storage key
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add_all(&self, findings: Vec<AddIntegrityFindingDto>) -> RepoResult<()> {
+        if findings.is_empty() {
+            return Ok(());
+        }
+        let trx = self.ctx.db.begin().await?;
+        let existing_condition = findings
+            .iter()
+            .map(|f| {
+                Condition::all()
+                    .add(integrity_finding::Column::Kind.eq(f.kind))
+                    .add(integrity_finding::Column::StorageKey.eq(f.storage_key.clone()))
+            })
+            .fold(Condition::any(), |acc, cond| acc.add(cond));
+        integrity_finding::Entity::delete_many()
+            .filter(existing_condition)
+            .exec(&trx)
+            .await?;
+
+        let detected_at = chrono::Local::now().naive_local();
+        let models: Vec<integrity_finding::ActiveModel> = findings
+            .into_iter()
+            .map(|f| integrity_finding::ActiveModel {
+                kind: Set(f.kind),
+                storage_key: Set(f.storage_key),
+                cd_id: Set(f.cd_id),
+                detected_at: Set(detected_at),
+                ..Default::default()
+            })
+            .collect();
+        integrity_finding::Entity::insert_many(models)
+            .exec(&trx)
+            .await?;
+        trx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Deletes the findings with the given ids
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete(&self, ids: Vec<i64>) -> RepoResult<()> {
+        integrity_finding::Entity::delete_many()
+            .filter(integrity_finding::Column::Id.is_in(ids))
+            .exec(&self.ctx.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Deletes all findings
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn clear(&self) -> RepoResult<()> {
+        integrity_finding::Entity::delete_many()
+            .exec(&self.ctx.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Moves all files affected by the given findings into the quarantine status
+    /// and returns the updated files
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn quarantine(&self, ids: Vec<i64>) -> RepoResult<Vec<FileDto>> {
+        let cd_ids: Vec<i64> = integrity_finding::Entity::find()
+            .filter(integrity_finding::Column::Id.is_in(ids))
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .filter_map(|f| f.cd_id)
+            .collect();
+
+        if cd_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        file::Entity::update_many()
+            .col_expr(
+                file::Column::Status,
+                Expr::value(FileStatus::Quarantined as i32),
+            )
+            .filter(file::Column::CdId.is_in(cd_ids.clone()))
+            .exec(&self.ctx.db)
+            .await?;
+
+        let files = file::Entity::find()
+            .find_also_related(content_descriptor::Entity)
+            .filter(file::Column::CdId.is_in(cd_ids))
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .filter_map(map_file_and_cd)
+            .collect();
+
+        Ok(files)
+    }
+}
@@ -0,0 +1,21 @@
+use mediarepo_core::error::RepoResult;
+
+use crate::dao::job::JobDao;
+
+impl JobDao {
+    /// Returns the size of the thumbnail storage and the part of it
+    /// that is used by replaced or deleted thumbnails
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn thumbnail_storage_usage(&self) -> RepoResult<(u64, u64)> {
+        let storage = &self.ctx.thumbnail_storage;
+
+        Ok((storage.get_size().await?, storage.get_wasted_size().await?))
+    }
+
+    /// Frees the space used by replaced or deleted thumbnails.
+    /// Returns the number of freed bytes
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn compact_thumbnails(&self) -> RepoResult<u64> {
+        self.ctx.thumbnail_storage.compact().await
+    }
+}
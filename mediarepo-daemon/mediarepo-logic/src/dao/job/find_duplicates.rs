@@ -0,0 +1,21 @@
+use sea_orm::prelude::*;
+
+use mediarepo_core::error::RepoResult;
+use mediarepo_database::entities::perceptual_hash;
+
+use crate::dao::job::JobDao;
+
+impl JobDao {
+    /// Returns the content descriptor ids of all images together with their perceptual hash
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn perceptual_hashes(&self) -> RepoResult<Vec<(i64, u64)>> {
+        let hashes = perceptual_hash::Entity::find()
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(|h| (h.cd_id, h.hash as u64))
+            .collect();
+
+        Ok(hashes)
+    }
+}
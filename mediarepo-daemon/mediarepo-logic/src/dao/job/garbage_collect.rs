@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime};
+
+use sea_orm::prelude::*;
+
+use mediarepo_core::content_descriptor::encode_content_descriptor;
+use mediarepo_core::error::RepoResult;
+use mediarepo_core::fs::file_hash_store::UPLOAD_EXPIRY;
+use mediarepo_database::entities::content_descriptor;
+
+use crate::dao::job::JobDao;
+use crate::dto::{OrphanDto, OrphanKind};
+
+/// Files that have been modified more recently are never collected or reported as
+/// stray as they might belong to an import that hasn't been committed to the database yet
+const ORPHAN_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+impl JobDao {
+    /// Returns all files in the main storage that don't belong to a content descriptor
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn find_orphaned_files(&self) -> RepoResult<Vec<OrphanDto>> {
+        let storage = &self.ctx.main_storage;
+        // the files are listed before the known descriptors are loaded so that files
+        // added in between are always known
+        let files = storage.list_files().await?;
+        let known_descriptors: HashSet<Vec<u8>> =
+            self.all_descriptors().await?.into_iter().collect();
+        let mut orphans = Vec::new();
+
+        for (key, descriptor) in files {
+            if descriptor
+                .map(|d| known_descriptors.contains(&d))
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            let metadata = storage.get_key_metadata(&key).await?;
+            if is_in_grace_period(metadata.modified) {
+                tracing::debug!("skipping recently modified file {}", key);
+                continue;
+            }
+            orphans.push(OrphanDto {
+                kind: OrphanKind::File,
+                key,
+                size: metadata.size,
+            });
+        }
+
+        Ok(orphans)
+    }
+
+    /// Returns all thumbnail folders that don't belong to a content descriptor
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn find_orphaned_thumbnails(&self) -> RepoResult<Vec<OrphanDto>> {
+        let storage = &self.ctx.thumbnail_storage;
+        let parents = storage.list_parents().await?;
+        let known_descriptors: HashSet<String> = self
+            .all_descriptors()
+            .await?
+            .into_iter()
+            .map(|d| encode_content_descriptor(&d))
+            .collect();
+        let mut orphans = Vec::new();
+
+        for parent in parents {
+            if known_descriptors.contains(&parent) {
+                continue;
+            }
+            let size = storage.get_parent_size(&parent).await?;
+            orphans.push(OrphanDto {
+                kind: OrphanKind::Thumbnails,
+                key: parent,
+                size,
+            });
+        }
+
+        Ok(orphans)
+    }
+
+    /// Returns the staging files of uploads that have been abandoned for longer than [UPLOAD_EXPIRY]
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn find_abandoned_uploads(&self) -> RepoResult<Vec<OrphanDto>> {
+        let orphans = self
+            .ctx
+            .main_storage
+            .find_abandoned_uploads(UPLOAD_EXPIRY)
+            .await?
+            .into_iter()
+            .map(|(key, metadata)| OrphanDto {
+                kind: OrphanKind::Upload,
+                key,
+                size: metadata.size,
+            })
+            .collect();
+
+        Ok(orphans)
+    }
+
+    /// Deletes an orphaned file, thumbnail folder or upload
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete_orphan(&self, orphan: &OrphanDto) -> RepoResult<()> {
+        match orphan.kind {
+            OrphanKind::File => self.ctx.main_storage.delete_key(&orphan.key).await,
+            OrphanKind::Thumbnails => {
+                self.ctx
+                    .thumbnail_storage
+                    .delete_parent(&orphan.key)
+                    .await?;
+                Ok(())
+            }
+            OrphanKind::Upload => {
+                self.ctx
+                    .main_storage
+                    .delete_abandoned_upload(&orphan.key)
+                    .await
+            }
+        }
+    }
+
+    async fn all_descriptors(&self) -> RepoResult<Vec<Vec<u8>>> {
+        let descriptors = content_descriptor::Entity::find()
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(|cd| cd.descriptor)
+            .collect();
+
+        Ok(descriptors)
+    }
+}
+
+pub(crate) fn is_in_grace_period(modified: Option<SystemTime>) -> bool {
+    modified
+        .and_then(|m| m.elapsed().ok())
+        .map(|elapsed| elapsed < ORPHAN_GRACE_PERIOD)
+        .unwrap_or(false)
+}
@@ -0,0 +1,18 @@
+use mediarepo_core::error::RepoResult;
+
+use crate::dao::job::JobDao;
+
+impl JobDao {
+    /// Returns the encoded content descriptors of all files whose thumbnails
+    /// still need to be moved into the configured thumbnail storage
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn unmigrated_thumbnail_parents(&self) -> RepoResult<Vec<String>> {
+        self.ctx.thumbnail_storage.list_unmigrated_parents().await
+    }
+
+    /// Moves the thumbnails of a file into the configured thumbnail storage
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn migrate_thumbnails(&self, encoded_cd: &str) -> RepoResult<()> {
+        self.ctx.thumbnail_storage.migrate_parent(encoded_cd).await
+    }
+}
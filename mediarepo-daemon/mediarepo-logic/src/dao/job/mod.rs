@@ -1,8 +1,15 @@
 use crate::dao_provider;
 
+pub mod compact_thumbnails;
+pub mod find_duplicates;
+pub mod garbage_collect;
 pub mod generate_missing_thumbnails;
 pub mod migrate_content_descriptors;
+pub mod migrate_thumbnails;
+pub mod reencode_thumbnails;
 pub mod sqlite_operations;
 pub mod state;
+pub mod sync_thumbnail_presence;
+pub mod verify_files;
 
 dao_provider!(JobDao);
@@ -0,0 +1,29 @@
+use mediarepo_core::error::RepoResult;
+use mediarepo_core::settings::ThumbnailSettings;
+
+use crate::dao::job::JobDao;
+
+impl JobDao {
+    /// Returns the settings new thumbnails are encoded with
+    pub fn thumbnail_settings(&self) -> &ThumbnailSettings {
+        self.ctx.thumbnail_storage.settings()
+    }
+
+    /// Returns the encoded content descriptors of all files with stored thumbnails
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn thumbnail_parents(&self) -> RepoResult<Vec<String>> {
+        let parents = self.ctx.thumbnail_storage.list_parents().await?;
+
+        Ok(parents)
+    }
+
+    /// Re-encodes the thumbnails of a file with the current thumbnail settings.
+    /// Returns the number of re-encoded thumbnails
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn reencode_thumbnails(&self, encoded_cd: &str, all: bool) -> RepoResult<usize> {
+        self.ctx
+            .thumbnail_storage
+            .reencode_thumbnails(encoded_cd, all)
+            .await
+    }
+}
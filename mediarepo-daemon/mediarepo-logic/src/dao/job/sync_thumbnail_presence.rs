@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+
+use sea_orm::prelude::*;
+use sea_orm::sea_query::Query;
+use sea_orm::{ConnectionTrait, DbBackend, Statement, Value};
+
+use mediarepo_core::content_descriptor::decode_content_descriptor;
+use mediarepo_core::error::RepoResult;
+use mediarepo_database::entities::{content_descriptor, content_descriptor_thumbnail};
+
+use crate::dao::job::JobDao;
+
+/// The number of content descriptors that are resolved and inserted per statement
+const BATCH_SIZE: usize = 500;
+
+impl JobDao {
+    /// Marks all content descriptors that have thumbnails in the thumbnail store
+    /// but aren't recorded as having thumbnails in the database yet.
+    /// Returns the number of marked content descriptors
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn sync_thumbnail_presence(&self) -> RepoResult<usize> {
+        let storage = &self.ctx.thumbnail_storage;
+        let mut parents = storage.list_parents().await?;
+        parents.append(&mut storage.list_unmigrated_parents().await?);
+
+        let descriptors: HashSet<Vec<u8>> = parents
+            .into_iter()
+            .filter_map(|parent| decode_content_descriptor(parent).ok())
+            .collect();
+        let descriptors: Vec<Vec<u8>> = descriptors.into_iter().collect();
+        let mut marked = 0;
+
+        for chunk in descriptors.chunks(BATCH_SIZE) {
+            let cd_ids: Vec<i64> = content_descriptor::Entity::find()
+                .filter(content_descriptor::Column::Descriptor.is_in(chunk.to_vec()))
+                .filter(
+                    content_descriptor::Column::Id.not_in_subquery(
+                        Query::select()
+                            .column(content_descriptor_thumbnail::Column::CdId)
+                            .from(content_descriptor_thumbnail::Entity)
+                            .to_owned(),
+                    ),
+                )
+                .all(&self.ctx.db)
+                .await?
+                .into_iter()
+                .map(|cd| cd.id)
+                .collect();
+
+            if cd_ids.is_empty() {
+                continue;
+            }
+            let placeholders = vec!["(?)"; cd_ids.len()].join(", ");
+            self.ctx
+                .db
+                .execute(Statement::from_sql_and_values(
+                    DbBackend::Sqlite,
+                    &format!(
+                        "INSERT OR IGNORE INTO content_descriptor_thumbnails (cd_id) VALUES {}",
+                        placeholders
+                    ),
+                    cd_ids.iter().map(|id| (*id).into()).collect::<Vec<Value>>(),
+                ))
+                .await?;
+            marked += cd_ids.len();
+        }
+
+        Ok(marked)
+    }
+}
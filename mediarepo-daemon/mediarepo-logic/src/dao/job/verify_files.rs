@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use sea_orm::prelude::*;
+use sea_orm::{QueryOrder, QuerySelect};
+
+use mediarepo_core::content_descriptor::{convert_v1_descriptor_to_v2, is_v1_content_descriptor};
+use mediarepo_core::error::RepoResult;
+use mediarepo_database::entities::content_descriptor;
+use mediarepo_database::entities::integrity_finding::FindingKind;
+
+use crate::dao::job::garbage_collect::is_in_grace_period;
+use crate::dao::job::JobDao;
+use crate::dao::DaoProvider;
+use crate::dto::AddIntegrityFindingDto;
+
+impl JobDao {
+    /// Re-hashes the stored files of up to `limit` content descriptors with an id greater
+    /// than `after_cd_id` and records missing files and mismatching hashes.
+    /// Returns the ids of all checked descriptors
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn verify_file_contents(&self, after_cd_id: i64, limit: u64) -> RepoResult<Vec<i64>> {
+        let cds: Vec<content_descriptor::Model> = content_descriptor::Entity::find()
+            .filter(content_descriptor::Column::Id.gt(after_cd_id))
+            .order_by_asc(content_descriptor::Column::Id)
+            .limit(limit)
+            .all(&self.ctx.db)
+            .await?;
+        let mut findings = Vec::new();
+
+        for cd in &cds {
+            let storage_key = self.ctx.main_storage.file_key(&cd.descriptor);
+            let kind = match self.ctx.main_storage.hash_file(&cd.descriptor).await? {
+                None => Some(FindingKind::Missing),
+                Some(hash) if hash != expected_hash(&cd.descriptor)? => Some(FindingKind::Mismatch),
+                Some(_) => None,
+            };
+            if let Some(kind) = kind {
+                tracing::warn!("integrity check of file {} failed: {:?}", storage_key, kind);
+                findings.push(AddIntegrityFindingDto {
+                    kind,
+                    storage_key,
+                    cd_id: Some(cd.id),
+                });
+            }
+        }
+        self.integrity().add_all(findings).await?;
+
+        Ok(cds.into_iter().map(|cd| cd.id).collect())
+    }
+
+    /// Records all files in the storage that don't belong to a content descriptor.
+    /// Recently modified files are skipped as their import might still be in progress.
+    /// Returns the number of stray files
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn find_stray_files(&self) -> RepoResult<usize> {
+        let storage = &self.ctx.main_storage;
+        // the files are listed before the known descriptors are loaded so that files
+        // added in between are always known
+        let files = storage.list_files().await?;
+        let known_descriptors: HashSet<Vec<u8>> = content_descriptor::Entity::find()
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(|cd| cd.descriptor)
+            .collect();
+        let mut findings = Vec::new();
+
+        for (storage_key, descriptor) in files {
+            if descriptor
+                .map(|d| known_descriptors.contains(&d))
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            let metadata = storage.get_key_metadata(&storage_key).await?;
+            if is_in_grace_period(metadata.modified) {
+                tracing::debug!("skipping recently modified file {}", storage_key);
+                continue;
+            }
+            findings.push(AddIntegrityFindingDto {
+                kind: FindingKind::Stray,
+                storage_key,
+                cd_id: None,
+            });
+        }
+        let stray_count = findings.len();
+        self.integrity().add_all(findings).await?;
+
+        Ok(stray_count)
+    }
+}
+
+/// Returns the hash the contents of a file with the given descriptor must produce
+fn expected_hash(descriptor: &[u8]) -> RepoResult<Vec<u8>> {
+    if is_v1_content_descriptor(descriptor) {
+        convert_v1_descriptor_to_v2(descriptor)
+    } else {
+        Ok(descriptor.to_vec())
+    }
+}
@@ -3,21 +3,35 @@ use sea_orm::{ActiveValue, DatabaseConnection};
 use mediarepo_core::fs::file_hash_store::FileHashStore;
 use mediarepo_core::fs::thumbnail_store::ThumbnailStore;
 
+use crate::dao::collection::CollectionDao;
+use crate::dao::custom_field::CustomFieldDao;
+use crate::dao::duplicate::DuplicateDao;
 use crate::dao::file::FileDao;
+use crate::dao::file_relation::FileRelationDao;
+use crate::dao::integrity::IntegrityDao;
 use crate::dao::job::JobDao;
+use crate::dao::saved_search::SavedSearchDao;
 use crate::dao::sorting_preset::SortingPresetDao;
+use crate::dao::source::SourceDao;
 use crate::dao::tag::TagDao;
 
+pub mod collection;
+pub mod custom_field;
+pub mod duplicate;
 pub mod file;
+pub mod file_relation;
+pub mod integrity;
 pub mod job;
 pub mod repo;
+pub mod saved_search;
 pub mod sorting_preset;
+pub mod source;
 pub mod tag;
 
 #[macro_export]
 macro_rules! dao_provider {
     ($name:ident) => {
-        use crate::dao::{DaoContext, DaoProvider};
+        use $crate::dao::{DaoContext, DaoProvider};
 
         pub struct $name {
             ctx: DaoContext,
@@ -62,6 +76,34 @@ pub trait DaoProvider {
     fn sorting_preset(&self) -> SortingPresetDao {
         SortingPresetDao::new(self.dao_ctx())
     }
+
+    fn saved_search(&self) -> SavedSearchDao {
+        SavedSearchDao::new(self.dao_ctx())
+    }
+
+    fn source(&self) -> SourceDao {
+        SourceDao::new(self.dao_ctx())
+    }
+
+    fn file_relation(&self) -> FileRelationDao {
+        FileRelationDao::new(self.dao_ctx())
+    }
+
+    fn custom_field(&self) -> CustomFieldDao {
+        CustomFieldDao::new(self.dao_ctx())
+    }
+
+    fn collection(&self) -> CollectionDao {
+        CollectionDao::new(self.dao_ctx())
+    }
+
+    fn integrity(&self) -> IntegrityDao {
+        IntegrityDao::new(self.dao_ctx())
+    }
+
+    fn duplicate(&self) -> DuplicateDao {
+        DuplicateDao::new(self.dao_ctx())
+    }
 }
 
 fn opt_to_active_val<T: Into<sea_orm::Value>>(opt: Option<T>) -> ActiveValue<T> {
@@ -1,7 +1,5 @@
 use std::fmt::Debug;
 
-use std::path::PathBuf;
-
 use sea_orm::DatabaseConnection;
 
 use mediarepo_core::error::RepoResult;
@@ -32,13 +30,13 @@ impl DaoProvider for Repo {
 impl Repo {
     pub(crate) fn new(
         db: DatabaseConnection,
-        file_store_path: PathBuf,
-        thumb_store_path: PathBuf,
+        main_storage: FileHashStore,
+        thumbnail_storage: ThumbnailStore,
     ) -> Self {
         Self {
             db,
-            main_storage: FileHashStore::new(file_store_path),
-            thumbnail_storage: ThumbnailStore::new(thumb_store_path),
+            main_storage,
+            thumbnail_storage,
         }
     }
 
@@ -46,11 +44,11 @@ impl Repo {
     #[tracing::instrument(level = "debug")]
     pub async fn connect<S: AsRef<str> + Debug>(
         uri: S,
-        file_store_path: PathBuf,
-        thumb_store_path: PathBuf,
+        main_storage: FileHashStore,
+        thumbnail_storage: ThumbnailStore,
     ) -> RepoResult<Self> {
         let db = get_database(uri).await?;
-        Ok(Self::new(db, file_store_path, thumb_store_path))
+        Ok(Self::new(db, main_storage, thumbnail_storage))
     }
 
     /// Returns the database of the repo for raw sql queries
@@ -0,0 +1,74 @@
+use sea_orm::prelude::*;
+use sea_orm::ActiveValue::{Set, Unchanged};
+use sea_orm::QueryOrder;
+
+use mediarepo_core::error::RepoResult;
+use mediarepo_database::entities::saved_search;
+
+use crate::dao::opt_to_active_val;
+use crate::dao_provider;
+use crate::dto::{AddSavedSearchDto, SavedSearchDto, UpdateSavedSearchDto};
+
+dao_provider!(SavedSearchDao);
+
+impl SavedSearchDao {
+    /// Returns all saved searches ordered by name
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn all(&self) -> RepoResult<Vec<SavedSearchDto>> {
+        let searches = saved_search::Entity::find()
+            .order_by_asc(saved_search::Column::Name)
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(SavedSearchDto::new)
+            .collect();
+
+        Ok(searches)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn by_id(&self, id: i64) -> RepoResult<Option<SavedSearchDto>> {
+        let search = saved_search::Entity::find_by_id(id)
+            .one(&self.ctx.db)
+            .await?
+            .map(SavedSearchDto::new);
+
+        Ok(search)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add(&self, search: AddSavedSearchDto) -> RepoResult<SavedSearchDto> {
+        let model = saved_search::ActiveModel {
+            name: Set(search.name),
+            query: Set(search.query),
+            sorting_preset_id: Set(search.sorting_preset_id),
+            ..Default::default()
+        };
+        let model = model.insert(&self.ctx.db).await?;
+
+        Ok(SavedSearchDto::new(model))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn update(&self, update_dto: UpdateSavedSearchDto) -> RepoResult<SavedSearchDto> {
+        let model = saved_search::ActiveModel {
+            id: Unchanged(update_dto.id),
+            name: opt_to_active_val(update_dto.name),
+            query: opt_to_active_val(update_dto.query),
+            sorting_preset_id: opt_to_active_val(update_dto.sorting_preset_id),
+        };
+        let model = model.update(&self.ctx.db).await?;
+
+        Ok(SavedSearchDto::new(model))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete(&self, id: i64) -> RepoResult<()> {
+        saved_search::Entity::delete_many()
+            .filter(saved_search::Column::Id.eq(id))
+            .exec(&self.ctx.db)
+            .await?;
+
+        Ok(())
+    }
+}
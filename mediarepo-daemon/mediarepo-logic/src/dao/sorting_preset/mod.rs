@@ -24,6 +24,20 @@ impl SortingPresetDao {
         Ok(presets)
     }
 
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn by_id(&self, id: i32) -> RepoResult<Option<SortingPresetDto>> {
+        let preset = sorting_preset::Entity::find_by_id(id)
+            .find_with_related(sort_key::Entity)
+            .order_by_asc(sorting_preset_key::Column::KeyIndex)
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .next()
+            .map(map_sorting_preset_dto);
+
+        Ok(preset)
+    }
+
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn delete(&self, id: i32) -> RepoResult<()> {
         sorting_preset::Entity::delete_many()
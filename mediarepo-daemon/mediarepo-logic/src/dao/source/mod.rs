@@ -0,0 +1,187 @@
+use sea_orm::prelude::*;
+use sea_orm::sea_query::Query;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{DatabaseTransaction, QueryOrder, TransactionTrait};
+
+use mediarepo_core::error::{RepoError, RepoResult};
+use mediarepo_core::itertools::Itertools;
+use mediarepo_database::entities::{content_descriptor_source, source};
+
+use crate::dao_provider;
+use crate::dto::SourceDto;
+
+dao_provider!(SourceDao);
+
+impl SourceDao {
+    /// Returns the sources of a content descriptor ordered by url
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn for_cd(&self, cd_id: i64) -> RepoResult<Vec<SourceDto>> {
+        let sources = source::Entity::find()
+            .filter(
+                source::Column::Id.in_subquery(
+                    Query::select()
+                        .column(content_descriptor_source::Column::SourceId)
+                        .from(content_descriptor_source::Entity)
+                        .and_where(content_descriptor_source::Column::CdId.eq(cd_id))
+                        .to_owned(),
+                ),
+            )
+            .order_by_asc(source::Column::Url)
+            .all(&self.ctx.db)
+            .await?
+            .into_iter()
+            .map(SourceDto::new)
+            .collect();
+
+        Ok(sources)
+    }
+
+    /// Adds the source urls to a content descriptor. Urls that are already
+    /// known are reused and only mapped to the content descriptor
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add_to_cd(&self, cd_id: i64, urls: Vec<String>) -> RepoResult<()> {
+        let urls = normalize_urls(urls)?;
+        if urls.is_empty() {
+            return Ok(());
+        }
+        let trx = self.ctx.db.begin().await?;
+        let existing_urls: Vec<String> = sources_by_url(&trx, urls.clone())
+            .await?
+            .into_iter()
+            .map(|s| s.url)
+            .collect();
+        let new_sources: Vec<source::ActiveModel> = urls
+            .iter()
+            .filter(|url| !existing_urls.contains(url))
+            .map(|url| source::ActiveModel {
+                url: Set(url.to_owned()),
+                ..Default::default()
+            })
+            .collect();
+
+        if !new_sources.is_empty() {
+            source::Entity::insert_many(new_sources).exec(&trx).await?;
+        }
+        let source_ids: Vec<i64> = sources_by_url(&trx, urls)
+            .await?
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        let mapped_ids: Vec<i64> = content_descriptor_source::Entity::find()
+            .filter(content_descriptor_source::Column::CdId.eq(cd_id))
+            .filter(content_descriptor_source::Column::SourceId.is_in(source_ids.clone()))
+            .all(&trx)
+            .await?
+            .into_iter()
+            .map(|m| m.source_id)
+            .collect();
+        let mappings: Vec<content_descriptor_source::ActiveModel> = source_ids
+            .into_iter()
+            .filter(|id| !mapped_ids.contains(id))
+            .map(|source_id| content_descriptor_source::ActiveModel {
+                cd_id: Set(cd_id),
+                source_id: Set(source_id),
+            })
+            .collect();
+
+        if !mappings.is_empty() {
+            content_descriptor_source::Entity::insert_many(mappings)
+                .exec(&trx)
+                .await?;
+        }
+        trx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Removes the source urls from a content descriptor and deletes
+    /// sources that aren't used by any other content descriptor
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn remove_from_cd(&self, cd_id: i64, urls: Vec<String>) -> RepoResult<()> {
+        let urls: Vec<String> = urls.iter().filter_map(|u| normalize_url(u)).collect();
+        let trx = self.ctx.db.begin().await?;
+        let source_ids: Vec<i64> = sources_by_url(&trx, urls)
+            .await?
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+
+        if source_ids.is_empty() {
+            return Ok(());
+        }
+        content_descriptor_source::Entity::delete_many()
+            .filter(content_descriptor_source::Column::CdId.eq(cd_id))
+            .filter(content_descriptor_source::Column::SourceId.is_in(source_ids.clone()))
+            .exec(&trx)
+            .await?;
+        source::Entity::delete_many()
+            .filter(source::Column::Id.is_in(source_ids))
+            .filter(
+                source::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(content_descriptor_source::Column::SourceId)
+                        .from(content_descriptor_source::Entity)
+                        .to_owned(),
+                ),
+            )
+            .exec(&trx)
+            .await?;
+        trx.commit().await?;
+
+        Ok(())
+    }
+}
+
+async fn sources_by_url(
+    trx: &DatabaseTransaction,
+    urls: Vec<String>,
+) -> RepoResult<Vec<source::Model>> {
+    if urls.is_empty() {
+        return Ok(vec![]);
+    }
+    let sources = source::Entity::find()
+        .filter(source::Column::Url.is_in(urls))
+        .all(trx)
+        .await?;
+
+    Ok(sources)
+}
+
+/// Normalizes and deduplicates the urls and fails if one of them isn't a valid url
+fn normalize_urls(urls: Vec<String>) -> RepoResult<Vec<String>> {
+    urls.into_iter()
+        .map(|url| {
+            normalize_url(&url).ok_or_else(|| {
+                RepoError::from(format!("invalid source url '{}'", url.trim()).as_str())
+            })
+        })
+        .collect::<RepoResult<Vec<String>>>()
+        .map(|urls| urls.into_iter().unique().collect())
+}
+
+/// Trims the url and lowercases its scheme and host.
+/// Returns `None` if the url doesn't have a scheme and a host
+fn normalize_url(url: &str) -> Option<String> {
+    let (scheme, rest) = url.trim().split_once("://")?;
+    let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+
+    if !valid_scheme || rest.is_empty() || rest.contains(char::is_whitespace) {
+        return None;
+    }
+    let host_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (host, path) = rest.split_at(host_end);
+
+    if host.is_empty() {
+        None
+    } else {
+        Some(format!(
+            "{}://{}{}",
+            scheme.to_lowercase(),
+            host.to_lowercase(),
+            path
+        ))
+    }
+}
@@ -1,6 +1,7 @@
 use crate::dao::tag::{map_tag_dto, TagDao};
 use crate::dto::{AddTagDto, NamespaceDto, TagDto};
 use mediarepo_core::error::RepoResult;
+use mediarepo_core::itertools::Itertools;
 use mediarepo_database::entities::{namespace, tag};
 use sea_orm::prelude::*;
 use sea_orm::ActiveValue::Set;
@@ -11,7 +12,11 @@ use std::iter::FromIterator;
 impl TagDao {
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn add_all(&self, mut tags: Vec<AddTagDto>) -> RepoResult<Vec<TagDto>> {
-        let namespaces = tags.iter().filter_map(|t| t.namespace.clone()).collect();
+        let namespaces = tags
+            .iter()
+            .filter_map(|t| t.namespace.clone())
+            .unique()
+            .collect();
         let trx = self.ctx.db.begin().await?;
         let existing_tags = tags_by_name(&trx, tags.clone()).await?;
 
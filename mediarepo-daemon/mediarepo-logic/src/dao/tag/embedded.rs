@@ -0,0 +1,53 @@
+use mediarepo_core::error::{RepoError, RepoResult};
+use mediarepo_core::fs::range_reader::FileRangeReader;
+use mediarepo_core::itertools::Itertools;
+use mediarepo_core::media_metadata::extract_embedded_fields;
+use mediarepo_core::settings::TagMapping;
+
+use crate::dao::tag::TagDao;
+use crate::dto::{AddTagDto, FileDto};
+
+impl TagDao {
+    /// Adds tags for the embedded metadata fields of a file that are mapped to a namespace.
+    /// The fields are extracted on a blocking thread that only reads the metadata regions.
+    /// Returns the number of tags the file has been tagged with
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn add_embedded_tags(
+        &self,
+        file: &FileDto,
+        mappings: &[TagMapping],
+    ) -> RepoResult<usize> {
+        if mappings.is_empty() {
+            return Ok(0);
+        }
+        let reader =
+            FileRangeReader::open(self.ctx.main_storage.clone(), file.cd().to_vec()).await?;
+        let mime_type = file.mime_type().to_owned();
+        let fields =
+            tokio::task::spawn_blocking(move || extract_embedded_fields(reader, &mime_type))
+                .await
+                .map_err(|e| RepoError::from(e.to_string().as_str()))??;
+
+        let tags: Vec<AddTagDto> = fields
+            .into_iter()
+            .flat_map(|(field, value)| {
+                mappings
+                    .iter()
+                    .filter(move |m| m.field == field)
+                    .map(move |m| AddTagDto {
+                        namespace: m.namespace.as_ref().map(|n| n.to_lowercase()),
+                        name: value.to_lowercase(),
+                    })
+            })
+            .unique_by(AddTagDto::normalized_name)
+            .collect();
+        if tags.is_empty() {
+            return Ok(0);
+        }
+        let tag_ids: Vec<i64> = self.add_all(tags).await?.iter().map(|t| t.id()).collect();
+        let count = tag_ids.len();
+        self.upsert_mappings(vec![file.cd_id()], tag_ids).await?;
+
+        Ok(count)
+    }
+}
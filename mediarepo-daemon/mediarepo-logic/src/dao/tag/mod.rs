@@ -17,6 +17,7 @@ pub mod add;
 pub mod all_for_cds_map;
 pub mod by_name;
 pub mod cdids_with_namespaced_tags;
+pub mod embedded;
 pub mod mappings;
 
 dao_provider!(TagDao);
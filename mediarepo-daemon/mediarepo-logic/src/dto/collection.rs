@@ -0,0 +1,43 @@
+use mediarepo_database::entities::collection;
+
+#[derive(Clone, Debug)]
+pub struct CollectionDto {
+    model: collection::Model,
+}
+
+impl CollectionDto {
+    pub(crate) fn new(model: collection::Model) -> Self {
+        Self { model }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.model.id
+    }
+
+    pub fn name(&self) -> &String {
+        &self.model.name
+    }
+
+    pub fn description(&self) -> Option<&String> {
+        self.model.description.as_ref()
+    }
+
+    pub fn cover_file_id(&self) -> Option<i64> {
+        self.model.cover_file_id
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AddCollectionDto {
+    pub name: String,
+    pub description: Option<String>,
+    pub cover_file_id: Option<i64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct UpdateCollectionDto {
+    pub id: i64,
+    pub name: Option<String>,
+    pub description: Option<Option<String>>,
+    pub cover_file_id: Option<Option<i64>>,
+}
@@ -0,0 +1,149 @@
+use chrono::NaiveDateTime;
+use mediarepo_database::entities::{custom_field, custom_field_value};
+
+#[derive(Clone, Debug)]
+pub struct CustomFieldDto {
+    model: custom_field::Model,
+    field_type: CustomFieldType,
+}
+
+impl CustomFieldDto {
+    /// Creates the dto from the stored field. Returns `None` if the field type is unknown
+    pub(crate) fn new(model: custom_field::Model) -> Option<Self> {
+        let field_type = CustomFieldType::from_number(model.field_type)?;
+
+        Some(Self { model, field_type })
+    }
+
+    pub fn id(&self) -> i64 {
+        self.model.id
+    }
+
+    pub fn name(&self) -> &String {
+        &self.model.name
+    }
+
+    pub fn field_type(&self) -> CustomFieldType {
+        self.field_type
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CustomFieldType {
+    Int = 0,
+    Float = 1,
+    Text = 2,
+    Date = 3,
+    Bool = 4,
+}
+
+impl CustomFieldType {
+    pub fn from_number(number: i32) -> Option<CustomFieldType> {
+        match number {
+            0 => Some(CustomFieldType::Int),
+            1 => Some(CustomFieldType::Float),
+            2 => Some(CustomFieldType::Text),
+            3 => Some(CustomFieldType::Date),
+            4 => Some(CustomFieldType::Bool),
+            _ => None,
+        }
+    }
+
+    pub fn to_number(&self) -> i32 {
+        *self as i32
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum CustomFieldValue {
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Date(NaiveDateTime),
+    Bool(bool),
+}
+
+impl CustomFieldValue {
+    pub fn field_type(&self) -> CustomFieldType {
+        match self {
+            CustomFieldValue::Int(_) => CustomFieldType::Int,
+            CustomFieldValue::Float(_) => CustomFieldType::Float,
+            CustomFieldValue::Text(_) => CustomFieldType::Text,
+            CustomFieldValue::Date(_) => CustomFieldType::Date,
+            CustomFieldValue::Bool(_) => CustomFieldType::Bool,
+        }
+    }
+
+    /// Converts the value to the given field type. Integers can be used as floats
+    /// and every value can be used as text. Returns `None` for other conversions
+    pub fn cast(self, field_type: CustomFieldType) -> Option<CustomFieldValue> {
+        match (self, field_type) {
+            (value, field_type) if value.field_type() == field_type => Some(value),
+            (CustomFieldValue::Int(value), CustomFieldType::Float) => {
+                Some(CustomFieldValue::Float(value as f64))
+            }
+            (CustomFieldValue::Int(value), CustomFieldType::Text) => {
+                Some(CustomFieldValue::Text(value.to_string()))
+            }
+            (CustomFieldValue::Float(value), CustomFieldType::Text) => {
+                Some(CustomFieldValue::Text(value.to_string()))
+            }
+            (CustomFieldValue::Date(value), CustomFieldType::Text) => {
+                Some(CustomFieldValue::Text(value.to_string()))
+            }
+            (CustomFieldValue::Bool(value), CustomFieldType::Text) => {
+                Some(CustomFieldValue::Text(value.to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CustomFieldValueDto {
+    field: CustomFieldDto,
+    file_id: i64,
+    value: CustomFieldValue,
+}
+
+impl CustomFieldValueDto {
+    /// Creates the dto from the stored value. Returns `None` if the stored
+    /// value doesn't match the type of the field
+    pub(crate) fn new(field: CustomFieldDto, model: custom_field_value::Model) -> Option<Self> {
+        let value = match field.field_type() {
+            CustomFieldType::Int => CustomFieldValue::Int(model.int_value?),
+            CustomFieldType::Float => CustomFieldValue::Float(model.float_value?),
+            CustomFieldType::Text => CustomFieldValue::Text(model.text_value?),
+            CustomFieldType::Date => CustomFieldValue::Date(model.date_value?),
+            CustomFieldType::Bool => CustomFieldValue::Bool(model.int_value? != 0),
+        };
+
+        Some(Self {
+            field,
+            file_id: model.file_id,
+            value,
+        })
+    }
+
+    pub fn field(&self) -> &CustomFieldDto {
+        &self.field
+    }
+
+    pub fn file_id(&self) -> i64 {
+        self.file_id
+    }
+
+    pub fn value(&self) -> &CustomFieldValue {
+        &self.value
+    }
+
+    pub fn into_value(self) -> CustomFieldValue {
+        self.value
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AddCustomFieldDto {
+    pub name: String,
+    pub field_type: CustomFieldType,
+}
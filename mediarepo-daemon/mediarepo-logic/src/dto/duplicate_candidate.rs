@@ -0,0 +1,62 @@
+use mediarepo_database::entities::duplicate_candidate;
+pub use mediarepo_database::entities::duplicate_candidate::DuplicateStatus;
+
+use crate::dto::FileDto;
+
+#[derive(Clone, Debug)]
+pub struct DuplicateCandidateDto {
+    model: duplicate_candidate::Model,
+    file_a: FileDto,
+    file_b: FileDto,
+}
+
+impl DuplicateCandidateDto {
+    pub(crate) fn new(model: duplicate_candidate::Model, file_a: FileDto, file_b: FileDto) -> Self {
+        Self {
+            model,
+            file_a,
+            file_b,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.model.id
+    }
+
+    /// Returns the hamming distance of the perceptual hashes of both files
+    pub fn distance(&self) -> u32 {
+        self.model.distance as u32
+    }
+
+    pub fn status(&self) -> DuplicateStatus {
+        self.model.status
+    }
+
+    pub fn file_a(&self) -> &FileDto {
+        &self.file_a
+    }
+
+    pub fn file_b(&self) -> &FileDto {
+        &self.file_b
+    }
+
+    pub fn into_files(self) -> (FileDto, FileDto) {
+        (self.file_a, self.file_b)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AddDuplicateCandidateDto {
+    pub cd_id_a: i64,
+    pub cd_id_b: i64,
+    pub distance: u32,
+}
+
+/// The decision made when reviewing a duplicate candidate
+#[derive(Clone, Debug)]
+pub enum DuplicateResolutionDto {
+    /// Keeps the given file and moves the other one to the trash
+    Keep { file_id: i64, merge_tags: bool },
+    /// Marks the files as not being duplicates of each other
+    NotDuplicates,
+}
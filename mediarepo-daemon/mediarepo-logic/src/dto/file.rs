@@ -1,6 +1,9 @@
 use chrono::NaiveDateTime;
 
+use std::path::PathBuf;
+
 use mediarepo_core::content_descriptor::encode_content_descriptor;
+use mediarepo_core::fs::link::ImportMode;
 use mediarepo_core::mediarepo_api::types::files::FileStatus as ApiFileStatus;
 use mediarepo_database::entities::content_descriptor;
 use mediarepo_database::entities::file;
@@ -49,10 +52,19 @@ impl FileDto {
             10 => FileStatus::Imported,
             20 => FileStatus::Archived,
             30 => FileStatus::Deleted,
+            40 => FileStatus::Quarantined,
             _ => FileStatus::Imported,
         }
     }
 
+    pub fn import_mode(&self) -> ImportMode {
+        match self.model.import_mode {
+            20 => ImportMode::Hardlink,
+            30 => ImportMode::Reflink,
+            _ => ImportMode::Copy,
+        }
+    }
+
     pub fn mime_type(&self) -> &String {
         &self.model.mime_type
     }
@@ -68,13 +80,24 @@ impl FileDto {
 
 #[derive(Clone, Debug)]
 pub struct AddFileDto {
-    pub content: Vec<u8>,
+    pub content: FileContent,
     pub mime_type: String,
     pub creation_time: NaiveDateTime,
     pub change_time: NaiveDateTime,
     pub name: Option<String>,
 }
 
+/// The source of the contents of a file that is added to the repository
+#[derive(Clone, Debug)]
+pub enum FileContent {
+    /// The complete contents of the file
+    Bytes(Vec<u8>),
+    /// The id of a chunked upload that has been transferred completely
+    Upload(String),
+    /// A file on the local file system that gets linked into the store if possible
+    Reference { path: PathBuf, mode: ImportMode },
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct UpdateFileDto {
     pub id: i64,
@@ -88,6 +111,7 @@ pub enum FileStatus {
     Imported = 10,
     Archived = 20,
     Deleted = 30,
+    Quarantined = 40,
 }
 
 impl From<ApiFileStatus> for FileStatus {
@@ -96,6 +120,7 @@ impl From<ApiFileStatus> for FileStatus {
             ApiFileStatus::Imported => Self::Imported,
             ApiFileStatus::Archived => Self::Archived,
             ApiFileStatus::Deleted => Self::Deleted,
+            ApiFileStatus::Quarantined => Self::Quarantined,
         }
     }
 }
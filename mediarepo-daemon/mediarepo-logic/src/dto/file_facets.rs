@@ -0,0 +1,28 @@
+use crate::dto::{NamespaceDto, TagDto};
+
+/// Aggregated properties of the files matching a search
+#[derive(Clone, Debug)]
+pub struct FileFacetsDto {
+    pub total: u64,
+    pub tags: Vec<(TagDto, u64)>,
+    pub namespaces: Vec<(NamespaceDto, u64)>,
+    pub mime_types: Vec<(String, u64)>,
+    pub sizes: Vec<SizeBucketDto>,
+    pub import_months: Vec<MonthBucketDto>,
+}
+
+/// The number of files with a size in `min..max`
+#[derive(Clone, Debug)]
+pub struct SizeBucketDto {
+    pub min: u64,
+    pub max: Option<u64>,
+    pub count: u64,
+}
+
+/// The number of files imported in a month
+#[derive(Clone, Debug)]
+pub struct MonthBucketDto {
+    pub year: i32,
+    pub month: u32,
+    pub count: u64,
+}
@@ -47,5 +47,17 @@ pub struct UpdateFileMetadataDto {
     pub name: Option<Option<String>>,
     pub comment: Option<Option<String>>,
     pub size: Option<i64>,
+    pub creation_time: Option<NaiveDateTime>,
     pub change_time: Option<NaiveDateTime>,
 }
+
+impl UpdateFileMetadataDto {
+    /// Returns if the update doesn't change any field
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.comment.is_none()
+            && self.size.is_none()
+            && self.creation_time.is_none()
+            && self.change_time.is_none()
+    }
+}
@@ -0,0 +1,40 @@
+use mediarepo_database::entities::file_rating;
+
+#[derive(Clone, Debug)]
+pub struct FileRatingDto {
+    model: file_rating::Model,
+}
+
+impl FileRatingDto {
+    pub(crate) fn new(model: file_rating::Model) -> Self {
+        Self { model }
+    }
+
+    /// Returns a rating for a file that hasn't been rated yet
+    pub(crate) fn unrated(file_id: i64) -> Self {
+        Self::new(file_rating::Model {
+            file_id,
+            rating: None,
+            favorite: false,
+        })
+    }
+
+    pub fn file_id(&self) -> i64 {
+        self.model.file_id
+    }
+
+    pub fn rating(&self) -> Option<u32> {
+        self.model.rating.map(|r| r as u32)
+    }
+
+    pub fn favorite(&self) -> bool {
+        self.model.favorite
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct UpdateFileRatingDto {
+    pub file_id: i64,
+    pub rating: Option<Option<u32>>,
+    pub favorite: Option<bool>,
+}
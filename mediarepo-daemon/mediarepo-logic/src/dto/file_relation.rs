@@ -0,0 +1,58 @@
+use mediarepo_database::entities::file_relation;
+
+#[derive(Clone, Debug)]
+pub struct FileRelationDto {
+    model: file_relation::Model,
+    relation_type: FileRelationType,
+}
+
+impl FileRelationDto {
+    /// Creates the dto from the stored relation. Returns `None` if the relation type is unknown
+    pub(crate) fn new(model: file_relation::Model) -> Option<Self> {
+        let relation_type = FileRelationType::from_number(model.relation_type)?;
+
+        Some(Self {
+            model,
+            relation_type,
+        })
+    }
+
+    pub fn file_id(&self) -> i64 {
+        self.model.file_id
+    }
+
+    pub fn related_file_id(&self) -> i64 {
+        self.model.related_file_id
+    }
+
+    pub fn relation_type(&self) -> FileRelationType {
+        self.relation_type
+    }
+}
+
+/// The type of a relation that reads as "the file is the `<type>` of the related file"
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileRelationType {
+    Alternate = 0,
+    Parent = 1,
+    Child = 2,
+    DerivedFrom = 3,
+    DuplicateOf = 4,
+}
+
+impl FileRelationType {
+    pub fn from_number(number: i32) -> Option<FileRelationType> {
+        match number {
+            0 => Some(FileRelationType::Alternate),
+            1 => Some(FileRelationType::Parent),
+            2 => Some(FileRelationType::Child),
+            3 => Some(FileRelationType::DerivedFrom),
+            4 => Some(FileRelationType::DuplicateOf),
+            _ => None,
+        }
+    }
+
+    pub fn to_number(&self) -> i32 {
+        *self as i32
+    }
+}
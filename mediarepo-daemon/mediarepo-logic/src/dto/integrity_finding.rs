@@ -0,0 +1,58 @@
+use chrono::NaiveDateTime;
+
+use mediarepo_core::content_descriptor::encode_content_descriptor;
+use mediarepo_database::entities::content_descriptor;
+use mediarepo_database::entities::integrity_finding;
+pub use mediarepo_database::entities::integrity_finding::FindingKind;
+
+#[derive(Clone, Debug)]
+pub struct IntegrityFindingDto {
+    model: integrity_finding::Model,
+    content_descriptor: Option<content_descriptor::Model>,
+}
+
+impl IntegrityFindingDto {
+    pub(crate) fn new(
+        model: integrity_finding::Model,
+        content_descriptor: Option<content_descriptor::Model>,
+    ) -> Self {
+        Self {
+            model,
+            content_descriptor,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.model.id
+    }
+
+    pub fn kind(&self) -> FindingKind {
+        self.model.kind
+    }
+
+    pub fn storage_key(&self) -> &String {
+        &self.model.storage_key
+    }
+
+    pub fn cd_id(&self) -> Option<i64> {
+        self.model.cd_id
+    }
+
+    /// Returns the encoded content descriptor of the affected file if it is known
+    pub fn encoded_cd(&self) -> Option<String> {
+        self.content_descriptor
+            .as_ref()
+            .map(|cd| encode_content_descriptor(&cd.descriptor))
+    }
+
+    pub fn detected_at(&self) -> NaiveDateTime {
+        self.model.detected_at
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AddIntegrityFindingDto {
+    pub kind: FindingKind,
+    pub storage_key: String,
+    pub cd_id: Option<i64>,
+}
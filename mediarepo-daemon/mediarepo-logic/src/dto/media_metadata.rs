@@ -0,0 +1,60 @@
+use mediarepo_core::bincode;
+use mediarepo_core::error::RepoResult;
+use mediarepo_database::entities::media_metadata;
+
+#[derive(Clone, Debug)]
+pub struct MediaMetadataDto {
+    model: media_metadata::Model,
+    exif: Vec<(String, String)>,
+}
+
+impl MediaMetadataDto {
+    pub(crate) fn new(model: media_metadata::Model) -> RepoResult<Self> {
+        let exif = bincode::deserialize(&model.exif)?;
+
+        Ok(Self { model, exif })
+    }
+
+    pub fn cd_id(&self) -> i64 {
+        self.model.cd_id
+    }
+
+    pub fn width(&self) -> Option<u32> {
+        self.model.width.map(|w| w as u32)
+    }
+
+    pub fn height(&self) -> Option<u32> {
+        self.model.height.map(|h| h as u32)
+    }
+
+    /// Returns the duration in milliseconds
+    pub fn duration(&self) -> Option<u64> {
+        self.model.duration.map(|d| d as u64)
+    }
+
+    pub fn frame_count(&self) -> Option<u64> {
+        self.model.frame_count.map(|c| c as u64)
+    }
+
+    pub fn video_codec(&self) -> Option<&String> {
+        self.model.video_codec.as_ref()
+    }
+
+    pub fn audio_codec(&self) -> Option<&String> {
+        self.model.audio_codec.as_ref()
+    }
+
+    /// Returns the average bitrate in bits per second
+    pub fn bitrate(&self) -> Option<u64> {
+        self.model.bitrate.map(|b| b as u64)
+    }
+
+    pub fn orientation(&self) -> Option<u8> {
+        self.model.orientation.map(|o| o as u8)
+    }
+
+    /// Returns the exif fields as tag name and display value
+    pub fn exif(&self) -> &Vec<(String, String)> {
+        &self.exif
+    }
+}
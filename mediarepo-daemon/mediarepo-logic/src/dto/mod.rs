@@ -1,15 +1,39 @@
+pub use collection::*;
+pub use custom_field::*;
+pub use duplicate_candidate::*;
 pub use file::*;
+pub use file_facets::*;
 pub use file_metadata::*;
+pub use file_rating::*;
+pub use file_relation::*;
+pub use integrity_finding::*;
 pub use job_state::*;
+pub use media_metadata::*;
 pub use namespace::*;
+pub use orphan::*;
+pub use saved_search::*;
 pub use sorting_preset::*;
+pub use source::*;
 pub use tag::*;
 pub use thumbnail::*;
 
+mod collection;
+mod custom_field;
+mod duplicate_candidate;
 mod file;
+mod file_facets;
 mod file_metadata;
+mod file_rating;
+mod file_relation;
+mod integrity_finding;
 mod job_state;
+mod media_metadata;
+#[allow(hidden_glob_reexports)]
 mod namespace;
+mod orphan;
+mod saved_search;
 mod sorting_preset;
+mod source;
+#[allow(hidden_glob_reexports)]
 mod tag;
 mod thumbnail;
@@ -0,0 +1,15 @@
+/// A stored file or thumbnail folder that doesn't belong to any content descriptor
+/// or the staging file of an abandoned upload
+#[derive(Clone, Debug)]
+pub struct OrphanDto {
+    pub kind: OrphanKind,
+    pub key: String,
+    pub size: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrphanKind {
+    File,
+    Thumbnails,
+    Upload,
+}
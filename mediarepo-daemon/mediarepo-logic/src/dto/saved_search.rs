@@ -0,0 +1,44 @@
+use mediarepo_database::entities::saved_search;
+
+#[derive(Clone, Debug)]
+pub struct SavedSearchDto {
+    model: saved_search::Model,
+}
+
+impl SavedSearchDto {
+    pub(crate) fn new(model: saved_search::Model) -> Self {
+        Self { model }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.model.id
+    }
+
+    pub fn name(&self) -> &String {
+        &self.model.name
+    }
+
+    /// Returns the serialized search request
+    pub fn query(&self) -> &[u8] {
+        &self.model.query
+    }
+
+    pub fn sorting_preset_id(&self) -> Option<i32> {
+        self.model.sorting_preset_id
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AddSavedSearchDto {
+    pub name: String,
+    pub query: Vec<u8>,
+    pub sorting_preset_id: Option<i32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct UpdateSavedSearchDto {
+    pub id: i64,
+    pub name: Option<String>,
+    pub query: Option<Vec<u8>>,
+    pub sorting_preset_id: Option<Option<i32>>,
+}
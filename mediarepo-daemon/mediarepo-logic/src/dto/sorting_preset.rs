@@ -1,6 +1,6 @@
 use crate::dto::KeyType::{
-    FileChangeTime, FileCreatedTime, FileImportedTime, FileName, FileSize, FileType, Namespace,
-    NumTags,
+    Bitrate, CustomField, Duration, FileChangeTime, FileCreatedTime, FileImportedTime, FileName,
+    FileSize, FileType, Height, Namespace, NumTags, Rating, Width,
 };
 use mediarepo_database::entities::sort_key;
 use mediarepo_database::entities::sorting_preset;
@@ -66,6 +66,12 @@ pub enum KeyType {
     FileChangeTime = 5,
     FileType = 6,
     NumTags = 7,
+    Width = 8,
+    Height = 9,
+    Duration = 10,
+    Bitrate = 11,
+    Rating = 12,
+    CustomField = 13,
 }
 
 impl KeyType {
@@ -79,6 +85,12 @@ impl KeyType {
             5 => Some(FileChangeTime),
             6 => Some(FileType),
             7 => Some(NumTags),
+            8 => Some(Width),
+            9 => Some(Height),
+            10 => Some(Duration),
+            11 => Some(Bitrate),
+            12 => Some(Rating),
+            13 => Some(CustomField),
             _ => None,
         }
     }
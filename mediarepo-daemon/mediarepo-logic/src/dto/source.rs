@@ -0,0 +1,20 @@
+use mediarepo_database::entities::source;
+
+#[derive(Clone, Debug)]
+pub struct SourceDto {
+    model: source::Model,
+}
+
+impl SourceDto {
+    pub(crate) fn new(model: source::Model) -> Self {
+        Self { model }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.model.id
+    }
+
+    pub fn url(&self) -> &String {
+        &self.model.url
+    }
+}
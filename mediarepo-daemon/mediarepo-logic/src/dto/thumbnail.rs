@@ -1,24 +1,23 @@
-use std::path::PathBuf;
-
-use tokio::fs;
-use tokio::fs::{File, OpenOptions};
-use tokio::io::BufReader;
-
 use mediarepo_core::error::RepoResult;
-use mediarepo_core::fs::thumbnail_store::Dimensions;
+use mediarepo_core::fs::thumbnail_store::{Dimensions, ThumbnailStore};
 
 #[derive(Clone, Debug)]
 pub struct ThumbnailDto {
-    path: PathBuf,
+    storage: ThumbnailStore,
     parent_cd: String,
     size: Dimensions,
     mime_type: String,
 }
 
 impl ThumbnailDto {
-    pub fn new(path: PathBuf, parent_cd: String, size: Dimensions, mime_type: String) -> Self {
+    pub fn new(
+        storage: ThumbnailStore,
+        parent_cd: String,
+        size: Dimensions,
+        mime_type: String,
+    ) -> Self {
         Self {
-            path,
+            storage,
             parent_cd,
             size,
             mime_type,
@@ -37,17 +36,19 @@ impl ThumbnailDto {
         &self.mime_type
     }
 
+    /// Returns the encoded thumbnail
     #[tracing::instrument(level = "debug")]
-    pub async fn get_reader(&self) -> RepoResult<BufReader<File>> {
-        let file = OpenOptions::new().read(true).open(&self.path).await?;
-        Ok(BufReader::new(file))
+    pub async fn get_bytes(&self) -> RepoResult<Vec<u8>> {
+        self.storage
+            .get_thumbnail(&self.parent_cd, &self.size)
+            .await
     }
 
     /// Deletes the thumbnail
     #[tracing::instrument(level = "debug")]
     pub async fn delete(self) -> RepoResult<()> {
-        fs::remove_file(&self.path).await?;
-
-        Ok(())
+        self.storage
+            .delete_thumbnail(&self.parent_cd, &self.size)
+            .await
     }
 }
@@ -1,13 +1,31 @@
+use mediarepo_core::mediarepo_api::types::collections::CollectionResponse;
+use mediarepo_core::mediarepo_api::types::custom_fields::{
+    CustomFieldResponse, CustomFieldType, CustomFieldValue, CustomFieldValueResponse,
+};
+use mediarepo_core::mediarepo_api::types::duplicates::DuplicateCandidateResponse;
+use mediarepo_core::mediarepo_api::types::facets::{
+    MimeTypeFacet, MonthBucket, NamespaceFacet, SearchFacetsResponse, SizeBucket, TagFacet,
+};
 use mediarepo_core::mediarepo_api::types::files::{
-    FileBasicDataResponse, FileMetadataResponse, FileStatus, ThumbnailMetadataResponse,
+    FileBasicDataResponse, FileMetadataResponse, FileRatingResponse, FileStatus,
+    MediaMetadataResponse, ThumbnailMetadataResponse,
 };
 use mediarepo_core::mediarepo_api::types::filtering::{
-    SortDirection, SortKey, SortNamespace, SortingPreset,
+    SortCustomField, SortDirection, SortKey, SortNamespace, SortingPreset,
 };
+use mediarepo_core::mediarepo_api::types::jobs::{
+    IntegrityFindingKind, IntegrityFindingResponse, OrphanKind, OrphanResponse,
+};
+use mediarepo_core::mediarepo_api::types::relations::{FileRelationResponse, FileRelationType};
+use mediarepo_core::mediarepo_api::types::sources::SourceResponse;
 use mediarepo_core::mediarepo_api::types::tags::{NamespaceResponse, TagResponse};
 use mediarepo_logic::dto::{
-    FileDto, FileMetadataDto, FileStatus as FileStatusModel, KeyType, NamespaceDto, SortKeyDto,
-    SortingPresetDto, TagDto, ThumbnailDto,
+    CollectionDto, CustomFieldDto, CustomFieldType as CustomFieldTypeModel,
+    CustomFieldValue as CustomFieldValueModel, CustomFieldValueDto, DuplicateCandidateDto, FileDto,
+    FileFacetsDto, FileMetadataDto, FileRatingDto, FileRelationDto,
+    FileRelationType as FileRelationTypeModel, FileStatus as FileStatusModel, FindingKind,
+    IntegrityFindingDto, KeyType, MediaMetadataDto, NamespaceDto, OrphanDto,
+    OrphanKind as OrphanKindModel, SortKeyDto, SortingPresetDto, SourceDto, TagDto, ThumbnailDto,
 };
 
 pub trait FromModel<M> {
@@ -24,6 +42,110 @@ impl FromModel<FileMetadataDto> for FileMetadataResponse {
             change_time: model.change_time().to_owned(),
             import_time: model.import_time().to_owned(),
             size: model.size() as u64,
+            media: None,
+        }
+    }
+}
+
+impl FromModel<FileRatingDto> for FileRatingResponse {
+    fn from_model(model: FileRatingDto) -> Self {
+        Self {
+            file_id: model.file_id(),
+            rating: model.rating(),
+            favorite: model.favorite(),
+        }
+    }
+}
+
+impl FromModel<CustomFieldDto> for CustomFieldResponse {
+    fn from_model(model: CustomFieldDto) -> Self {
+        Self {
+            id: model.id(),
+            name: model.name().to_owned(),
+            field_type: CustomFieldType::from_model(model.field_type()),
+        }
+    }
+}
+
+impl FromModel<CustomFieldTypeModel> for CustomFieldType {
+    fn from_model(model: CustomFieldTypeModel) -> Self {
+        match model {
+            CustomFieldTypeModel::Int => Self::Int,
+            CustomFieldTypeModel::Float => Self::Float,
+            CustomFieldTypeModel::Text => Self::Text,
+            CustomFieldTypeModel::Date => Self::Date,
+            CustomFieldTypeModel::Bool => Self::Bool,
+        }
+    }
+}
+
+impl FromModel<CustomFieldValueModel> for CustomFieldValue {
+    fn from_model(model: CustomFieldValueModel) -> Self {
+        match model {
+            CustomFieldValueModel::Int(value) => Self::Int(value),
+            CustomFieldValueModel::Float(value) => Self::Float(value),
+            CustomFieldValueModel::Text(value) => Self::Text(value),
+            CustomFieldValueModel::Date(value) => Self::Date(value),
+            CustomFieldValueModel::Bool(value) => Self::Bool(value),
+        }
+    }
+}
+
+impl FromModel<CustomFieldValueDto> for CustomFieldValueResponse {
+    fn from_model(model: CustomFieldValueDto) -> Self {
+        Self {
+            field_id: model.field().id(),
+            name: model.field().name().to_owned(),
+            value: CustomFieldValue::from_model(model.into_value()),
+        }
+    }
+}
+
+impl FromModel<CollectionDto> for CollectionResponse {
+    fn from_model(model: CollectionDto) -> Self {
+        Self {
+            id: model.id(),
+            name: model.name().to_owned(),
+            description: model.description().cloned(),
+            cover_file_id: model.cover_file_id(),
+        }
+    }
+}
+
+impl FromModel<FileRelationDto> for FileRelationResponse {
+    fn from_model(model: FileRelationDto) -> Self {
+        Self {
+            file_id: model.file_id(),
+            related_file_id: model.related_file_id(),
+            relation_type: FileRelationType::from_model(model.relation_type()),
+        }
+    }
+}
+
+impl FromModel<FileRelationTypeModel> for FileRelationType {
+    fn from_model(model: FileRelationTypeModel) -> Self {
+        match model {
+            FileRelationTypeModel::Alternate => Self::Alternate,
+            FileRelationTypeModel::Parent => Self::Parent,
+            FileRelationTypeModel::Child => Self::Child,
+            FileRelationTypeModel::DerivedFrom => Self::DerivedFrom,
+            FileRelationTypeModel::DuplicateOf => Self::DuplicateOf,
+        }
+    }
+}
+
+impl FromModel<MediaMetadataDto> for MediaMetadataResponse {
+    fn from_model(model: MediaMetadataDto) -> Self {
+        Self {
+            width: model.width(),
+            height: model.height(),
+            duration: model.duration(),
+            frame_count: model.frame_count(),
+            video_codec: model.video_codec().cloned(),
+            audio_codec: model.audio_codec().cloned(),
+            bitrate: model.bitrate(),
+            orientation: model.orientation(),
+            exif: model.exif().clone(),
         }
     }
 }
@@ -45,6 +167,7 @@ impl FromModel<FileStatusModel> for FileStatus {
             FileStatusModel::Imported => FileStatus::Imported,
             FileStatusModel::Archived => FileStatus::Archived,
             FileStatusModel::Deleted => FileStatus::Deleted,
+            FileStatusModel::Quarantined => FileStatus::Quarantined,
         }
     }
 }
@@ -79,6 +202,15 @@ impl FromModel<NamespaceDto> for NamespaceResponse {
     }
 }
 
+impl FromModel<SourceDto> for SourceResponse {
+    fn from_model(model: SourceDto) -> Self {
+        Self {
+            id: model.id(),
+            url: model.url().to_owned(),
+        }
+    }
+}
+
 impl FromModel<SortingPresetDto> for SortingPreset {
     fn from_model(model: SortingPresetDto) -> Self {
         SortingPreset {
@@ -107,6 +239,15 @@ fn map_sort_dto_to_key(dto: SortKeyDto) -> Option<SortKey> {
         KeyType::FileChangeTime => Some(SortKey::FileChangeTime(direction)),
         KeyType::FileType => Some(SortKey::FileType(direction)),
         KeyType::NumTags => Some(SortKey::NumTags(direction)),
+        KeyType::Width => Some(SortKey::Width(direction)),
+        KeyType::Height => Some(SortKey::Height(direction)),
+        KeyType::Duration => Some(SortKey::Duration(direction)),
+        KeyType::Bitrate => Some(SortKey::Bitrate(direction)),
+        KeyType::Rating => Some(SortKey::Rating(direction)),
+        KeyType::CustomField => Some(SortKey::CustomField(SortCustomField {
+            name: dto.value()?.to_owned(),
+            direction,
+        })),
     }
 }
 
@@ -117,3 +258,101 @@ fn map_direction(ascending: bool) -> SortDirection {
         SortDirection::Descending
     }
 }
+
+impl FromModel<IntegrityFindingDto> for IntegrityFindingResponse {
+    fn from_model(model: IntegrityFindingDto) -> Self {
+        Self {
+            id: model.id(),
+            kind: IntegrityFindingKind::from_model(model.kind()),
+            storage_key: model.storage_key().to_owned(),
+            cd: model.encoded_cd(),
+            detected_at: model.detected_at(),
+        }
+    }
+}
+
+impl FromModel<FindingKind> for IntegrityFindingKind {
+    fn from_model(kind: FindingKind) -> Self {
+        match kind {
+            FindingKind::Missing => IntegrityFindingKind::Missing,
+            FindingKind::Mismatch => IntegrityFindingKind::Mismatch,
+            FindingKind::Stray => IntegrityFindingKind::Stray,
+        }
+    }
+}
+
+impl FromModel<OrphanDto> for OrphanResponse {
+    fn from_model(model: OrphanDto) -> Self {
+        Self {
+            kind: match model.kind {
+                OrphanKindModel::File => OrphanKind::File,
+                OrphanKindModel::Thumbnails => OrphanKind::Thumbnails,
+                OrphanKindModel::Upload => OrphanKind::Upload,
+            },
+            key: model.key,
+            size: model.size,
+        }
+    }
+}
+
+impl FromModel<DuplicateCandidateDto> for DuplicateCandidateResponse {
+    fn from_model(model: DuplicateCandidateDto) -> Self {
+        let id = model.id();
+        let distance = model.distance();
+        let (file_a, file_b) = model.into_files();
+
+        Self {
+            id,
+            distance,
+            file_a: FileBasicDataResponse::from_model(file_a),
+            file_b: FileBasicDataResponse::from_model(file_b),
+        }
+    }
+}
+
+impl FromModel<FileFacetsDto> for SearchFacetsResponse {
+    fn from_model(model: FileFacetsDto) -> Self {
+        Self {
+            total: model.total,
+            tags: model
+                .tags
+                .into_iter()
+                .map(|(tag, count)| TagFacet {
+                    tag: TagResponse::from_model(tag),
+                    count,
+                })
+                .collect(),
+            namespaces: model
+                .namespaces
+                .into_iter()
+                .map(|(namespace, count)| NamespaceFacet {
+                    namespace: NamespaceResponse::from_model(namespace),
+                    count,
+                })
+                .collect(),
+            mime_types: model
+                .mime_types
+                .into_iter()
+                .map(|(mime_type, count)| MimeTypeFacet { mime_type, count })
+                .collect(),
+            sizes: model
+                .sizes
+                .into_iter()
+                .map(|b| SizeBucket {
+                    min: b.min,
+                    max: b.max,
+                    count: b.count,
+                })
+                .collect(),
+            import_months: model
+                .import_months
+                .into_iter()
+                .map(|b| MonthBucket {
+                    year: b.year,
+                    month: b.month,
+                    count: b.count,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use mediarepo_core::bromine::prelude::*;
+use mediarepo_core::error::RepoResult;
+use mediarepo_core::mediarepo_api::types::collections::{
+    AddCollectionRequest, CollectionResponse, InsertCollectionFilesRequest,
+    MoveCollectionFileRequest, RemoveCollectionFilesRequest, UpdateCollectionRequest,
+};
+use mediarepo_core::mediarepo_api::types::files::FileBasicDataResponse;
+use mediarepo_core::mediarepo_api::types::identifier::FileIdentifier;
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use mediarepo_logic::dto::{AddCollectionDto, UpdateCollectionDto};
+
+use crate::from_model::FromModel;
+use crate::utils::{file_by_identifier, get_repo_from_context};
+
+pub struct CollectionsNamespace;
+
+impl NamespaceProvider for CollectionsNamespace {
+    fn name() -> &'static str {
+        "collections"
+    }
+
+    fn register(handler: &mut EventHandler) {
+        events!(handler,
+            "all_collections" => Self::all_collections,
+            "add_collection" => Self::add_collection,
+            "update_collection" => Self::update_collection,
+            "delete_collection" => Self::delete_collection,
+            "collection_files" => Self::collection_files,
+            "insert_collection_files" => Self::insert_collection_files,
+            "move_collection_file" => Self::move_collection_file,
+            "remove_collection_files" => Self::remove_collection_files
+        );
+    }
+}
+
+impl CollectionsNamespace {
+    /// Returns all collections
+    #[tracing::instrument(skip_all)]
+    async fn all_collections(ctx: &Context, _: Event) -> IPCResult<Response> {
+        let repo = get_repo_from_context(ctx).await;
+        let collections: Vec<CollectionResponse> = repo
+            .collection()
+            .all()
+            .await?
+            .into_iter()
+            .map(CollectionResponse::from_model)
+            .collect();
+
+        ctx.response(collections)
+    }
+
+    /// Creates a new collection
+    #[tracing::instrument(skip_all)]
+    async fn add_collection(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<AddCollectionRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let collection = repo
+            .collection()
+            .add(AddCollectionDto {
+                name: request.name,
+                description: request.description,
+                cover_file_id: request.cover_file_id,
+            })
+            .await?;
+
+        ctx.response(CollectionResponse::from_model(collection))
+    }
+
+    /// Updates the fields of a collection that are set in the request
+    #[tracing::instrument(skip_all)]
+    async fn update_collection(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<UpdateCollectionRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let collection = repo
+            .collection()
+            .update(UpdateCollectionDto {
+                id: request.id,
+                name: request.name,
+                description: request.description,
+                cover_file_id: request.cover_file_id,
+            })
+            .await?;
+
+        ctx.response(CollectionResponse::from_model(collection))
+    }
+
+    /// Deletes a collection
+    #[tracing::instrument(skip_all)]
+    async fn delete_collection(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let id = event.payload::<i64>()?;
+        let repo = get_repo_from_context(ctx).await;
+        repo.collection().delete(id).await?;
+
+        Ok(Response::empty())
+    }
+
+    /// Returns the files of a collection in their order
+    #[tracing::instrument(skip_all)]
+    async fn collection_files(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let id = event.payload::<i64>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let responses = file_responses(&repo, id).await?;
+
+        ctx.response(responses)
+    }
+
+    /// Inserts files into a collection and returns the files of the collection
+    #[tracing::instrument(skip_all)]
+    async fn insert_collection_files(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<InsertCollectionFilesRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file_ids = file_ids_by_identifiers(&repo, request.files).await?;
+        repo.collection()
+            .insert_files(request.collection_id, file_ids, request.position)
+            .await?;
+        let responses = file_responses(&repo, request.collection_id).await?;
+
+        ctx.response(responses)
+    }
+
+    /// Moves a file of a collection and returns the files of the collection
+    #[tracing::instrument(skip_all)]
+    async fn move_collection_file(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<MoveCollectionFileRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file = file_by_identifier(request.file, &repo).await?;
+        repo.collection()
+            .move_file(request.collection_id, file.id(), request.position)
+            .await?;
+        let responses = file_responses(&repo, request.collection_id).await?;
+
+        ctx.response(responses)
+    }
+
+    /// Removes files from a collection and returns the remaining files of the collection
+    #[tracing::instrument(skip_all)]
+    async fn remove_collection_files(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<RemoveCollectionFilesRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file_ids = file_ids_by_identifiers(&repo, request.files).await?;
+        repo.collection()
+            .remove_files(request.collection_id, file_ids)
+            .await?;
+        let responses = file_responses(&repo, request.collection_id).await?;
+
+        ctx.response(responses)
+    }
+}
+
+async fn file_responses(repo: &Repo, collection_id: i64) -> RepoResult<Vec<FileBasicDataResponse>> {
+    let files = repo
+        .collection()
+        .files(collection_id)
+        .await?
+        .into_iter()
+        .map(FileBasicDataResponse::from_model)
+        .collect();
+
+    Ok(files)
+}
+
+async fn file_ids_by_identifiers(
+    repo: &Repo,
+    identifiers: Vec<FileIdentifier>,
+) -> RepoResult<Vec<i64>> {
+    let mut file_ids = Vec::with_capacity(identifiers.len());
+
+    for identifier in identifiers {
+        file_ids.push(file_by_identifier(identifier, repo).await?.id());
+    }
+
+    Ok(file_ids)
+}
@@ -0,0 +1,137 @@
+use mediarepo_core::bromine::prelude::*;
+use mediarepo_core::error::RepoResult;
+use mediarepo_core::mediarepo_api::types::custom_fields::{
+    AddCustomFieldRequest, CustomFieldResponse, CustomFieldType, CustomFieldValue,
+    CustomFieldValueResponse, SetCustomFieldValuesRequest,
+};
+use mediarepo_core::mediarepo_api::types::identifier::FileIdentifier;
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use mediarepo_logic::dto::{
+    AddCustomFieldDto, CustomFieldType as CustomFieldTypeModel,
+    CustomFieldValue as CustomFieldValueModel,
+};
+
+use crate::from_model::FromModel;
+use crate::utils::{file_by_identifier, get_repo_from_context};
+
+pub struct CustomFieldsNamespace;
+
+impl NamespaceProvider for CustomFieldsNamespace {
+    fn name() -> &'static str {
+        "custom_fields"
+    }
+
+    fn register(handler: &mut EventHandler) {
+        events!(handler,
+            "all_custom_fields" => Self::all_custom_fields,
+            "add_custom_field" => Self::add_custom_field,
+            "delete_custom_field" => Self::delete_custom_field,
+            "custom_field_values_for_file" => Self::custom_field_values_for_file,
+            "set_custom_field_values" => Self::set_custom_field_values
+        );
+    }
+}
+
+impl CustomFieldsNamespace {
+    /// Returns all custom fields of the repository
+    #[tracing::instrument(skip_all)]
+    async fn all_custom_fields(ctx: &Context, _event: Event) -> IPCResult<Response> {
+        let repo = get_repo_from_context(ctx).await;
+        let fields: Vec<CustomFieldResponse> = repo
+            .custom_field()
+            .all()
+            .await?
+            .into_iter()
+            .map(CustomFieldResponse::from_model)
+            .collect();
+
+        ctx.response(fields)
+    }
+
+    /// Adds a new custom field
+    #[tracing::instrument(skip_all)]
+    async fn add_custom_field(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<AddCustomFieldRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let field = repo
+            .custom_field()
+            .add(AddCustomFieldDto {
+                name: request.name,
+                field_type: field_type_to_model(request.field_type),
+            })
+            .await?;
+
+        ctx.response(CustomFieldResponse::from_model(field))
+    }
+
+    /// Deletes a custom field together with all of its values
+    #[tracing::instrument(skip_all)]
+    async fn delete_custom_field(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let id = event.payload::<i64>()?;
+        let repo = get_repo_from_context(ctx).await;
+        repo.custom_field().delete(id).await?;
+
+        Ok(Response::empty())
+    }
+
+    /// Returns the custom field values of a file
+    #[tracing::instrument(skip_all)]
+    async fn custom_field_values_for_file(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let id = event.payload::<FileIdentifier>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file = file_by_identifier(id, &repo).await?;
+        let responses = value_responses(&repo, file.id()).await?;
+
+        ctx.response(responses)
+    }
+
+    /// Sets or removes custom field values of a file and returns all values of the file
+    #[tracing::instrument(skip_all)]
+    async fn set_custom_field_values(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<SetCustomFieldValuesRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file = file_by_identifier(request.file_id, &repo).await?;
+        let values = request
+            .values
+            .into_iter()
+            .map(|update| (update.field_id, update.value.map(value_to_model)))
+            .collect();
+        repo.custom_field().set_values(file.id(), values).await?;
+        let responses = value_responses(&repo, file.id()).await?;
+
+        ctx.response(responses)
+    }
+}
+
+async fn value_responses(repo: &Repo, file_id: i64) -> RepoResult<Vec<CustomFieldValueResponse>> {
+    let values = repo
+        .custom_field()
+        .values_for_file(file_id)
+        .await?
+        .into_iter()
+        .map(CustomFieldValueResponse::from_model)
+        .collect();
+
+    Ok(values)
+}
+
+fn field_type_to_model(field_type: CustomFieldType) -> CustomFieldTypeModel {
+    match field_type {
+        CustomFieldType::Int => CustomFieldTypeModel::Int,
+        CustomFieldType::Float => CustomFieldTypeModel::Float,
+        CustomFieldType::Text => CustomFieldTypeModel::Text,
+        CustomFieldType::Date => CustomFieldTypeModel::Date,
+        CustomFieldType::Bool => CustomFieldTypeModel::Bool,
+    }
+}
+
+pub(crate) fn value_to_model(value: CustomFieldValue) -> CustomFieldValueModel {
+    match value {
+        CustomFieldValue::Int(value) => CustomFieldValueModel::Int(value),
+        CustomFieldValue::Float(value) => CustomFieldValueModel::Float(value),
+        CustomFieldValue::Text(value) => CustomFieldValueModel::Text(value),
+        CustomFieldValue::Date(value) => CustomFieldValueModel::Date(value),
+        CustomFieldValue::Bool(value) => CustomFieldValueModel::Bool(value),
+    }
+}
@@ -0,0 +1,60 @@
+use crate::from_model::FromModel;
+use crate::utils::get_repo_from_context;
+use mediarepo_core::bromine::prelude::*;
+use mediarepo_core::mediarepo_api::types::duplicates::{
+    DuplicateCandidateResponse, DuplicateResolution, ResolveDuplicateRequest,
+};
+use mediarepo_logic::dao::DaoProvider;
+use mediarepo_logic::dto::DuplicateResolutionDto;
+
+pub struct DuplicatesNamespace;
+
+impl NamespaceProvider for DuplicatesNamespace {
+    fn name() -> &'static str {
+        "duplicates"
+    }
+
+    fn register(handler: &mut EventHandler) {
+        events!(handler,
+            "get_duplicate_candidates" => Self::get_duplicate_candidates,
+            "resolve_duplicate" => Self::resolve_duplicate
+        );
+    }
+}
+
+impl DuplicatesNamespace {
+    /// Returns all duplicate candidates that still need to be reviewed
+    #[tracing::instrument(skip_all)]
+    pub async fn get_duplicate_candidates(ctx: &Context, _: Event) -> IPCResult<Response> {
+        let repo = get_repo_from_context(ctx).await;
+        let candidates: Vec<DuplicateCandidateResponse> = repo
+            .duplicate()
+            .pending()
+            .await?
+            .into_iter()
+            .map(DuplicateCandidateResponse::from_model)
+            .collect();
+
+        ctx.response(candidates)
+    }
+
+    /// Resolves a duplicate candidate
+    #[tracing::instrument(skip_all)]
+    pub async fn resolve_duplicate(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<ResolveDuplicateRequest>()?;
+        let resolution = match request.resolution {
+            DuplicateResolution::Keep {
+                file_id,
+                merge_tags,
+            } => DuplicateResolutionDto::Keep {
+                file_id,
+                merge_tags,
+            },
+            DuplicateResolution::NotDuplicates => DuplicateResolutionDto::NotDuplicates,
+        };
+        let repo = get_repo_from_context(ctx).await;
+        repo.duplicate().resolve(request.id, resolution).await?;
+
+        Ok(Response::empty())
+    }
+}
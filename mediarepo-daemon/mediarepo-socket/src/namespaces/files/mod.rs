@@ -1,28 +1,39 @@
-use tokio::io::AsyncReadExt;
+use std::path::PathBuf;
 
 use mediarepo_core::bromine::prelude::*;
 use mediarepo_core::content_descriptor::{create_content_descriptor, encode_content_descriptor};
-use mediarepo_core::error::RepoError;
+use mediarepo_core::error::{RepoError, RepoResult};
 use mediarepo_core::fs::thumbnail_store::Dimensions;
 use mediarepo_core::itertools::Itertools;
+use mediarepo_core::media_metadata::supports_embedded_fields;
+use mediarepo_core::mediarepo_api::types::facets::{SearchFacetsRequest, SearchFacetsResponse};
 use mediarepo_core::mediarepo_api::types::files::{
-    AddFileRequestHeader, FileBasicDataResponse, FileMetadataResponse,
-    GetFileThumbnailOfSizeRequest, GetFileThumbnailsRequest, ReadFileRequest,
-    ThumbnailMetadataResponse, UpdateFileNameRequest, UpdateFileStatusRequest,
+    AddFileByReferenceRequest, AddFileRequestHeader, CommitUploadRequest, FileBasicDataResponse,
+    FileMetadataResponse, FileOSMetadata, FileRangeResponse, FileRatingResponse,
+    FileUploadStatusResponse, FindFilesPageResponse, GetFileThumbnailOfSizeRequest,
+    GetFileThumbnailsRequest, MediaMetadataResponse, ReadFileRangeRequest, ReadFileRequest,
+    ThumbnailMetadataResponse, UpdateFileMetadataRequest, UpdateFileNameRequest,
+    UpdateFileRatingRequest, UpdateFileStatusRequest, UploadChunkRequestHeader, MAX_RANGE_LENGTH,
 };
-use mediarepo_core::mediarepo_api::types::filtering::FindFilesRequest;
+use mediarepo_core::mediarepo_api::types::filtering::{FindFilesPageRequest, FindFilesRequest};
 use mediarepo_core::mediarepo_api::types::identifier::FileIdentifier;
 use mediarepo_core::thumbnailer::ThumbnailSize;
+use mediarepo_core::type_keys::SettingsKey;
 use mediarepo_core::utils::parse_namespace_and_tag;
+use mediarepo_logic::dao::repo::Repo;
 use mediarepo_logic::dao::DaoProvider;
-use mediarepo_logic::dto::{AddFileDto, AddTagDto, UpdateFileDto, UpdateFileMetadataDto};
+use mediarepo_logic::dto::{
+    AddFileDto, AddTagDto, FileContent, FileDto, FileMetadataDto, UpdateFileDto,
+    UpdateFileMetadataDto, UpdateFileRatingDto,
+};
 
 use crate::from_model::FromModel;
-use crate::namespaces::files::searching::find_files_for_filters;
-use crate::namespaces::files::sorting::sort_files_by_properties;
+use crate::namespaces::files::searching::{
+    find_files_for_filters, find_files_page_for_filters, search_facets_for_filters,
+};
 use crate::utils::{cd_by_identifier, file_by_identifier, get_repo_from_context};
 
-mod searching;
+pub(crate) mod searching;
 mod sorting;
 
 pub struct FilesNamespace;
@@ -39,11 +50,24 @@ impl NamespaceProvider for FilesNamespace {
             "get_file_metadata" => Self::get_file_metadata,
             "get_files" => Self::get_files,
             "find_files" => Self::find_files,
+            "find_files_page" => Self::find_files_page,
+            "search_facets" => Self::search_facets,
             "add_file" => Self::add_file,
+            "add_file_by_reference" => Self::add_file_by_reference,
+            "begin_upload" => Self::begin_upload,
+            "upload_status" => Self::upload_status,
+            "upload_chunk" => Self::upload_chunk,
+            "commit_upload" => Self::commit_upload,
+            "abort_upload" => Self::abort_upload,
             "read_file" => Self::read_file,
+            "read_file_range" => Self::read_file_range,
             "get_thumbnails" => Self::thumbnails,
             "get_thumbnail_of_size" => Self::get_thumbnail_of_size,
             "update_file_name" => Self::update_file_name,
+            "update_file_metadata" => Self::update_file_metadata,
+            "update_files_metadata" => Self::update_files_metadata,
+            "get_file_rating" => Self::get_file_rating,
+            "update_file_rating" => Self::update_file_rating,
             "delete_thumbnails" => Self::delete_thumbnails,
             "update_file_status" => Self::update_status,
             "delete_file" => Self::delete_file
@@ -84,6 +108,7 @@ impl FilesNamespace {
         let repo = get_repo_from_context(ctx).await;
         let file = file_by_identifier(id, &repo).await?;
         let file_id = file.id();
+        let cd_id = file.cd_id();
 
         let metadata = if let Some(metadata) = file.into_metadata() {
             metadata
@@ -94,7 +119,7 @@ impl FilesNamespace {
                 .ok_or_else(|| RepoError::from("file metadata not found"))?
         };
 
-        ctx.response(FileMetadataResponse::from_model(metadata))
+        ctx.response(file_metadata_response(&repo, cd_id, metadata).await?)
     }
 
     /// Returns a list of files by identifier
@@ -121,8 +146,7 @@ impl FilesNamespace {
         let req = event.payload::<FindFilesRequest>()?;
         let repo = get_repo_from_context(ctx).await;
 
-        let mut files = find_files_for_filters(&repo, req.filters).await?;
-        sort_files_by_properties(&repo, req.sort_expression, &mut files).await?;
+        let files = find_files_for_filters(&repo, req.filters, req.sort_expression).await?;
 
         let responses: Vec<FileBasicDataResponse> = files
             .into_iter()
@@ -132,13 +156,48 @@ impl FilesNamespace {
         ctx.response(responses)
     }
 
+    /// Searches for files and returns a page of the results with the total number of matches
+    #[tracing::instrument(skip_all)]
+    async fn find_files_page(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let FindFilesPageRequest {
+            query,
+            offset,
+            limit,
+        } = event.payload::<FindFilesPageRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+
+        let (files, total) =
+            find_files_page_for_filters(&repo, query.filters, query.sort_expression, offset, limit)
+                .await?;
+        let files = files
+            .into_iter()
+            .map(FileBasicDataResponse::from_model)
+            .collect();
+
+        ctx.response(FindFilesPageResponse { files, total })
+    }
+
+    /// Returns the facets of the files matching a search
+    #[tracing::instrument(skip_all)]
+    async fn search_facets(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let SearchFacetsRequest { query, tag_limit } = event.payload::<SearchFacetsRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let facets = search_facets_for_filters(&repo, query.filters, tag_limit).await?;
+
+        ctx.response(SearchFacetsResponse::from_model(facets))
+    }
+
     /// Adds a file to the repository
     #[tracing::instrument(skip_all)]
     async fn add_file(ctx: &Context, event: Event) -> IPCResult<Response> {
         let (request, bytes) = event
             .payload::<TandemPayload<AddFileRequestHeader, BytePayload>>()?
             .into_inner();
-        let AddFileRequestHeader { metadata, tags } = request;
+        let AddFileRequestHeader {
+            metadata,
+            tags,
+            sources,
+        } = request;
         let repo = get_repo_from_context(ctx).await;
         let bytes = bytes.into_inner();
         let cd = create_content_descriptor(&bytes);
@@ -147,35 +206,111 @@ impl FilesNamespace {
             tracing::debug!("Inserted file already exists");
             file
         } else {
-            let add_dto = AddFileDto {
-                content: bytes,
-                mime_type: metadata
-                    .mime_type
-                    .unwrap_or_else(|| String::from("application/octet-stream")),
-                creation_time: metadata.creation_time,
-                change_time: metadata.change_time,
-                name: Some(metadata.name),
-            };
-            repo.file().add(add_dto).await?
+            let file = repo
+                .file()
+                .add(add_file_dto(FileContent::Bytes(bytes), metadata))
+                .await?;
+            add_embedded_tags_to_file(ctx, &repo, &file).await;
+            file
         };
+        add_tags_to_file(&repo, &file, tags).await?;
+        repo.source().add_to_cd(file.cd_id(), sources).await?;
+
+        ctx.response(FileBasicDataResponse::from_model(file))
+    }
+
+    /// Adds a file from the file system of the daemon without transferring its contents
+    #[tracing::instrument(skip_all)]
+    async fn add_file_by_reference(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let AddFileByReferenceRequest { mode, header } =
+            event.payload::<AddFileByReferenceRequest>()?;
+        let AddFileRequestHeader {
+            metadata,
+            tags,
+            sources,
+        } = header;
+        let repo = get_repo_from_context(ctx).await;
+        let content = FileContent::Reference {
+            path: PathBuf::from(&metadata.path),
+            mode: mode.into(),
+        };
+        let file = repo.file().add(add_file_dto(content, metadata)).await?;
+        add_embedded_tags_to_file(ctx, &repo, &file).await;
+        add_tags_to_file(&repo, &file, tags).await?;
+        repo.source().add_to_cd(file.cd_id(), sources).await?;
+
+        ctx.response(FileBasicDataResponse::from_model(file))
+    }
 
-        let tags = repo
-            .tag()
-            .add_all(
-                tags.into_iter()
-                    .map(parse_namespace_and_tag)
-                    .map(AddTagDto::from_tuple)
-                    .collect(),
-            )
+    /// Starts a new chunked upload
+    #[tracing::instrument(skip_all)]
+    async fn begin_upload(ctx: &Context, _event: Event) -> IPCResult<Response> {
+        let repo = get_repo_from_context(ctx).await;
+        let upload_id = repo.file().begin_upload().await?;
+
+        ctx.response(FileUploadStatusResponse {
+            upload_id,
+            offset: 0,
+        })
+    }
+
+    /// Returns the number of bytes received for an upload so it can be resumed
+    #[tracing::instrument(skip_all)]
+    async fn upload_status(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let upload_id = event.payload::<String>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let offset = repo.file().upload_offset(&upload_id).await?;
+
+        ctx.response(FileUploadStatusResponse { upload_id, offset })
+    }
+
+    /// Appends a chunk of data to an upload
+    #[tracing::instrument(skip_all)]
+    async fn upload_chunk(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let (request, bytes) = event
+            .payload::<TandemPayload<UploadChunkRequestHeader, BytePayload>>()?
+            .into_inner();
+        let UploadChunkRequestHeader { upload_id, offset } = request;
+        let repo = get_repo_from_context(ctx).await;
+        let offset = repo
+            .file()
+            .append_upload_chunk(&upload_id, offset, &bytes.into_inner())
             .await?;
-        let tag_ids: Vec<i64> = tags.into_iter().map(|t| t.id()).unique().collect();
-        repo.tag()
-            .upsert_mappings(vec![file.cd_id()], tag_ids)
+
+        ctx.response(FileUploadStatusResponse { upload_id, offset })
+    }
+
+    /// Adds the file of a completed upload to the repository
+    #[tracing::instrument(skip_all)]
+    async fn commit_upload(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let CommitUploadRequest { upload_id, header } = event.payload::<CommitUploadRequest>()?;
+        let AddFileRequestHeader {
+            metadata,
+            tags,
+            sources,
+        } = header;
+        let repo = get_repo_from_context(ctx).await;
+        let file = repo
+            .file()
+            .add(add_file_dto(FileContent::Upload(upload_id), metadata))
             .await?;
+        add_embedded_tags_to_file(ctx, &repo, &file).await;
+        add_tags_to_file(&repo, &file, tags).await?;
+        repo.source().add_to_cd(file.cd_id(), sources).await?;
 
         ctx.response(FileBasicDataResponse::from_model(file))
     }
 
+    /// Discards an upload
+    #[tracing::instrument(skip_all)]
+    async fn abort_upload(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let upload_id = event.payload::<String>()?;
+        let repo = get_repo_from_context(ctx).await;
+        repo.file().abort_upload(&upload_id).await?;
+
+        Ok(Response::empty())
+    }
+
     #[tracing::instrument(skip_all)]
     async fn update_status(ctx: &Context, event: Event) -> IPCResult<Response> {
         let request = event.payload::<UpdateFileStatusRequest>()?;
@@ -204,6 +339,27 @@ impl FilesNamespace {
         ctx.response(BytePayload::new(bytes))
     }
 
+    /// Reads a range of the binary contents of a file. Ranges longer
+    /// than [MAX_RANGE_LENGTH] are shortened
+    #[tracing::instrument(skip_all)]
+    async fn read_file_range(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<ReadFileRangeRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file = file_by_identifier(request.id, &repo).await?;
+        let length = request.length.min(MAX_RANGE_LENGTH);
+        let (bytes, total_size) = repo
+            .file()
+            .get_range(file.cd(), request.offset, length)
+            .await?;
+        let range_payload = FileRangeResponse {
+            offset: request.offset,
+            total_size,
+            mime_type: file.mime_type().to_owned(),
+        };
+
+        ctx.response(TandemPayload::new(range_payload, BytePayload::new(bytes)))
+    }
+
     /// Deletes a file
     #[tracing::instrument(skip_all)]
     async fn delete_file(ctx: &Context, event: Event) -> IPCResult<Response> {
@@ -279,9 +435,7 @@ impl FilesNamespace {
                 .next()
                 .ok_or_else(|| RepoError::from("thumbnail could not be created"))?
         };
-        let mut buf = Vec::new();
-        thumbnail.get_reader().await?.read_to_end(&mut buf).await?;
-        let byte_payload = BytePayload::new(buf);
+        let byte_payload = BytePayload::new(thumbnail.get_bytes().await?);
         let thumb_payload = ThumbnailMetadataResponse::from_model(thumbnail);
 
         ctx.response(TandemPayload::new(thumb_payload, byte_payload))
@@ -303,7 +457,74 @@ impl FilesNamespace {
             })
             .await?;
 
-        ctx.response(FileMetadataResponse::from_model(metadata))
+        ctx.response(file_metadata_response(&repo, file.cd_id(), metadata).await?)
+    }
+
+    /// Updates the name, comment and timestamps of a file
+    #[tracing::instrument(skip_all)]
+    async fn update_file_metadata(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let repo = get_repo_from_context(ctx).await;
+        let request = event.payload::<UpdateFileMetadataRequest>()?;
+        let file = file_by_identifier(request.file_id.clone(), &repo).await?;
+
+        let metadata = repo
+            .file()
+            .update_metadata(update_file_metadata_dto(file.id(), request))
+            .await?;
+
+        ctx.response(file_metadata_response(&repo, file.cd_id(), metadata).await?)
+    }
+
+    /// Updates the metadata of multiple files in a single transaction
+    #[tracing::instrument(skip_all)]
+    async fn update_files_metadata(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let repo = get_repo_from_context(ctx).await;
+        let requests = event.payload::<Vec<UpdateFileMetadataRequest>>()?;
+        let mut cd_ids = Vec::with_capacity(requests.len());
+        let mut update_dtos = Vec::with_capacity(requests.len());
+
+        for request in requests {
+            let file = file_by_identifier(request.file_id.clone(), &repo).await?;
+            cd_ids.push(file.cd_id());
+            update_dtos.push(update_file_metadata_dto(file.id(), request));
+        }
+        let metadata = repo.file().update_all_metadata(update_dtos).await?;
+        let mut responses = Vec::with_capacity(metadata.len());
+
+        for (cd_id, metadata) in cd_ids.into_iter().zip(metadata) {
+            responses.push(file_metadata_response(&repo, cd_id, metadata).await?);
+        }
+
+        ctx.response(responses)
+    }
+
+    /// Returns the rating and favorite flag of a file
+    #[tracing::instrument(skip_all)]
+    async fn get_file_rating(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let repo = get_repo_from_context(ctx).await;
+        let id = event.payload::<FileIdentifier>()?;
+        let file = file_by_identifier(id, &repo).await?;
+        let rating = repo.file().rating(file.id()).await?;
+
+        ctx.response(FileRatingResponse::from_model(rating))
+    }
+
+    /// Updates the rating and favorite flag of a file
+    #[tracing::instrument(skip_all)]
+    async fn update_file_rating(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let repo = get_repo_from_context(ctx).await;
+        let request = event.payload::<UpdateFileRatingRequest>()?;
+        let file = file_by_identifier(request.file_id, &repo).await?;
+        let rating = repo
+            .file()
+            .update_rating(UpdateFileRatingDto {
+                file_id: file.id(),
+                rating: request.rating,
+                favorite: request.favorite,
+            })
+            .await?;
+
+        ctx.response(FileRatingResponse::from_model(rating))
     }
 
     /// Deletes all thumbnails of a file
@@ -312,12 +533,86 @@ impl FilesNamespace {
         let repo = get_repo_from_context(ctx).await;
         let id = event.payload::<FileIdentifier>()?;
         let file = file_by_identifier(id, &repo).await?;
-        let thumbnails = repo.file().thumbnails(file.encoded_cd()).await?;
-
-        for thumb in thumbnails {
-            thumb.delete().await?;
-        }
+        repo.file().delete_thumbnails(&file).await?;
 
         Ok(Response::empty())
     }
 }
+
+fn add_file_dto(content: FileContent, metadata: FileOSMetadata) -> AddFileDto {
+    AddFileDto {
+        content,
+        mime_type: metadata
+            .mime_type
+            .unwrap_or_else(|| String::from("application/octet-stream")),
+        creation_time: metadata.creation_time,
+        change_time: metadata.change_time,
+        name: Some(metadata.name),
+    }
+}
+
+async fn add_tags_to_file(repo: &Repo, file: &FileDto, tags: Vec<String>) -> RepoResult<()> {
+    let tags = repo
+        .tag()
+        .add_all(
+            tags.into_iter()
+                .map(parse_namespace_and_tag)
+                .map(AddTagDto::from_tuple)
+                .collect(),
+        )
+        .await?;
+    let tag_ids: Vec<i64> = tags.into_iter().map(|t| t.id()).unique().collect();
+    repo.tag()
+        .upsert_mappings(vec![file.cd_id()], tag_ids)
+        .await?;
+
+    Ok(())
+}
+
+fn update_file_metadata_dto(
+    file_id: i64,
+    request: UpdateFileMetadataRequest,
+) -> UpdateFileMetadataDto {
+    UpdateFileMetadataDto {
+        file_id,
+        name: request.name.map(Some),
+        comment: request.comment,
+        creation_time: request.creation_time,
+        change_time: request.change_time,
+        ..Default::default()
+    }
+}
+
+/// Tags an imported file with its embedded metadata if the extraction is enabled
+async fn add_embedded_tags_to_file(ctx: &Context, repo: &Repo, file: &FileDto) {
+    let settings = {
+        let data = ctx.data.read().await;
+        data.get::<SettingsKey>().unwrap().tag_extraction.clone()
+    };
+    if !settings.enabled || !supports_embedded_fields(file.mime_type(), &settings.mappings) {
+        return;
+    }
+    if let Err(e) = repo.tag().add_embedded_tags(file, &settings.mappings).await {
+        tracing::warn!(
+            "failed to extract embedded tags for {}: {}",
+            file.encoded_cd(),
+            e
+        );
+    }
+}
+
+/// Builds the metadata response of a file including its media metadata
+async fn file_metadata_response(
+    repo: &Repo,
+    cd_id: i64,
+    metadata: FileMetadataDto,
+) -> RepoResult<FileMetadataResponse> {
+    let mut response = FileMetadataResponse::from_model(metadata);
+    response.media = repo
+        .file()
+        .media_metadata(cd_id)
+        .await?
+        .map(MediaMetadataResponse::from_model);
+
+    Ok(response)
+}
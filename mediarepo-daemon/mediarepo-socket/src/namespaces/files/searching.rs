@@ -4,137 +4,299 @@ use mediarepo_core::content_descriptor::decode_content_descriptor;
 use mediarepo_core::error::RepoResult;
 use mediarepo_core::mediarepo_api::types::files::FileStatus as ApiFileStatus;
 use mediarepo_core::mediarepo_api::types::filtering::{
-    FilterExpression, FilterQuery, PropertyQuery, TagQuery, ValueComparator,
+    CustomFieldQuery, FilterExpression, FilterQuery, PropertyQuery, SortKey, TagQuery,
+    ValueComparator,
 };
 use mediarepo_logic::dao::file::find::NegatableComparator::{Is, IsNot};
-use mediarepo_logic::dao::file::find::{FilterFileProperty, FilterProperty, OrderingComparator};
+use mediarepo_logic::dao::file::find::{
+    FilterCondition, FilterCustomFieldValue, FilterFileProperty, FilterMediaProperty,
+    FilterProperty, OrderingComparator,
+};
 use mediarepo_logic::dao::repo::Repo;
 use mediarepo_logic::dao::DaoProvider;
-use mediarepo_logic::dto::{FileDto, FileStatus};
+use mediarepo_logic::dto::{
+    CustomFieldDto, CustomFieldType, CustomFieldValue, FileDto, FileFacetsDto, FileStatus,
+};
 
+use crate::namespaces::custom_fields::value_to_model;
+use crate::namespaces::files::sorting::{sort_files_by_properties, sort_keys_to_ordering};
+use crate::namespaces::relations::relation_type_to_model;
+
+/// Finds all files matching the filters sorted by the sort expression
 #[tracing::instrument(level = "debug", skip(repo))]
 pub async fn find_files_for_filters(
     repo: &Repo,
     expressions: Vec<FilterExpression>,
+    sort_expression: Vec<SortKey>,
 ) -> RepoResult<Vec<FileDto>> {
+    let filters = build_filter_condition(repo, expressions).await?;
+
+    // namespaces are sorted in memory so that their tags are compared with natural ordering
+    if sort_expression
+        .iter()
+        .any(|key| matches!(key, SortKey::Namespace(_)))
+    {
+        let mut files = repo.file().find(filters).await?;
+        sort_files_by_properties(repo, sort_expression, &mut files).await?;
+
+        Ok(files)
+    } else {
+        let ordering = sort_keys_to_ordering(&sort_expression);
+        repo.file().find_sorted(filters, ordering, 0, None).await
+    }
+}
+
+/// Finds a page of the files matching the filters sorted by the sort expression.
+/// The files are sorted in the database which compares numeric namespace tags by their
+/// value and all other tags by their text instead of using natural ordering.
+/// Returns the files of the page and the total number of matching files
+#[tracing::instrument(level = "debug", skip(repo))]
+pub async fn find_files_page_for_filters(
+    repo: &Repo,
+    expressions: Vec<FilterExpression>,
+    sort_expression: Vec<SortKey>,
+    offset: u64,
+    limit: u64,
+) -> RepoResult<(Vec<FileDto>, u64)> {
+    let filters = build_filter_condition(repo, expressions).await?;
+    let ordering = sort_keys_to_ordering(&sort_expression);
+    let total = repo.file().count(filters.clone()).await?;
+    let files = repo
+        .file()
+        .find_sorted(filters, ordering, offset, Some(limit))
+        .await?;
+
+    Ok((files, total))
+}
+
+/// Returns the facets of the files matching the filters
+#[tracing::instrument(level = "debug", skip(repo))]
+pub async fn search_facets_for_filters(
+    repo: &Repo,
+    expressions: Vec<FilterExpression>,
+    tag_limit: u64,
+) -> RepoResult<FileFacetsDto> {
+    let filters = build_filter_condition(repo, expressions).await?;
+
+    repo.file().facets(filters, tag_limit).await
+}
+
+/// Data from outside the query that is needed to build the filters
+#[derive(Debug, Default)]
+struct FilterContext {
+    tag_ids: HashMap<String, i64>,
+    custom_fields: HashMap<String, CustomFieldDto>,
+}
+
+async fn build_filter_condition(
+    repo: &Repo,
+    expressions: Vec<FilterExpression>,
+) -> RepoResult<FilterCondition> {
     let tag_names = get_tag_names_from_expressions(&expressions);
-    let tag_id_map = repo.tag().normalized_tags_to_ids(tag_names).await?;
-    let filters = build_filters_from_expressions(expressions, &tag_id_map);
+    let mut context = FilterContext {
+        tag_ids: repo.tag().normalized_tags_to_ids(tag_names).await?,
+        ..Default::default()
+    };
+    if expressions
+        .iter()
+        .any(|e| contains_property(e, &|p| matches!(p, PropertyQuery::CustomField(_))))
+    {
+        context.custom_fields = repo
+            .custom_field()
+            .all()
+            .await?
+            .into_iter()
+            .map(|f| (f.name().to_owned(), f))
+            .collect();
+    }
+
+    Ok(build_filters_from_expressions(expressions, &context))
+}
 
-    repo.file().find(filters).await
+fn contains_property<F: Fn(&PropertyQuery) -> bool>(
+    expression: &FilterExpression,
+    predicate: &F,
+) -> bool {
+    match expression {
+        FilterExpression::OrExpression(queries) => queries
+            .iter()
+            .any(|q| matches!(q, FilterQuery::Property(p) if predicate(p))),
+        FilterExpression::Query(FilterQuery::Property(property)) => predicate(property),
+        FilterExpression::Query(FilterQuery::Tag(_)) => false,
+        FilterExpression::And(expressions) | FilterExpression::Or(expressions) => {
+            expressions.iter().any(|e| contains_property(e, predicate))
+        }
+        FilterExpression::Not(expression) => contains_property(expression, predicate),
+    }
 }
 
 #[tracing::instrument(level = "debug")]
 fn get_tag_names_from_expressions(expressions: &Vec<FilterExpression>) -> Vec<String> {
-    expressions
-        .iter()
-        .flat_map(|f| match f {
-            FilterExpression::OrExpression(queries) => queries
-                .iter()
-                .filter_map(|q| match q {
-                    FilterQuery::Tag(tag) => Some(tag.tag.to_owned()),
-                    _ => None,
-                })
-                .collect::<Vec<String>>(),
-            FilterExpression::Query(q) => match q {
-                FilterQuery::Tag(tag) => {
-                    vec![tag.tag.to_owned()]
-                }
-                FilterQuery::Property(_) => {
-                    vec![]
-                }
-            },
-        })
-        .collect::<Vec<String>>()
+    let mut tag_names = Vec::new();
+
+    for expression in expressions {
+        collect_tag_names(expression, &mut tag_names);
+    }
+
+    tag_names
+}
+
+fn collect_tag_names(expression: &FilterExpression, tag_names: &mut Vec<String>) {
+    match expression {
+        FilterExpression::OrExpression(queries) => {
+            tag_names.extend(queries.iter().filter_map(|q| match q {
+                FilterQuery::Tag(tag) if !is_tag_pattern(&tag.tag) => Some(unescape_tag(&tag.tag)),
+                _ => None,
+            }))
+        }
+        FilterExpression::Query(FilterQuery::Tag(tag)) if !is_tag_pattern(&tag.tag) => {
+            tag_names.push(unescape_tag(&tag.tag))
+        }
+        FilterExpression::Query(_) => {}
+        FilterExpression::And(expressions) | FilterExpression::Or(expressions) => {
+            for expression in expressions {
+                collect_tag_names(expression, tag_names);
+            }
+        }
+        FilterExpression::Not(expression) => collect_tag_names(expression, tag_names),
+    }
 }
 
 #[tracing::instrument(level = "debug")]
 fn build_filters_from_expressions(
     expressions: Vec<FilterExpression>,
-    tag_id_map: &HashMap<String, i64>,
-) -> Vec<Vec<FilterProperty>> {
-    expressions
-        .into_iter()
-        .filter_map(|e| {
-            let filters = match e {
-                FilterExpression::OrExpression(queries) => queries
-                    .into_iter()
-                    .filter_map(|q| map_query_to_filter(q, tag_id_map))
-                    .collect(),
-                FilterExpression::Query(q) => {
-                    if let Some(filter) = map_query_to_filter(q, tag_id_map) {
-                        vec![filter]
-                    } else {
-                        vec![]
-                    }
-                }
-            };
-            if filters.is_empty() {
-                None
-            } else {
-                Some(filters)
-            }
-        })
-        .collect()
+    context: &FilterContext,
+) -> FilterCondition {
+    FilterCondition::And(
+        expressions
+            .into_iter()
+            .filter_map(|e| build_filter_from_expression(e, context))
+            .collect(),
+    )
 }
 
-fn map_query_to_filter(
-    query: FilterQuery,
-    tag_id_map: &HashMap<String, i64>,
-) -> Option<FilterProperty> {
-    match query {
-        FilterQuery::Tag(tag_query) => map_tag_query_to_filter(tag_query, tag_id_map),
-        FilterQuery::Property(property) => map_property_query_to_filter(property),
+/// Builds the condition for an expression. Expressions without any queries are skipped.
+/// Queries that can't be resolved (e.g. unknown tags) never match so that
+/// the meaning of the surrounding boolean expression is kept
+fn build_filter_from_expression(
+    expression: FilterExpression,
+    context: &FilterContext,
+) -> Option<FilterCondition> {
+    match expression {
+        FilterExpression::OrExpression(queries) => non_empty(
+            queries
+                .into_iter()
+                .map(|q| map_query_to_condition(q, context))
+                .collect(),
+        )
+        .map(FilterCondition::Or),
+        FilterExpression::Query(q) => Some(map_query_to_condition(q, context)),
+        FilterExpression::And(expressions) => {
+            build_filters_from_list(expressions, context).map(FilterCondition::And)
+        }
+        FilterExpression::Or(expressions) => {
+            build_filters_from_list(expressions, context).map(FilterCondition::Or)
+        }
+        FilterExpression::Not(expression) => build_filter_from_expression(*expression, context)
+            .map(|c| FilterCondition::Not(Box::new(c))),
     }
 }
 
-fn map_tag_query_to_filter(
-    query: TagQuery,
-    tag_id_map: &HashMap<String, i64>,
-) -> Option<FilterProperty> {
-    if query.tag.ends_with('*') {
-        map_wildcard_tag_to_filter(query, tag_id_map)
+fn build_filters_from_list(
+    expressions: Vec<FilterExpression>,
+    context: &FilterContext,
+) -> Option<Vec<FilterCondition>> {
+    non_empty(
+        expressions
+            .into_iter()
+            .filter_map(|e| build_filter_from_expression(e, context))
+            .collect(),
+    )
+}
+
+fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+    if items.is_empty() {
+        None
     } else {
-        map_tag_to_filter(query, tag_id_map)
+        Some(items)
+    }
+}
+
+fn map_query_to_condition(query: FilterQuery, context: &FilterContext) -> FilterCondition {
+    match query {
+        FilterQuery::Tag(tag_query) => map_tag_query_to_condition(tag_query, &context.tag_ids),
+        FilterQuery::Property(property) => map_property_query_to_filter(property, context)
+            .map(FilterCondition::Leaf)
+            .unwrap_or(FilterCondition::Never),
     }
 }
 
-fn map_wildcard_tag_to_filter(
+fn map_tag_query_to_condition(
     query: TagQuery,
     tag_id_map: &HashMap<String, i64>,
-) -> Option<FilterProperty> {
-    let filter_tag = query.tag.trim_end_matches('*');
-    let relevant_ids = tag_id_map
-        .iter()
-        .filter_map(|(name, id)| {
-            if name.starts_with(filter_tag) {
-                Some(*id)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<i64>>();
-
-    if relevant_ids.is_empty() {
-        None
-    } else {
+) -> FilterCondition {
+    if is_tag_pattern(&query.tag) {
         let comparator = if query.negate {
-            IsNot(relevant_ids)
+            IsNot(query.tag)
         } else {
-            Is(relevant_ids)
+            Is(query.tag)
         };
-        Some(FilterProperty::TagWildcardIds(comparator))
+        FilterCondition::Leaf(FilterProperty::TagPattern(comparator))
+    } else {
+        map_tag_to_condition(query, tag_id_map)
+    }
+}
+
+/// Returns if the tag contains wildcards that aren't escaped with `\`
+/// and is matched as a pattern
+fn is_tag_pattern(tag: &str) -> bool {
+    let mut chars = tag.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '*' | '?' => return true,
+            _ => {}
+        }
     }
+
+    false
 }
 
-fn map_tag_to_filter(query: TagQuery, tag_id_map: &HashMap<String, i64>) -> Option<FilterProperty> {
-    tag_id_map.get(&query.tag).map(|id| {
-        let comparator = if query.negate { IsNot(*id) } else { Is(*id) };
-        FilterProperty::TagId(comparator)
-    })
+/// Removes the escaping `\` of a tag that isn't matched as a pattern
+fn unescape_tag(tag: &str) -> String {
+    let mut unescaped = String::with_capacity(tag.len());
+    let mut chars = tag.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+
+    unescaped
 }
 
-fn map_property_query_to_filter(query: PropertyQuery) -> Option<FilterProperty> {
+/// Maps the tag to a filter on its id. Tags that don't exist aren't assigned to
+/// any file so they never match and their negation always matches
+fn map_tag_to_condition(query: TagQuery, tag_id_map: &HashMap<String, i64>) -> FilterCondition {
+    match tag_id_map.get(&unescape_tag(&query.tag)) {
+        Some(id) => {
+            let comparator = if query.negate { IsNot(*id) } else { Is(*id) };
+            FilterCondition::Leaf(FilterProperty::TagId(comparator))
+        }
+        None if query.negate => FilterCondition::Not(Box::new(FilterCondition::Never)),
+        None => FilterCondition::Never,
+    }
+}
+
+fn map_property_query_to_filter(
+    query: PropertyQuery,
+    context: &FilterContext,
+) -> Option<FilterProperty> {
     match query {
         PropertyQuery::Status(s) => Some(FilterProperty::FileProperty(FilterFileProperty::Status(
             Is(file_status_to_number(s)),
@@ -160,14 +322,113 @@ fn map_property_query_to_filter(query: PropertyQuery) -> Option<FilterProperty>
             .ok()
             .map(|cd| FilterProperty::ContentDescriptor(Is(cd))),
         PropertyQuery::Id(id) => Some(FilterProperty::FileProperty(FilterFileProperty::Id(Is(id)))),
+        PropertyQuery::Name(text) => Some(FilterProperty::FileProperty(
+            FilterFileProperty::NameText(text),
+        )),
+        PropertyQuery::Comment(text) => Some(FilterProperty::FileProperty(
+            FilterFileProperty::CommentText(text),
+        )),
+        PropertyQuery::MimeType(mime_type) => Some(FilterProperty::FileProperty(
+            FilterFileProperty::MimeType(Is(mime_type)),
+        )),
+        PropertyQuery::Namespace(namespace) => Some(FilterProperty::Namespace(Is(namespace))),
+        PropertyQuery::Untagged(untagged) => Some(FilterProperty::Untagged(untagged)),
+        PropertyQuery::SourceDomain(domain) => Some(FilterProperty::SourceDomain(Is(domain))),
+        PropertyQuery::SourceUrl(url) => Some(FilterProperty::SourceUrl(Is(url))),
+        PropertyQuery::Rating(rating) => Some(FilterProperty::Rating(val_comparator_to_order(
+            rating,
+            |v| v as i64,
+        ))),
+        PropertyQuery::Favorite(favorite) => Some(FilterProperty::Favorite(favorite)),
+        PropertyQuery::InCollection(id) => Some(FilterProperty::InCollection(id)),
+        PropertyQuery::Relation(relation) => Some(FilterProperty::Relation(
+            relation_type_to_model(relation.relation_type),
+            relation.related_file_id,
+        )),
+        PropertyQuery::CustomField(field_query) => {
+            map_custom_field_query_to_filter(field_query, &context.custom_fields)
+        }
+        PropertyQuery::MissingThumbnail(missing) => Some(FilterProperty::MissingThumbnail(missing)),
+        PropertyQuery::Width(w) => Some(FilterProperty::MediaProperty(FilterMediaProperty::Width(
+            val_comparator_to_order(w, |v| v as i64),
+        ))),
+        PropertyQuery::Height(h) => Some(FilterProperty::MediaProperty(
+            FilterMediaProperty::Height(val_comparator_to_order(h, |v| v as i64)),
+        )),
+        PropertyQuery::Duration(d) => Some(FilterProperty::MediaProperty(
+            FilterMediaProperty::Duration(val_comparator_to_order(d, |v| v as i64)),
+        )),
+        PropertyQuery::Bitrate(b) => Some(FilterProperty::MediaProperty(
+            FilterMediaProperty::Bitrate(val_comparator_to_order(b, |v| v as i64)),
+        )),
+        PropertyQuery::VideoCodec(codec) => Some(FilterProperty::MediaProperty(
+            FilterMediaProperty::VideoCodec(Is(codec)),
+        )),
+        PropertyQuery::AudioCodec(codec) => Some(FilterProperty::MediaProperty(
+            FilterMediaProperty::AudioCodec(Is(codec)),
+        )),
     }
 }
 
+/// Maps the query to a filter on the field with the same name. Returns `None`
+/// if the field doesn't exist or the values can't be converted to its type
+fn map_custom_field_query_to_filter(
+    query: CustomFieldQuery,
+    fields: &HashMap<String, CustomFieldDto>,
+) -> Option<FilterProperty> {
+    let field = fields.get(&query.name)?;
+    let field_type = field.field_type();
+    let comparator = query.comparator;
+    let cast = |v| value_to_model(v).cast(field_type);
+
+    let value = match field_type {
+        CustomFieldType::Int => {
+            FilterCustomFieldValue::Int(try_val_comparator_to_order(comparator, |v| {
+                match cast(v)? {
+                    CustomFieldValue::Int(i) => Some(i),
+                    _ => None,
+                }
+            })?)
+        }
+        CustomFieldType::Float => FilterCustomFieldValue::Float(try_val_comparator_to_order(
+            comparator,
+            |v| match cast(v)? {
+                CustomFieldValue::Float(f) => Some(f),
+                _ => None,
+            },
+        )?),
+        CustomFieldType::Text => FilterCustomFieldValue::Text(try_val_comparator_to_order(
+            comparator,
+            |v| match cast(v)? {
+                CustomFieldValue::Text(t) => Some(t),
+                _ => None,
+            },
+        )?),
+        CustomFieldType::Date => FilterCustomFieldValue::Date(try_val_comparator_to_order(
+            comparator,
+            |v| match cast(v)? {
+                CustomFieldValue::Date(d) => Some(d),
+                _ => None,
+            },
+        )?),
+        CustomFieldType::Bool => match comparator {
+            ValueComparator::Equal(v) => match cast(v)? {
+                CustomFieldValue::Bool(b) => FilterCustomFieldValue::Bool(b),
+                _ => return None,
+            },
+            _ => return None,
+        },
+    };
+
+    Some(FilterProperty::CustomField(field.id(), value))
+}
+
 fn file_status_to_number(status: ApiFileStatus) -> i64 {
     match status {
         ApiFileStatus::Imported => FileStatus::Imported as i64,
         ApiFileStatus::Archived => FileStatus::Archived as i64,
         ApiFileStatus::Deleted => FileStatus::Deleted as i64,
+        ApiFileStatus::Quarantined => FileStatus::Quarantined as i64,
     }
 }
 
@@ -185,3 +446,21 @@ fn val_comparator_to_order<T1, T2, F: Fn(T1) -> T2>(
         }
     }
 }
+
+/// Converts the comparator like [val_comparator_to_order] but returns `None`
+/// if any of the values can't be converted
+fn try_val_comparator_to_order<T1, T2, F: Fn(T1) -> Option<T2>>(
+    comp: ValueComparator<T1>,
+    conv_fn: F,
+) -> Option<OrderingComparator<T2>> {
+    let comparator = match comp {
+        ValueComparator::Less(v) => OrderingComparator::Less(conv_fn(v)?),
+        ValueComparator::Equal(v) => OrderingComparator::Equal(conv_fn(v)?),
+        ValueComparator::Greater(v) => OrderingComparator::Greater(conv_fn(v)?),
+        ValueComparator::Between((v1, v2)) => {
+            OrderingComparator::Between((conv_fn(v1)?, conv_fn(v2)?))
+        }
+    };
+
+    Some(comparator)
+}
@@ -9,9 +9,10 @@ use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use mediarepo_core::error::RepoResult;
 use mediarepo_core::mediarepo_api::types::filtering::{SortDirection, SortKey};
 use mediarepo_database::queries::tags::get_content_descriptors_with_tag_count;
+use mediarepo_logic::dao::file::find::{FileOrdering, FileSortColumn};
 use mediarepo_logic::dao::repo::Repo;
 use mediarepo_logic::dao::DaoProvider;
-use mediarepo_logic::dto::{FileDto, FileMetadataDto};
+use mediarepo_logic::dto::{CustomFieldValue, FileDto, FileMetadataDto, MediaMetadataDto};
 
 pub struct FileSortContext {
     name: Option<String>,
@@ -22,6 +23,9 @@ pub struct FileSortContext {
     import_time: NaiveDateTime,
     create_time: NaiveDateTime,
     change_time: NaiveDateTime,
+    media: Option<MediaMetadataDto>,
+    rating: Option<u32>,
+    custom_fields: HashMap<String, CustomFieldValue>,
 }
 
 #[tracing::instrument(level = "debug", skip(repo, files))]
@@ -43,6 +47,42 @@ pub async fn sort_files_by_properties(
     Ok(())
 }
 
+/// Maps the sort keys to columns the database can sort by
+pub fn sort_keys_to_ordering(sort_expression: &[SortKey]) -> Vec<FileOrdering> {
+    sort_expression
+        .iter()
+        .map(|key| {
+            let (column, direction) = match key {
+                SortKey::Namespace(namespace) => (
+                    FileSortColumn::Namespace(namespace.name.clone()),
+                    &namespace.direction,
+                ),
+                SortKey::FileName(direction) => (FileSortColumn::Name, direction),
+                SortKey::FileSize(direction) => (FileSortColumn::Size, direction),
+                SortKey::FileImportedTime(direction) => (FileSortColumn::ImportedTime, direction),
+                SortKey::FileCreatedTime(direction) => (FileSortColumn::CreatedTime, direction),
+                SortKey::FileChangeTime(direction) => (FileSortColumn::ChangedTime, direction),
+                SortKey::FileType(direction) => (FileSortColumn::MimeType, direction),
+                SortKey::NumTags(direction) => (FileSortColumn::TagCount, direction),
+                SortKey::Width(direction) => (FileSortColumn::Width, direction),
+                SortKey::Height(direction) => (FileSortColumn::Height, direction),
+                SortKey::Duration(direction) => (FileSortColumn::Duration, direction),
+                SortKey::Bitrate(direction) => (FileSortColumn::Bitrate, direction),
+                SortKey::Rating(direction) => (FileSortColumn::Rating, direction),
+                SortKey::CustomField(field) => (
+                    FileSortColumn::CustomField(field.name.clone()),
+                    &field.direction,
+                ),
+            };
+
+            match direction {
+                SortDirection::Ascending => FileOrdering::Ascending(column),
+                SortDirection::Descending => FileOrdering::Descending(column),
+            }
+        })
+        .collect()
+}
+
 async fn build_sort_context(
     repo: &Repo,
     files: &Vec<FileDto>,
@@ -54,7 +94,34 @@ async fn build_sort_context(
         .tag()
         .cdids_with_namespaced_tags(cd_ids.clone())
         .await?;
-    let mut cid_tag_counts = get_content_descriptors_with_tag_count(repo.db(), cd_ids).await?;
+    let mut cid_tag_counts =
+        get_content_descriptors_with_tag_count(repo.db(), cd_ids.clone()).await?;
+    let mut cid_media: HashMap<i64, MediaMetadataDto> = repo
+        .file()
+        .all_media_metadata(cd_ids)
+        .await?
+        .into_iter()
+        .map(|m| (m.cd_id(), m))
+        .collect();
+    let file_ratings: HashMap<i64, u32> = repo
+        .file()
+        .all_ratings(file_ids.clone())
+        .await?
+        .into_iter()
+        .filter_map(|r| Some((r.file_id(), r.rating()?)))
+        .collect();
+    let mut file_custom_fields: HashMap<i64, HashMap<String, CustomFieldValue>> = HashMap::new();
+
+    for value in repo
+        .custom_field()
+        .values_for_files(file_ids.clone())
+        .await?
+    {
+        file_custom_fields
+            .entry(value.file_id())
+            .or_default()
+            .insert(value.field().name().to_owned(), value.into_value());
+    }
 
     let files_metadata = repo.file().all_metadata(file_ids).await?;
 
@@ -74,8 +141,13 @@ async fn build_sort_context(
                     .unwrap_or_else(|| HashMap::with_capacity(0)),
                 tag_count: cid_tag_counts.remove(&file.cd_id()).unwrap_or(0),
                 import_time: metadata.import_time().to_owned(),
-                create_time: metadata.import_time().to_owned(),
+                create_time: metadata.creation_time().to_owned(),
                 change_time: metadata.change_time().to_owned(),
+                media: cid_media.remove(&file.cd_id()),
+                rating: file_ratings.get(&file.id()).copied(),
+                custom_fields: file_custom_fields
+                    .remove(&file.id())
+                    .unwrap_or_else(|| HashMap::with_capacity(0)),
             };
             contexts.insert(file.id(), context);
         }
@@ -136,6 +208,32 @@ fn compare_files(
                 cmp_u32.compare(&ctx_a.tag_count, &ctx_b.tag_count),
                 direction,
             ),
+            SortKey::Width(direction) => adjust_for_dir(
+                compare_media(ctx_a, ctx_b, MediaMetadataDto::width),
+                direction,
+            ),
+            SortKey::Height(direction) => adjust_for_dir(
+                compare_media(ctx_a, ctx_b, MediaMetadataDto::height),
+                direction,
+            ),
+            SortKey::Duration(direction) => adjust_for_dir(
+                compare_media(ctx_a, ctx_b, MediaMetadataDto::duration),
+                direction,
+            ),
+            SortKey::Bitrate(direction) => adjust_for_dir(
+                compare_media(ctx_a, ctx_b, MediaMetadataDto::bitrate),
+                direction,
+            ),
+            SortKey::Rating(direction) => {
+                adjust_for_dir(compare_opts(&ctx_a.rating, &ctx_b.rating), direction)
+            }
+            SortKey::CustomField(field) => adjust_for_dir(
+                compare_custom_fields(
+                    ctx_a.custom_fields.get(&field.name),
+                    ctx_b.custom_fields.get(&field.name),
+                ),
+                &field.direction,
+            ),
         };
         if !ordering.is_eq() {
             return ordering;
@@ -145,6 +243,18 @@ fn compare_files(
     Ordering::Equal
 }
 
+/// Compares a media metadata value where files without the value are sorted first
+fn compare_media<T: Ord, F: Fn(&MediaMetadataDto) -> Option<T>>(
+    ctx_a: &FileSortContext,
+    ctx_b: &FileSortContext,
+    value: F,
+) -> Ordering {
+    compare_opts(
+        &ctx_a.media.as_ref().and_then(&value),
+        &ctx_b.media.as_ref().and_then(&value),
+    )
+}
+
 fn compare_opts<T: Ord + Sized>(opt_a: &Option<T>, opt_b: &Option<T>) -> Ordering {
     let cmp = compare::natural();
     if let (Some(a), Some(b)) = (opt_a, opt_b) {
@@ -158,6 +268,19 @@ fn compare_opts<T: Ord + Sized>(opt_a: &Option<T>, opt_b: &Option<T>) -> Orderin
     }
 }
 
+/// Compares custom field values where files without the value are sorted first
+fn compare_custom_fields(
+    value_a: Option<&CustomFieldValue>,
+    value_b: Option<&CustomFieldValue>,
+) -> Ordering {
+    match (value_a, value_b) {
+        (Some(a), Some(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => Ordering::Equal,
+    }
+}
+
 fn compare_f32(a: f32, b: f32) -> Ordering {
     if a > b {
         Ordering::Greater
@@ -1,15 +1,23 @@
+use crate::from_model::FromModel;
 use crate::TypeMap;
 use mediarepo_core::bromine::prelude::*;
 use mediarepo_core::error::RepoResult;
-use mediarepo_core::mediarepo_api::types::jobs::{JobType, RunJobRequest};
+use mediarepo_core::mediarepo_api::types::files::FileBasicDataResponse;
+use mediarepo_core::mediarepo_api::types::jobs::{
+    GarbageCollectionResponse, IntegrityFindingResponse, JobType, OrphanResponse, RunJobRequest,
+};
 use mediarepo_core::type_keys::{RepoPathKey, SettingsKey, SizeMetadataKey};
+use mediarepo_logic::dao::DaoProvider;
 use mediarepo_worker::handle::JobState;
 use mediarepo_worker::job_dispatcher::JobDispatcher;
 use mediarepo_worker::jobs::{
-    CalculateSizesJob, CheckIntegrityJob, GenerateMissingThumbsJob, Job, MigrateCDsJob, VacuumJob,
+    CalculateSizesJob, CheckIntegrityJob, CompactThumbnailsJob, ExtractEmbeddedTagsJob,
+    FindDuplicatesJob, GarbageCollectJob, GenerateMediaMetadataJob, GenerateMissingThumbsJob,
+    GeneratePerceptualHashesJob, Job, MigrateCDsJob, MigrateThumbnailsJob, ReencodeThumbnailsJob,
+    VacuumJob, VerifyFilesJob,
 };
 
-use crate::utils::get_job_dispatcher_from_context;
+use crate::utils::{get_job_dispatcher_from_context, get_repo_from_context};
 
 pub struct JobsNamespace;
 
@@ -21,7 +29,11 @@ impl NamespaceProvider for JobsNamespace {
     fn register(handler: &mut EventHandler) {
         events!(handler,
             "run_job" => Self::run_job,
-            "is_job_running" => Self::is_job_running
+            "is_job_running" => Self::is_job_running,
+            "get_integrity_findings" => Self::get_integrity_findings,
+            "quarantine_integrity_findings" => Self::quarantine_integrity_findings,
+            "dismiss_integrity_findings" => Self::dismiss_integrity_findings,
+            "get_garbage_collection_report" => Self::get_garbage_collection_report
         );
     }
 }
@@ -45,8 +57,37 @@ impl JobsNamespace {
             JobType::CheckIntegrity => {
                 dispatch_job(&dispatcher, CheckIntegrityJob::default(), run_request.sync).await?
             }
-            JobType::Vacuum => {
-                dispatch_job(&dispatcher, VacuumJob::default(), run_request.sync).await?
+            JobType::Vacuum => dispatch_job(&dispatcher, VacuumJob, run_request.sync).await?,
+            JobType::VerifyFiles => {
+                dispatch_job(&dispatcher, VerifyFilesJob::default(), run_request.sync).await?
+            }
+            JobType::GarbageCollect { dry_run } => {
+                dispatch_job(
+                    &dispatcher,
+                    GarbageCollectJob::new(dry_run),
+                    run_request.sync,
+                )
+                .await?
+            }
+            JobType::GeneratePerceptualHashes => {
+                dispatch_job(
+                    &dispatcher,
+                    GeneratePerceptualHashesJob::default(),
+                    run_request.sync,
+                )
+                .await?
+            }
+            JobType::FindDuplicates => {
+                let max_distance = {
+                    let data = ctx.data.read().await;
+                    data.get::<SettingsKey>().unwrap().duplicates.max_distance
+                };
+                dispatch_job(
+                    &dispatcher,
+                    FindDuplicatesJob::new(max_distance),
+                    run_request.sync,
+                )
+                .await?
             }
             JobType::GenerateThumbnails => {
                 dispatch_job(
@@ -56,6 +97,54 @@ impl JobsNamespace {
                 )
                 .await?
             }
+            JobType::ReencodeThumbnails => {
+                dispatch_job(
+                    &dispatcher,
+                    ReencodeThumbnailsJob::default(),
+                    run_request.sync,
+                )
+                .await?
+            }
+            JobType::MigrateThumbnails => {
+                dispatch_job(
+                    &dispatcher,
+                    MigrateThumbnailsJob::default(),
+                    run_request.sync,
+                )
+                .await?
+            }
+            JobType::CompactThumbnails => {
+                dispatch_job(
+                    &dispatcher,
+                    CompactThumbnailsJob::forced(),
+                    run_request.sync,
+                )
+                .await?
+            }
+            JobType::GenerateMediaMetadata => {
+                dispatch_job(
+                    &dispatcher,
+                    GenerateMediaMetadataJob::default(),
+                    run_request.sync,
+                )
+                .await?
+            }
+            JobType::ExtractEmbeddedTags => {
+                let mappings = {
+                    let data = ctx.data.read().await;
+                    data.get::<SettingsKey>()
+                        .unwrap()
+                        .tag_extraction
+                        .mappings
+                        .clone()
+                };
+                dispatch_job(
+                    &dispatcher,
+                    ExtractEmbeddedTagsJob::new(mappings),
+                    run_request.sync,
+                )
+                .await?
+            }
         }
 
         Ok(Response::empty())
@@ -76,10 +165,98 @@ impl JobsNamespace {
             }
             JobType::CheckIntegrity => is_job_running::<CheckIntegrityJob>(&dispatcher).await,
             JobType::Vacuum => is_job_running::<VacuumJob>(&dispatcher).await,
+            JobType::VerifyFiles => is_job_running::<VerifyFilesJob>(&dispatcher).await,
+            JobType::GarbageCollect { .. } => {
+                is_job_running::<GarbageCollectJob>(&dispatcher).await
+            }
+            JobType::GeneratePerceptualHashes => {
+                is_job_running::<GeneratePerceptualHashesJob>(&dispatcher).await
+            }
+            JobType::FindDuplicates => is_job_running::<FindDuplicatesJob>(&dispatcher).await,
+            JobType::ReencodeThumbnails => {
+                is_job_running::<ReencodeThumbnailsJob>(&dispatcher).await
+            }
+            JobType::MigrateThumbnails => is_job_running::<MigrateThumbnailsJob>(&dispatcher).await,
+            JobType::CompactThumbnails => is_job_running::<CompactThumbnailsJob>(&dispatcher).await,
+            JobType::GenerateMediaMetadata => {
+                is_job_running::<GenerateMediaMetadataJob>(&dispatcher).await
+            }
+            JobType::ExtractEmbeddedTags => {
+                is_job_running::<ExtractEmbeddedTagsJob>(&dispatcher).await
+            }
         };
 
         Response::payload(ctx, running)
     }
+
+    /// Returns the findings of the last file verification
+    #[tracing::instrument(skip_all)]
+    pub async fn get_integrity_findings(ctx: &Context, _event: Event) -> IPCResult<Response> {
+        let repo = get_repo_from_context(ctx).await;
+        let findings: Vec<IntegrityFindingResponse> = repo
+            .integrity()
+            .all()
+            .await?
+            .into_iter()
+            .map(IntegrityFindingResponse::from_model)
+            .collect();
+
+        Response::payload(ctx, findings)
+    }
+
+    /// Quarantines the files affected by the given findings
+    #[tracing::instrument(skip_all)]
+    pub async fn quarantine_integrity_findings(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let ids = event.payload::<Vec<i64>>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let files: Vec<FileBasicDataResponse> = repo
+            .integrity()
+            .quarantine(ids)
+            .await?
+            .into_iter()
+            .map(FileBasicDataResponse::from_model)
+            .collect();
+
+        Response::payload(ctx, files)
+    }
+
+    /// Removes the given findings
+    #[tracing::instrument(skip_all)]
+    pub async fn dismiss_integrity_findings(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let ids = event.payload::<Vec<i64>>()?;
+        let repo = get_repo_from_context(ctx).await;
+        repo.integrity().delete(ids).await?;
+
+        Ok(Response::empty())
+    }
+
+    /// Returns the orphans found by the last garbage collection
+    #[tracing::instrument(skip_all)]
+    pub async fn get_garbage_collection_report(
+        ctx: &Context,
+        _event: Event,
+    ) -> IPCResult<Response> {
+        let dispatcher = get_job_dispatcher_from_context(ctx).await;
+        let report = if let Some(handle) = dispatcher.get_handle::<GarbageCollectJob>().await {
+            let state = handle.status().read().await;
+            let orphans: Vec<OrphanResponse> = state
+                .orphans
+                .iter()
+                .cloned()
+                .map(OrphanResponse::from_model)
+                .collect();
+
+            Some(GarbageCollectionResponse {
+                dry_run: state.dry_run,
+                total_size: orphans.iter().map(|o| o.size).sum(),
+                orphans,
+            })
+        } else {
+            None
+        };
+
+        Response::payload(ctx, report)
+    }
 }
 
 async fn dispatch_job<J: 'static + Job>(
@@ -1,10 +1,16 @@
 use mediarepo_core::bromine::prelude::AsyncStreamProtocolListener;
 use mediarepo_core::bromine::{namespace, namespace::Namespace, IPCBuilder};
 
+pub mod collections;
+pub mod custom_fields;
+pub mod duplicates;
 pub mod files;
 pub mod jobs;
 pub mod presets;
+pub mod relations;
 pub mod repo;
+pub mod searches;
+pub mod sources;
 pub mod tags;
 
 pub fn build_namespaces<L: AsyncStreamProtocolListener>(builder: IPCBuilder<L>) -> IPCBuilder<L> {
@@ -14,4 +20,10 @@ pub fn build_namespaces<L: AsyncStreamProtocolListener>(builder: IPCBuilder<L>)
         .add_namespace(namespace!(repo::RepoNamespace))
         .add_namespace(namespace!(jobs::JobsNamespace))
         .add_namespace(namespace!(presets::PresetsNamespace))
+        .add_namespace(namespace!(duplicates::DuplicatesNamespace))
+        .add_namespace(namespace!(searches::SearchesNamespace))
+        .add_namespace(namespace!(sources::SourcesNamespace))
+        .add_namespace(namespace!(custom_fields::CustomFieldsNamespace))
+        .add_namespace(namespace!(relations::RelationsNamespace))
+        .add_namespace(namespace!(collections::CollectionsNamespace))
 }
@@ -69,6 +69,11 @@ fn sort_key_to_add_dto(key: SortKey) -> AddSortKeyDto {
             key_type: KeyType::Namespace,
             value: Some(namespace.name),
         },
+        SortKey::CustomField(field) => AddSortKeyDto {
+            ascending: field.direction == SortDirection::Ascending,
+            key_type: KeyType::CustomField,
+            value: Some(field.name),
+        },
         SortKey::FileName(dir) => AddSortKeyDto {
             ascending: dir == SortDirection::Ascending,
             key_type: KeyType::FileName,
@@ -104,5 +109,30 @@ fn sort_key_to_add_dto(key: SortKey) -> AddSortKeyDto {
             key_type: KeyType::NumTags,
             value: None,
         },
+        SortKey::Width(dir) => AddSortKeyDto {
+            ascending: dir == SortDirection::Ascending,
+            key_type: KeyType::Width,
+            value: None,
+        },
+        SortKey::Height(dir) => AddSortKeyDto {
+            ascending: dir == SortDirection::Ascending,
+            key_type: KeyType::Height,
+            value: None,
+        },
+        SortKey::Duration(dir) => AddSortKeyDto {
+            ascending: dir == SortDirection::Ascending,
+            key_type: KeyType::Duration,
+            value: None,
+        },
+        SortKey::Bitrate(dir) => AddSortKeyDto {
+            ascending: dir == SortDirection::Ascending,
+            key_type: KeyType::Bitrate,
+            value: None,
+        },
+        SortKey::Rating(dir) => AddSortKeyDto {
+            ascending: dir == SortDirection::Ascending,
+            key_type: KeyType::Rating,
+            value: None,
+        },
     }
 }
@@ -0,0 +1,101 @@
+use mediarepo_core::bromine::prelude::*;
+use mediarepo_core::error::RepoResult;
+use mediarepo_core::mediarepo_api::types::identifier::FileIdentifier;
+use mediarepo_core::mediarepo_api::types::relations::{
+    ChangeFileRelationRequest, FileRelationResponse, FileRelationType,
+};
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use mediarepo_logic::dto::FileRelationType as FileRelationTypeModel;
+
+use crate::from_model::FromModel;
+use crate::utils::{file_by_identifier, get_repo_from_context};
+
+pub struct RelationsNamespace;
+
+impl NamespaceProvider for RelationsNamespace {
+    fn name() -> &'static str {
+        "relations"
+    }
+
+    fn register(handler: &mut EventHandler) {
+        events!(handler,
+            "relations_for_file" => Self::relations_for_file,
+            "add_file_relation" => Self::add_file_relation,
+            "remove_file_relation" => Self::remove_file_relation
+        );
+    }
+}
+
+impl RelationsNamespace {
+    /// Returns all relations of a file
+    #[tracing::instrument(skip_all)]
+    async fn relations_for_file(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let id = event.payload::<FileIdentifier>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file = file_by_identifier(id, &repo).await?;
+        let responses = relation_responses(&repo, file.id()).await?;
+
+        ctx.response(responses)
+    }
+
+    /// Adds a relation between two files and returns all relations of the file
+    #[tracing::instrument(skip_all)]
+    async fn add_file_relation(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<ChangeFileRelationRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file = file_by_identifier(request.file_id, &repo).await?;
+        let related_file = file_by_identifier(request.related_file_id, &repo).await?;
+        repo.file_relation()
+            .add(
+                file.id(),
+                related_file.id(),
+                relation_type_to_model(request.relation_type),
+            )
+            .await?;
+        let responses = relation_responses(&repo, file.id()).await?;
+
+        ctx.response(responses)
+    }
+
+    /// Removes a relation between two files and returns the remaining relations of the file
+    #[tracing::instrument(skip_all)]
+    async fn remove_file_relation(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<ChangeFileRelationRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file = file_by_identifier(request.file_id, &repo).await?;
+        let related_file = file_by_identifier(request.related_file_id, &repo).await?;
+        repo.file_relation()
+            .remove(
+                file.id(),
+                related_file.id(),
+                relation_type_to_model(request.relation_type),
+            )
+            .await?;
+        let responses = relation_responses(&repo, file.id()).await?;
+
+        ctx.response(responses)
+    }
+}
+
+async fn relation_responses(repo: &Repo, file_id: i64) -> RepoResult<Vec<FileRelationResponse>> {
+    let relations = repo
+        .file_relation()
+        .for_file(file_id)
+        .await?
+        .into_iter()
+        .map(FileRelationResponse::from_model)
+        .collect();
+
+    Ok(relations)
+}
+
+pub(crate) fn relation_type_to_model(relation_type: FileRelationType) -> FileRelationTypeModel {
+    match relation_type {
+        FileRelationType::Alternate => FileRelationTypeModel::Alternate,
+        FileRelationType::Parent => FileRelationTypeModel::Parent,
+        FileRelationType::Child => FileRelationTypeModel::Child,
+        FileRelationType::DerivedFrom => FileRelationTypeModel::DerivedFrom,
+        FileRelationType::DuplicateOf => FileRelationTypeModel::DuplicateOf,
+    }
+}
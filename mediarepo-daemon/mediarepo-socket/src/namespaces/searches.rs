@@ -0,0 +1,142 @@
+use crate::from_model::FromModel;
+use crate::namespaces::files::searching::find_files_page_for_filters;
+use crate::utils::get_repo_from_context;
+use mediarepo_core::bincode;
+use mediarepo_core::bromine::prelude::*;
+use mediarepo_core::error::{RepoError, RepoResult};
+use mediarepo_core::mediarepo_api::types::files::{FileBasicDataResponse, FindFilesPageResponse};
+use mediarepo_core::mediarepo_api::types::filtering::SortingPreset;
+use mediarepo_core::mediarepo_api::types::searches::{
+    AddSavedSearchRequest, RunSavedSearchRequest, SavedSearch, UpdateSavedSearchRequest,
+};
+use mediarepo_logic::dao::DaoProvider;
+use mediarepo_logic::dto::{AddSavedSearchDto, SavedSearchDto, UpdateSavedSearchDto};
+
+pub struct SearchesNamespace;
+
+impl NamespaceProvider for SearchesNamespace {
+    fn name() -> &'static str {
+        "searches"
+    }
+
+    fn register(handler: &mut EventHandler) {
+        events!(handler,
+            "all_saved_searches" => Self::all_saved_searches,
+            "add_saved_search" => Self::add_saved_search,
+            "update_saved_search" => Self::update_saved_search,
+            "delete_saved_search" => Self::delete_saved_search,
+            "run_saved_search" => Self::run_saved_search
+        );
+    }
+}
+
+impl SearchesNamespace {
+    /// Returns all saved searches
+    #[tracing::instrument(skip_all)]
+    pub async fn all_saved_searches(ctx: &Context, _: Event) -> IPCResult<Response> {
+        let repo = get_repo_from_context(ctx).await;
+        let searches = repo
+            .saved_search()
+            .all()
+            .await?
+            .into_iter()
+            .map(saved_search_from_dto)
+            .collect::<RepoResult<Vec<SavedSearch>>>()?;
+
+        ctx.response(searches)
+    }
+
+    /// Saves a new search
+    #[tracing::instrument(skip_all)]
+    pub async fn add_saved_search(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<AddSavedSearchRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let search = repo
+            .saved_search()
+            .add(AddSavedSearchDto {
+                name: request.name,
+                query: bincode::serialize(&request.query).map_err(RepoError::from)?,
+                sorting_preset_id: request.sorting_preset_id,
+            })
+            .await?;
+
+        ctx.response(saved_search_from_dto(search)?)
+    }
+
+    /// Updates the fields of a saved search that are set in the request
+    #[tracing::instrument(skip_all)]
+    pub async fn update_saved_search(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<UpdateSavedSearchRequest>()?;
+        let query = request
+            .query
+            .map(|q| bincode::serialize(&q))
+            .transpose()
+            .map_err(RepoError::from)?;
+        let repo = get_repo_from_context(ctx).await;
+        let search = repo
+            .saved_search()
+            .update(UpdateSavedSearchDto {
+                id: request.id,
+                name: request.name,
+                query,
+                sorting_preset_id: request.sorting_preset_id,
+            })
+            .await?;
+
+        ctx.response(saved_search_from_dto(search)?)
+    }
+
+    /// Deletes a saved search
+    #[tracing::instrument(skip_all)]
+    pub async fn delete_saved_search(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let id = event.payload::<i64>()?;
+        let repo = get_repo_from_context(ctx).await;
+        repo.saved_search().delete(id).await?;
+
+        Ok(Response::empty())
+    }
+
+    /// Returns a page of the files matching a saved search with the total number of matches.
+    /// The keys of a linked sorting preset are used instead of the sort expression of the search
+    #[tracing::instrument(skip_all)]
+    pub async fn run_saved_search(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let RunSavedSearchRequest { id, offset, limit } =
+            event.payload::<RunSavedSearchRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let search = repo
+            .saved_search()
+            .by_id(id)
+            .await?
+            .ok_or_else(|| RepoError::from("saved search not found"))?;
+        let mut query = saved_search_from_dto(search)?;
+
+        if let Some(preset_id) = query.sorting_preset_id {
+            if let Some(preset) = repo.sorting_preset().by_id(preset_id).await? {
+                query.query.sort_expression = SortingPreset::from_model(preset).keys;
+            }
+        }
+        let (files, total) = find_files_page_for_filters(
+            &repo,
+            query.query.filters,
+            query.query.sort_expression,
+            offset,
+            limit,
+        )
+        .await?;
+        let files = files
+            .into_iter()
+            .map(FileBasicDataResponse::from_model)
+            .collect();
+
+        ctx.response(FindFilesPageResponse { files, total })
+    }
+}
+
+fn saved_search_from_dto(dto: SavedSearchDto) -> RepoResult<SavedSearch> {
+    Ok(SavedSearch {
+        id: dto.id(),
+        name: dto.name().to_owned(),
+        query: bincode::deserialize(dto.query())?,
+        sorting_preset_id: dto.sorting_preset_id(),
+    })
+}
@@ -0,0 +1,76 @@
+use mediarepo_core::bromine::prelude::*;
+use mediarepo_core::error::RepoResult;
+use mediarepo_core::mediarepo_api::types::identifier::FileIdentifier;
+use mediarepo_core::mediarepo_api::types::sources::{ChangeFileSourcesRequest, SourceResponse};
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+
+use crate::from_model::FromModel;
+use crate::utils::{file_by_identifier, get_repo_from_context};
+
+pub struct SourcesNamespace;
+
+impl NamespaceProvider for SourcesNamespace {
+    fn name() -> &'static str {
+        "sources"
+    }
+
+    fn register(handler: &mut EventHandler) {
+        events!(handler,
+            "sources_for_file" => Self::sources_for_file,
+            "add_sources_to_file" => Self::add_sources_to_file,
+            "remove_sources_from_file" => Self::remove_sources_from_file
+        );
+    }
+}
+
+impl SourcesNamespace {
+    /// Returns the source urls of a file
+    #[tracing::instrument(skip_all)]
+    async fn sources_for_file(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let id = event.payload::<FileIdentifier>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file = file_by_identifier(id, &repo).await?;
+        let responses = source_responses(&repo, file.cd_id()).await?;
+
+        ctx.response(responses)
+    }
+
+    /// Adds source urls to a file and returns all sources of the file
+    #[tracing::instrument(skip_all)]
+    async fn add_sources_to_file(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<ChangeFileSourcesRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file = file_by_identifier(request.file_id, &repo).await?;
+        repo.source().add_to_cd(file.cd_id(), request.urls).await?;
+        let responses = source_responses(&repo, file.cd_id()).await?;
+
+        ctx.response(responses)
+    }
+
+    /// Removes source urls from a file and returns the remaining sources of the file
+    #[tracing::instrument(skip_all)]
+    async fn remove_sources_from_file(ctx: &Context, event: Event) -> IPCResult<Response> {
+        let request = event.payload::<ChangeFileSourcesRequest>()?;
+        let repo = get_repo_from_context(ctx).await;
+        let file = file_by_identifier(request.file_id, &repo).await?;
+        repo.source()
+            .remove_from_cd(file.cd_id(), request.urls)
+            .await?;
+        let responses = source_responses(&repo, file.cd_id()).await?;
+
+        ctx.response(responses)
+    }
+}
+
+async fn source_responses(repo: &Repo, cd_id: i64) -> RepoResult<Vec<SourceResponse>> {
+    let sources = repo
+        .source()
+        .for_cd(cd_id)
+        .await?
+        .into_iter()
+        .map(SourceResponse::from_model)
+        .collect();
+
+    Ok(sources)
+}
@@ -0,0 +1,51 @@
+use crate::jobs::{EmptyStatus, Job};
+use async_trait::async_trait;
+use mediarepo_core::error::RepoResult;
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// The part of the thumbnail storage that needs to be wasted
+/// before the storage is compacted without being forced to
+const COMPACTION_THRESHOLD: f64 = 0.1;
+
+/// Frees the space of replaced and deleted thumbnails in packed thumbnail storage.
+/// Returns the number of freed bytes
+#[derive(Clone, Default)]
+pub struct CompactThumbnailsJob {
+    force: bool,
+}
+
+impl CompactThumbnailsJob {
+    /// Creates a job that compacts the storage even if only little space can be freed
+    pub fn forced() -> Self {
+        Self { force: true }
+    }
+}
+
+#[async_trait]
+impl Job for CompactThumbnailsJob {
+    type JobStatus = ();
+    type Result = u64;
+
+    fn status(&self) -> Arc<RwLock<Self::JobStatus>> {
+        EmptyStatus::default()
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn run(&self, repo: Arc<Repo>) -> RepoResult<u64> {
+        let job_dao = repo.job();
+        let (size, wasted_size) = job_dao.thumbnail_storage_usage().await?;
+
+        if wasted_size == 0
+            || (!self.force && (wasted_size as f64) < size as f64 * COMPACTION_THRESHOLD)
+        {
+            return Ok(0);
+        }
+        let freed = job_dao.compact_thumbnails().await?;
+        tracing::info!("compacting the thumbnail storage freed {} bytes", freed);
+
+        Ok(freed)
+    }
+}
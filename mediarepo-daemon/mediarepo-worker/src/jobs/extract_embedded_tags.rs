@@ -0,0 +1,68 @@
+use crate::jobs::Job;
+use crate::status_utils::SimpleProgress;
+use async_trait::async_trait;
+use mediarepo_core::error::RepoResult;
+use mediarepo_core::media_metadata::supports_embedded_fields;
+use mediarepo_core::settings::TagMapping;
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Tags all files in the repo with the mapped fields of their embedded metadata
+#[derive(Clone)]
+pub struct ExtractEmbeddedTagsJob {
+    mappings: Vec<TagMapping>,
+    progress: Arc<RwLock<SimpleProgress>>,
+}
+
+impl ExtractEmbeddedTagsJob {
+    pub fn new(mappings: Vec<TagMapping>) -> Self {
+        Self {
+            mappings,
+            progress: Default::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for ExtractEmbeddedTagsJob {
+    type JobStatus = SimpleProgress;
+    type Result = ();
+
+    fn status(&self) -> Arc<RwLock<Self::JobStatus>> {
+        self.progress.clone()
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn run(&self, repo: Arc<Repo>) -> RepoResult<()> {
+        let tag_dao = repo.tag();
+        let files: Vec<_> = repo
+            .file()
+            .all()
+            .await?
+            .into_iter()
+            .filter(|f| supports_embedded_fields(f.mime_type(), &self.mappings))
+            .collect();
+        {
+            let mut progress = self.progress.write().await;
+            progress.set_total(files.len() as u64);
+        }
+        let mut tag_count = 0;
+
+        for file in files {
+            match tag_dao.add_embedded_tags(&file, &self.mappings).await {
+                Ok(count) => tag_count += count,
+                Err(e) => tracing::warn!(
+                    "failed to extract embedded tags for {}: {}",
+                    file.encoded_cd(),
+                    e
+                ),
+            }
+            self.progress.write().await.tick();
+        }
+        tracing::info!("tagged files with {} embedded metadata tags", tag_count);
+
+        Ok(())
+    }
+}
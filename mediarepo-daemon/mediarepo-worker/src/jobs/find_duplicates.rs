@@ -0,0 +1,82 @@
+use crate::jobs::Job;
+use crate::status_utils::SimpleProgress;
+use async_trait::async_trait;
+use mediarepo_core::error::RepoResult;
+use mediarepo_core::perceptual_hash::PerceptualHashTree;
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use mediarepo_logic::dto::AddDuplicateCandidateDto;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+const CANDIDATE_BATCH_SIZE: usize = 500;
+const PROGRESS_INTERVAL: u64 = 1000;
+
+/// Groups images with similar perceptual hashes into duplicate candidates
+#[derive(Clone)]
+pub struct FindDuplicatesJob {
+    max_distance: u32,
+    progress: Arc<RwLock<SimpleProgress>>,
+}
+
+impl FindDuplicatesJob {
+    pub fn new(max_distance: u32) -> Self {
+        Self {
+            max_distance,
+            progress: Default::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for FindDuplicatesJob {
+    type JobStatus = SimpleProgress;
+    type Result = usize;
+
+    fn status(&self) -> Arc<RwLock<Self::JobStatus>> {
+        self.progress.clone()
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn run(&self, repo: Arc<Repo>) -> RepoResult<Self::Result> {
+        let duplicate_dao = repo.duplicate();
+        let hashes = repo.job().perceptual_hashes().await?;
+        self.progress.write().await.set_total(hashes.len() as u64);
+        let mut tree = PerceptualHashTree::default();
+        let mut candidates = Vec::new();
+        let mut new_candidates = 0;
+
+        // every hash is only compared to the ones inserted before it so each pair is found once
+        for (checked, (cd_id, hash)) in (1u64..).zip(hashes) {
+            candidates.extend(tree.find(hash, self.max_distance).into_iter().map(
+                |(other_cd_id, distance)| AddDuplicateCandidateDto {
+                    cd_id_a: *other_cd_id,
+                    cd_id_b: cd_id,
+                    distance,
+                },
+            ));
+            tree.insert(hash, cd_id);
+
+            if candidates.len() >= CANDIDATE_BATCH_SIZE {
+                new_candidates += duplicate_dao
+                    .add_candidates(std::mem::take(&mut candidates))
+                    .await?;
+            }
+            if checked % PROGRESS_INTERVAL == 0 {
+                self.progress.write().await.set_current(checked);
+                tokio::task::yield_now().await;
+            }
+        }
+        if !candidates.is_empty() {
+            new_candidates += duplicate_dao.add_candidates(candidates).await?;
+        }
+        tracing::info!("found {} new duplicate candidates", new_candidates);
+        {
+            let mut progress = self.progress.write().await;
+            let total = progress.total;
+            progress.set_current(total);
+        }
+
+        Ok(new_candidates)
+    }
+}
@@ -0,0 +1,84 @@
+use crate::jobs::Job;
+use crate::status_utils::SimpleProgress;
+use async_trait::async_trait;
+use mediarepo_core::error::RepoResult;
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use mediarepo_logic::dto::OrphanDto;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+pub struct GarbageCollectState {
+    pub progress: SimpleProgress,
+    pub dry_run: bool,
+    pub orphans: Vec<OrphanDto>,
+}
+
+/// Removes stored files and thumbnails that don't belong to any content descriptor
+/// as well as the staging files of abandoned uploads.
+/// In dry run mode the orphans are only collected without deleting them
+#[derive(Clone)]
+pub struct GarbageCollectJob {
+    dry_run: bool,
+    state: Arc<RwLock<GarbageCollectState>>,
+}
+
+impl GarbageCollectJob {
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            state: Arc::new(RwLock::new(GarbageCollectState {
+                progress: SimpleProgress::default(),
+                dry_run,
+                orphans: Vec::new(),
+            })),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for GarbageCollectJob {
+    type JobStatus = GarbageCollectState;
+    type Result = Vec<OrphanDto>;
+
+    fn status(&self) -> Arc<RwLock<Self::JobStatus>> {
+        self.state.clone()
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn run(&self, repo: Arc<Repo>) -> RepoResult<Self::Result> {
+        let job_dao = repo.job();
+        let mut orphans = job_dao.find_orphaned_files().await?;
+        orphans.append(&mut job_dao.find_orphaned_thumbnails().await?);
+        orphans.append(&mut job_dao.find_abandoned_uploads().await?);
+        {
+            let mut state = self.state.write().await;
+            state.progress.set_total(orphans.len() as u64);
+            state.orphans = orphans.clone();
+        }
+        let freed_bytes: u64 = orphans.iter().map(|o| o.size).sum();
+
+        if self.dry_run {
+            tracing::info!(
+                "found {} orphans occupying {} bytes",
+                orphans.len(),
+                freed_bytes
+            );
+        } else {
+            for orphan in &orphans {
+                job_dao.delete_orphan(orphan).await?;
+                self.state.write().await.progress.tick();
+            }
+            tracing::info!(
+                "removed {} orphans freeing {} bytes",
+                orphans.len(),
+                freed_bytes
+            );
+        }
+        let mut state = self.state.write().await;
+        let total = state.progress.total;
+        state.progress.set_current(total);
+
+        Ok(orphans)
+    }
+}
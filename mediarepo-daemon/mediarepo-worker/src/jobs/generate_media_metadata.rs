@@ -0,0 +1,47 @@
+use crate::jobs::Job;
+use crate::status_utils::SimpleProgress;
+use async_trait::async_trait;
+use mediarepo_core::error::RepoResult;
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Extracts the media metadata of files that have been imported without it
+#[derive(Clone, Default)]
+pub struct GenerateMediaMetadataJob {
+    progress: Arc<RwLock<SimpleProgress>>,
+}
+
+#[async_trait]
+impl Job for GenerateMediaMetadataJob {
+    type JobStatus = SimpleProgress;
+    type Result = ();
+
+    fn status(&self) -> Arc<RwLock<Self::JobStatus>> {
+        self.progress.clone()
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn run(&self, repo: Arc<Repo>) -> RepoResult<()> {
+        let file_dao = repo.file();
+        let files = file_dao.all_without_media_metadata().await?;
+        {
+            let mut progress = self.progress.write().await;
+            progress.set_total(files.len() as u64);
+        }
+
+        for file in files {
+            if let Err(e) = file_dao.create_media_metadata(&file).await {
+                tracing::warn!(
+                    "failed to extract media metadata for {}: {}",
+                    file.encoded_cd(),
+                    e
+                );
+            }
+            self.progress.write().await.tick();
+        }
+
+        Ok(())
+    }
+}
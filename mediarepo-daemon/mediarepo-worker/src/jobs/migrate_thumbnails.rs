@@ -0,0 +1,41 @@
+use crate::jobs::Job;
+use crate::status_utils::SimpleProgress;
+use async_trait::async_trait;
+use mediarepo_core::error::RepoResult;
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Moves thumbnails that are still stored in the directory layout into
+/// the pack files when the repo is configured to use packed thumbnail storage
+#[derive(Clone, Default)]
+pub struct MigrateThumbnailsJob {
+    progress: Arc<RwLock<SimpleProgress>>,
+}
+
+#[async_trait]
+impl Job for MigrateThumbnailsJob {
+    type JobStatus = SimpleProgress;
+    type Result = ();
+
+    fn status(&self) -> Arc<RwLock<Self::JobStatus>> {
+        self.progress.clone()
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn run(&self, repo: Arc<Repo>) -> RepoResult<()> {
+        let job_dao = repo.job();
+        let parents = job_dao.unmigrated_thumbnail_parents().await?;
+        self.progress.write().await.set_total(parents.len() as u64);
+
+        for parent in parents {
+            if let Err(e) = job_dao.migrate_thumbnails(&parent).await {
+                tracing::warn!("failed to migrate thumbnails of {}: {}", parent, e);
+            }
+            self.progress.write().await.tick();
+        }
+
+        Ok(())
+    }
+}
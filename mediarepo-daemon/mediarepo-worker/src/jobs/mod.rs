@@ -1,16 +1,36 @@
 mod calculate_sizes;
 mod check_integrity;
+mod compact_thumbnails;
+mod extract_embedded_tags;
+mod find_duplicates;
+mod garbage_collect;
+mod generate_media_metadata;
 mod generate_missing_thumbnails;
+mod generate_perceptual_hashes;
 mod migrate_content_descriptors;
+mod migrate_thumbnails;
+mod reencode_thumbnails;
+mod sync_thumbnail_presence;
 mod vacuum;
+mod verify_files;
 
 pub use calculate_sizes::*;
 pub use check_integrity::*;
+pub use compact_thumbnails::*;
+pub use extract_embedded_tags::*;
+pub use find_duplicates::*;
+pub use garbage_collect::*;
+pub use generate_media_metadata::*;
 pub use generate_missing_thumbnails::*;
+pub use generate_perceptual_hashes::*;
 pub use migrate_content_descriptors::*;
+pub use migrate_thumbnails::*;
+pub use reencode_thumbnails::*;
 use std::marker::PhantomData;
 use std::sync::Arc;
+pub use sync_thumbnail_presence::*;
 pub use vacuum::*;
+pub use verify_files::*;
 
 use crate::handle::JobHandle;
 use async_trait::async_trait;
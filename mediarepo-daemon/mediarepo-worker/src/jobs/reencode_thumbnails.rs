@@ -0,0 +1,94 @@
+use crate::jobs::{deserialize_state, serialize_state, Job};
+use crate::status_utils::SimpleProgress;
+use async_trait::async_trait;
+use mediarepo_core::error::RepoResult;
+use mediarepo_core::settings::{ThumbnailFormat, ThumbnailSettings};
+use mediarepo_database::entities::job_state::JobType;
+use mediarepo_logic::dao::job::JobDao;
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Re-encodes existing thumbnails when the thumbnail settings of the repo have changed
+#[derive(Clone, Default)]
+pub struct ReencodeThumbnailsJob {
+    progress: Arc<RwLock<SimpleProgress>>,
+    state: Arc<RwLock<ReencodeThumbnailsState>>,
+}
+
+#[async_trait]
+impl Job for ReencodeThumbnailsJob {
+    type JobStatus = SimpleProgress;
+    type Result = ();
+
+    fn status(&self) -> Arc<RwLock<Self::JobStatus>> {
+        self.progress.clone()
+    }
+
+    async fn load_state(&self, job_dao: JobDao) -> RepoResult<()> {
+        if let Some(state) = job_dao.state_for_job_type(JobType::ReencodeThumbs).await? {
+            let mut own_state = self.state.write().await;
+            *own_state = deserialize_state(state)?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn run(&self, repo: Arc<Repo>) -> RepoResult<()> {
+        let job_dao = repo.job();
+        let settings = job_dao.thumbnail_settings().clone();
+        let applied = self.state.read().await.applied.clone();
+
+        if applied == settings {
+            tracing::debug!("thumbnails are already encoded with the current settings");
+            return Ok(());
+        }
+        // thumbnails that already have the right format only need to be
+        // re-encoded if the quality of a lossy format has changed
+        let reencode_all = applied.format == settings.format
+            && applied.quality != settings.quality
+            && settings.format != ThumbnailFormat::Png;
+        let parents = job_dao.thumbnail_parents().await?;
+        self.progress.write().await.set_total(parents.len() as u64);
+        let mut count = 0;
+        let mut failed = 0;
+
+        for parent in parents {
+            match job_dao.reencode_thumbnails(&parent, reencode_all).await {
+                Ok(reencoded) => count += reencoded,
+                Err(e) => {
+                    tracing::warn!("failed to re-encode thumbnails of {}: {}", parent, e);
+                    failed += 1;
+                }
+            }
+            self.progress.write().await.tick();
+        }
+        tracing::info!("re-encoded {} thumbnails", count);
+
+        // the settings are only recorded as applied when all thumbnails have been
+        // re-encoded so that the failed ones are retried on the next run
+        if failed > 0 {
+            tracing::warn!("failed to re-encode the thumbnails of {} files", failed);
+        } else {
+            self.state.write().await.applied = settings;
+        }
+
+        Ok(())
+    }
+
+    async fn save_state(&self, job_dao: JobDao) -> RepoResult<()> {
+        let state = self.state.read().await;
+        job_dao
+            .upsert_state(serialize_state(JobType::ReencodeThumbs, &*state)?)
+            .await
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct ReencodeThumbnailsState {
+    /// The settings all existing thumbnails are encoded with
+    applied: ThumbnailSettings,
+}
@@ -0,0 +1,69 @@
+use crate::jobs::{deserialize_state, serialize_state, EmptyStatus, Job};
+use async_trait::async_trait;
+use mediarepo_core::error::RepoResult;
+use mediarepo_database::entities::job_state::JobType;
+use mediarepo_logic::dao::job::JobDao;
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Records the files that already have thumbnails in the thumbnail store
+/// in the database so they can be filtered by thumbnail presence.
+/// The sync only runs once per repository
+#[derive(Clone, Default)]
+pub struct SyncThumbnailPresenceJob {
+    synced: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Job for SyncThumbnailPresenceJob {
+    type JobStatus = ();
+    type Result = ();
+
+    fn status(&self) -> Arc<RwLock<Self::JobStatus>> {
+        EmptyStatus::default()
+    }
+
+    async fn load_state(&self, job_dao: JobDao) -> RepoResult<()> {
+        if let Some(state) = job_dao
+            .state_for_job_type(JobType::SyncThumbPresence)
+            .await?
+        {
+            let state = deserialize_state::<SyncStatus>(state)?;
+            self.synced.store(state.synced, Ordering::SeqCst);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn run(&self, repo: Arc<Repo>) -> RepoResult<()> {
+        if self.synced.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let marked = repo.job().sync_thumbnail_presence().await?;
+
+        if marked > 0 {
+            tracing::info!("recorded the thumbnails of {} files", marked);
+        }
+        self.synced.store(true, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    async fn save_state(&self, job_dao: JobDao) -> RepoResult<()> {
+        if self.synced.load(Ordering::Relaxed) {
+            let state = serialize_state(JobType::SyncThumbPresence, &SyncStatus { synced: true })?;
+            job_dao.upsert_state(state).await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SyncStatus {
+    pub synced: bool,
+}
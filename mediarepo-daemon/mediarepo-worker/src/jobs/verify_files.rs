@@ -0,0 +1,98 @@
+use crate::jobs::{deserialize_state, serialize_state, Job};
+use crate::status_utils::SimpleProgress;
+use async_trait::async_trait;
+use mediarepo_core::error::RepoResult;
+use mediarepo_database::entities::job_state::JobType;
+use mediarepo_logic::dao::job::JobDao;
+use mediarepo_logic::dao::repo::Repo;
+use mediarepo_logic::dao::DaoProvider;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+const VERIFY_BATCH_SIZE: u64 = 100;
+
+/// Re-hashes all stored files and records missing, damaged and stray files.
+/// The job can be interrupted and continues with the next unchecked file on the next run
+#[derive(Clone, Default)]
+pub struct VerifyFilesJob {
+    progress: Arc<RwLock<SimpleProgress>>,
+    state: Arc<RwLock<VerifyFilesState>>,
+}
+
+#[async_trait]
+impl Job for VerifyFilesJob {
+    type JobStatus = SimpleProgress;
+    type Result = ();
+
+    fn status(&self) -> Arc<RwLock<Self::JobStatus>> {
+        self.progress.clone()
+    }
+
+    async fn load_state(&self, job_dao: JobDao) -> RepoResult<()> {
+        if let Some(state) = job_dao.state_for_job_type(JobType::VerifyFiles).await? {
+            let mut own_state = self.state.write().await;
+            *own_state = deserialize_state(state)?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn run(&self, repo: Arc<Repo>) -> RepoResult<Self::Result> {
+        let job_dao = repo.job();
+
+        if self.state.read().await.last_cd_id == 0 {
+            repo.integrity().clear().await?;
+        }
+        let cd_count = repo.get_counts().await?.cd_count as u64;
+        {
+            let mut progress = self.progress.write().await;
+            // the stray file detection is counted as one additional step
+            progress.set_total(cd_count + 1);
+            progress.set_current(self.state.read().await.checked);
+        }
+
+        loop {
+            let last_cd_id = self.state.read().await.last_cd_id;
+            let checked_ids = job_dao
+                .verify_file_contents(last_cd_id, VERIFY_BATCH_SIZE)
+                .await?;
+
+            if let Some(last_id) = checked_ids.last() {
+                let mut state = self.state.write().await;
+                state.last_cd_id = *last_id;
+                state.checked += checked_ids.len() as u64;
+                self.progress.write().await.set_current(state.checked);
+                job_dao
+                    .upsert_state(serialize_state(JobType::VerifyFiles, &*state)?)
+                    .await?;
+            }
+            if (checked_ids.len() as u64) < VERIFY_BATCH_SIZE {
+                break;
+            }
+        }
+        let stray_count = job_dao.find_stray_files().await?;
+        tracing::info!("found {} stray files", stray_count);
+
+        *self.state.write().await = VerifyFilesState::default();
+        let mut progress = self.progress.write().await;
+        let total = progress.total;
+        progress.set_current(total);
+
+        Ok(())
+    }
+
+    async fn save_state(&self, job_dao: JobDao) -> RepoResult<()> {
+        let state = self.state.read().await;
+        job_dao
+            .upsert_state(serialize_state(JobType::VerifyFiles, &*state)?)
+            .await
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct VerifyFilesState {
+    last_cd_id: i64,
+    checked: u64,
+}
@@ -1,5 +1,8 @@
 use crate::job_dispatcher::JobDispatcher;
-use crate::jobs::{CheckIntegrityJob, MigrateCDsJob};
+use crate::jobs::{
+    CheckIntegrityJob, CompactThumbnailsJob, MigrateCDsJob, MigrateThumbnailsJob,
+    ReencodeThumbnailsJob, SyncThumbnailPresenceJob,
+};
 use mediarepo_core::error::RepoError;
 use mediarepo_core::tokio_graceful_shutdown::Toplevel;
 use mediarepo_logic::dao::repo::Repo;
@@ -25,7 +28,18 @@ pub async fn start(top_level: Toplevel, repo: Repo) -> (Toplevel, JobDispatcher)
                     Duration::from_secs(60 * 60 * 24),
                 )
                 .await;
+            dispatcher
+                .dispatch_periodically(
+                    CompactThumbnailsJob::default(),
+                    Duration::from_secs(60 * 60 * 24),
+                )
+                .await;
             dispatcher.dispatch(MigrateCDsJob::default()).await;
+            dispatcher.dispatch(MigrateThumbnailsJob::default()).await;
+            dispatcher.dispatch(ReencodeThumbnailsJob::default()).await;
+            dispatcher
+                .dispatch(SyncThumbnailPresenceJob::default())
+                .await;
 
             Ok(())
         });
@@ -10,7 +10,7 @@ use tokio::io::AsyncWriteExt;
 
 use mediarepo_core::error::RepoResult;
 use mediarepo_core::fs::drop_file::DropFile;
-use mediarepo_core::settings::{PathSettings, Settings};
+use mediarepo_core::settings::Settings;
 use mediarepo_core::tokio_graceful_shutdown::{SubsystemHandle, Toplevel};
 use mediarepo_core::trait_bound_typemap::{CloneSendSyncTypeMap, SendSyncTypeMap, TypeMap};
 use mediarepo_core::type_keys::{RepoPathKey, SettingsKey};
@@ -54,6 +54,7 @@ enum SubCommand {
 }
 
 #[tokio::main]
+#[allow(deprecated)] // human_panic still uses the deprecated PanicInfo type
 async fn main() -> RepoResult<()> {
     human_panic::setup_panic!();
     let mut opt: Opt = Opt::from_args();
@@ -97,15 +98,15 @@ async fn main() -> RepoResult<()> {
     }
 }
 
-async fn init_repo(opt: &Opt, paths: &PathSettings) -> RepoResult<Repo> {
-    let repo = get_repo(&opt.repo, paths).await?;
+async fn init_repo(opt: &Opt, settings: &Settings) -> RepoResult<Repo> {
+    let repo = get_repo(&opt.repo, settings).await?;
 
     Ok(repo)
 }
 
 /// Starts the server
 async fn start_server(opt: Opt, settings: Settings) -> RepoResult<()> {
-    let repo = init_repo(&opt, &settings.paths).await?;
+    let repo = init_repo(&opt, &settings).await?;
     let (mut top_level, dispatcher) = mediarepo_worker::start(Toplevel::new(), repo.clone()).await;
 
     let mut shared_data = CloneSendSyncTypeMap::new();
@@ -233,7 +234,7 @@ async fn init(opt: Opt, force: bool) -> RepoResult<()> {
         panic!("Database already exists in location. Use --force with init to delete everything and start a new repository");
     }
     log::debug!("Creating repo");
-    let _repo = get_repo(&opt.repo, &settings.paths).await?;
+    let _repo = get_repo(&opt.repo, &settings).await?;
 
     log::debug!("Writing settings");
     settings.save(&opt.repo)?;
@@ -3,6 +3,9 @@ use std::path::Path;
 use tokio::fs;
 
 use mediarepo_core::error::RepoResult;
+use mediarepo_core::fs::blob_store::create_blob_store;
+use mediarepo_core::fs::file_hash_store::FileHashStore;
+use mediarepo_core::fs::thumbnail_store::ThumbnailStore;
 use mediarepo_core::settings::v1::SettingsV1;
 use mediarepo_core::settings::{PathSettings, Settings};
 use mediarepo_logic::dao::repo::Repo;
@@ -21,14 +24,24 @@ pub fn load_settings(root_path: &Path) -> RepoResult<Settings> {
     }
 }
 
-pub async fn get_repo(root_path: &Path, path_settings: &PathSettings) -> RepoResult<Repo> {
+pub async fn get_repo(root_path: &Path, settings: &Settings) -> RepoResult<Repo> {
+    let path_settings = &settings.paths;
+    let files_dir = path_settings.files_dir(root_path);
+    let blob_store = create_blob_store(&settings.storage, files_dir.clone())?;
+    let thumbnail_store = ThumbnailStore::open(
+        path_settings.thumbs_dir(root_path),
+        settings.storage.thumbnails,
+        settings.thumbnails.clone(),
+    )
+    .await?;
+
     Repo::connect(
         format!(
             "sqlite://{}",
             path_settings.db_file_path(root_path).to_string_lossy()
         ),
-        path_settings.files_dir(root_path),
-        path_settings.thumbs_dir(root_path),
+        FileHashStore::new(files_dir, blob_store),
+        thumbnail_store,
     )
     .await
 }